@@ -0,0 +1,45 @@
+import type { AuthProfile, OAuthToken } from '~/types'
+import { invoke } from '@tauri-apps/api/core'
+
+// OAuth 2.0 auth profiles of the active workspace. Tokens are obtained,
+// cached and refreshed by the backend; the frontend only refers to profiles by id.
+export const useAuthProfiles = () => {
+  const profiles = useState<AuthProfile[]>('authProfiles', () => [])
+
+  const profileOptions = computed(() =>
+    profiles.value.map(p => ({ value: p.id, label: p.name || p.id }))
+  )
+
+  const loadProfiles = async () => {
+    try {
+      profiles.value = await invoke<AuthProfile[]>('get_auth_profiles')
+    } catch (e) {
+      console.error('Failed to load auth profiles:', e)
+    }
+  }
+
+  const saveProfiles = async (updated: AuthProfile[]) => {
+    await invoke('save_auth_profiles', { profiles: updated })
+    profiles.value = updated
+  }
+
+  // Runs the browser flow for interactive grants (see the `oauth-authorization` event)
+  const authorize = (profileId: string) =>
+    invoke<OAuthToken>('oauth_authorize', { profileId })
+
+  const getToken = (profileId: string) =>
+    invoke<OAuthToken | null>('get_oauth_token', { profileId })
+
+  const clearTokens = (profileId: string) =>
+    invoke('clear_oauth_tokens', { profileId })
+
+  return {
+    profiles,
+    profileOptions,
+    loadProfiles,
+    saveProfiles,
+    authorize,
+    getToken,
+    clearTokens,
+  }
+}
@@ -752,6 +752,20 @@ export const useVariableStore = () => {
     return newEnv
   }
 
+  // Add environments that came with an imported collection
+  const importEnvironments = async (imported: Environment[]) => {
+    environments.value = [...environments.value, ...imported]
+    
+    // Persist to database
+    for (const env of imported) {
+      try {
+        await invoke('save_environment', { environment: env })
+      } catch (e) {
+        console.error('Failed to save imported environment:', e)
+      }
+    }
+  }
+
   // ============ Environment Variable Actions ============
   const addEnvironmentVariable = async (envId: string, variable?: Partial<Variable>) => {
     console.log('[VariableStore] addEnvironmentVariable called:', { envId, variable })
@@ -1155,6 +1169,7 @@ export const useVariableStore = () => {
     deleteEnvironment,
     setActiveEnvironment,
     duplicateEnvironment,
+    importEnvironments,
     
     // Environment variable actions
     addEnvironmentVariable,
@@ -1,6 +1,6 @@
 import { type ClassValue, clsx } from 'clsx'
 import { twMerge } from 'tailwind-merge'
-import type { Collection, CollectionFolder, FolderSettings, AuthConfig, KeyValue, Variable } from '~/types'
+import type { Collection, CollectionFolder, FolderSettings, AuthConfig, KeyValue, RequestAuth, Variable } from '~/types'
 
 export function cn(...inputs: ClassValue[]) {
   return twMerge(clsx(inputs))
@@ -319,3 +319,74 @@ export function getAuthQueryParams(
     enabled: true
   }]
 }
+
+/**
+ * Auth the backend applies when sending (signatures computed over the final request)
+ */
+export function toRequestAuth(
+  auth: AuthConfig | undefined,
+  variableResolver: (value: string) => string
+): RequestAuth | null {
+  if (!auth || !auth.enabled) return null
+
+  switch (auth.type) {
+    case 'aws-sigv4': {
+      const config = auth.awsSigV4
+      if (!config) return null
+      return {
+        type: 'aws-sigv4',
+        accessKeyId: variableResolver(config.accessKeyId || ''),
+        secretAccessKey: variableResolver(config.secretAccessKey || ''),
+        sessionToken: config.sessionToken ? variableResolver(config.sessionToken) : undefined,
+        region: variableResolver(config.region || ''),
+        service: variableResolver(config.service || ''),
+        unsignedPayload: config.unsignedPayload ?? false,
+      }
+    }
+    case 'digest':
+      return {
+        type: 'digest',
+        username: variableResolver(auth.username || ''),
+        password: variableResolver(auth.password || ''),
+      }
+    case 'hawk': {
+      const config = auth.hawk
+      if (!config) return null
+      return {
+        type: 'hawk',
+        id: variableResolver(config.id || ''),
+        key: variableResolver(config.key || ''),
+        algorithm: config.algorithm ?? 'sha256',
+        ext: config.ext ? variableResolver(config.ext) : undefined,
+        app: config.app ? variableResolver(config.app) : undefined,
+        dlg: config.dlg ? variableResolver(config.dlg) : undefined,
+        includePayloadHash: config.includePayloadHash ?? false,
+      }
+    }
+    case 'jwt': {
+      const config = auth.jwt
+      if (!config) return null
+      return {
+        type: 'jwt',
+        algorithm: config.algorithm,
+        key: variableResolver(config.key || ''),
+        keyBase64: config.keyBase64 ?? false,
+        claims: parseJsonObject(variableResolver(config.claims || '{}')),
+        header: parseJsonObject(variableResolver(config.header || '{}')),
+        expiresIn: config.expiresIn,
+        prefix: config.prefix,
+      }
+    }
+  }
+
+  return null
+}
+
+function parseJsonObject(json: string): Record<string, any> {
+  try {
+    const value = JSON.parse(json)
+    return value && typeof value === 'object' && !Array.isArray(value) ? value : {}
+  } catch {
+    return {}
+  }
+}
@@ -1,5 +1,6 @@
 use axum::{
     extract::{Path, Query, State},
+    http::header,
     Json,
     response::IntoResponse,
 };
@@ -8,6 +9,8 @@ use serde_json::Value;
 use utoipa::ToSchema;
 use std::sync::Arc;
 
+use crate::openapi::{self, OpenApiVersion};
+use crate::postman;
 use crate::storage::{Storage, Collection};
 use super::{ApiError, PaginatedResponse, PaginationQuery, SuccessResponse};
 
@@ -206,6 +209,79 @@ pub async fn get_collection(
     Ok(Json(CollectionResponse::from(collection)))
 }
 
+/// Export a collection as a Postman Collection v2.1 document
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_id}/collections/{collection_id}/export/postman",
+    params(
+        ("workspace_id" = String, Path, description = "Workspace ID"),
+        ("collection_id" = String, Path, description = "Collection ID")
+    ),
+    responses(
+        (status = 200, description = "Postman Collection v2.1 JSON", body = Object),
+        (status = 404, description = "Collection not found", body = ApiError)
+    ),
+    tag = "Collections"
+)]
+pub async fn export_postman(
+    State(storage): State<Arc<Storage>>,
+    Path((workspace_id, collection_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let collection = storage.get_collection(&workspace_id, &collection_id)
+        .map_err(|e| ApiError::internal_error(e))?
+        .ok_or_else(|| ApiError::not_found("Collection not found"))?;
+
+    let collection = serde_json::to_value(&collection)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let document = postman::to_postman(&collection)
+        .map_err(ApiError::internal_error)?;
+
+    Ok(Json(document))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenApiExportQuery {
+    pub version: Option<String>,
+}
+
+/// Generate an OpenAPI YAML document from a collection, with response schemas
+/// inferred from the workspace history
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_id}/collections/{collection_id}/export/openapi",
+    params(
+        ("workspace_id" = String, Path, description = "Workspace ID"),
+        ("collection_id" = String, Path, description = "Collection ID"),
+        ("version" = Option<String>, Query, description = "OpenAPI version, 3.0 or 3.1 (default)")
+    ),
+    responses(
+        (status = 200, description = "OpenAPI YAML document", body = String, content_type = "application/yaml"),
+        (status = 400, description = "Unsupported OpenAPI version", body = ApiError),
+        (status = 404, description = "Collection not found", body = ApiError)
+    ),
+    tag = "Collections"
+)]
+pub async fn export_openapi(
+    State(storage): State<Arc<Storage>>,
+    Path((workspace_id, collection_id)): Path<(String, String)>,
+    Query(query): Query<OpenApiExportQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let version = OpenApiVersion::parse(query.version.as_deref())
+        .map_err(ApiError::bad_request)?;
+    let collection = storage.get_collection(&workspace_id, &collection_id)
+        .map_err(|e| ApiError::internal_error(e))?
+        .ok_or_else(|| ApiError::not_found("Collection not found"))?;
+    let history = storage.get_history(&workspace_id)
+        .map_err(|e| ApiError::internal_error(e))?;
+
+    let collection = serde_json::to_value(&collection)
+        .map_err(|e| ApiError::internal_error(e.to_string()))?;
+    let document = openapi::to_openapi(&collection, &history, version)
+        .map_err(ApiError::internal_error)?;
+
+    Ok(([(header::CONTENT_TYPE, "application/yaml")], document))
+}
+
 /// Update a collection
 #[utoipa::path(
     put,
@@ -0,0 +1,193 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use std::sync::Arc;
+
+use crate::storage::{Cookie, Storage};
+use super::{ApiError, SuccessResponse};
+
+// Request/Response types
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CookieResponse {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    pub path: String,
+    pub host_only: bool,
+    /// Unix timestamp in seconds (absent for session cookies)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<i64>,
+    pub secure: bool,
+    pub http_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>,
+}
+
+impl From<Cookie> for CookieResponse {
+    fn from(c: Cookie) -> Self {
+        CookieResponse {
+            name: c.name,
+            value: c.value,
+            domain: c.domain,
+            path: c.path,
+            host_only: c.host_only,
+            expires: c.expires,
+            secure: c.secure,
+            http_only: c.http_only,
+            same_site: c.same_site,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveCookieRequest {
+    pub name: String,
+    #[serde(default)]
+    pub value: String,
+    pub domain: String,
+    #[serde(default = "default_path")]
+    pub path: String,
+    #[serde(default)]
+    pub host_only: bool,
+    #[serde(default)]
+    pub expires: Option<i64>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    #[serde(default)]
+    pub same_site: Option<String>,
+}
+
+fn default_path() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CookieQuery {
+    pub domain: Option<String>,
+    pub name: Option<String>,
+    pub path: Option<String>,
+}
+
+/// List cookies stored in a workspace
+#[utoipa::path(
+    get,
+    path = "/api/workspaces/{workspace_id}/cookies",
+    params(
+        ("workspace_id" = String, Path, description = "Workspace ID"),
+        ("domain" = Option<String>, Query, description = "Only return cookies stored for this domain")
+    ),
+    responses(
+        (status = 200, description = "List of cookies", body = [CookieResponse])
+    ),
+    tag = "Cookies"
+)]
+pub async fn list_cookies(
+    State(storage): State<Arc<Storage>>,
+    Path(workspace_id): Path<String>,
+    Query(query): Query<CookieQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let cookies = storage.get_cookies(&workspace_id)
+        .map_err(|e| ApiError::internal_error(e))?;
+
+    let items: Vec<CookieResponse> = cookies
+        .into_iter()
+        .filter(|c| query.domain.as_ref().is_none_or(|d| &c.domain == d))
+        .map(CookieResponse::from)
+        .collect();
+
+    Ok(Json(items))
+}
+
+/// Create or replace a cookie (identified by name, domain and path)
+#[utoipa::path(
+    put,
+    path = "/api/workspaces/{workspace_id}/cookies",
+    params(
+        ("workspace_id" = String, Path, description = "Workspace ID")
+    ),
+    request_body = SaveCookieRequest,
+    responses(
+        (status = 200, description = "Cookie saved", body = CookieResponse),
+        (status = 400, description = "Invalid request", body = ApiError)
+    ),
+    tag = "Cookies"
+)]
+pub async fn save_cookie(
+    State(storage): State<Arc<Storage>>,
+    Path(workspace_id): Path<String>,
+    Json(req): Json<SaveCookieRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    if req.name.trim().is_empty() || req.domain.trim().is_empty() {
+        return Err(ApiError::bad_request("Cookie name and domain are required"));
+    }
+
+    let cookie = Cookie {
+        name: req.name,
+        value: req.value,
+        domain: req.domain.trim_start_matches('.').to_lowercase(),
+        path: req.path,
+        host_only: req.host_only,
+        expires: req.expires,
+        secure: req.secure,
+        http_only: req.http_only,
+        same_site: req.same_site,
+    };
+
+    storage.save_cookie(&workspace_id, &cookie)
+        .map_err(|e| ApiError::internal_error(e))?;
+
+    Ok(Json(CookieResponse::from(cookie)))
+}
+
+/// Delete one cookie, all cookies of a domain, or the whole cookie jar
+#[utoipa::path(
+    delete,
+    path = "/api/workspaces/{workspace_id}/cookies",
+    params(
+        ("workspace_id" = String, Path, description = "Workspace ID"),
+        ("domain" = Option<String>, Query, description = "Only delete cookies stored for this domain"),
+        ("name" = Option<String>, Query, description = "Delete a single cookie (requires domain)"),
+        ("path" = Option<String>, Query, description = "Path of the single cookie to delete (default: /)")
+    ),
+    responses(
+        (status = 200, description = "Cookies deleted", body = SuccessResponse),
+        (status = 400, description = "Invalid request", body = ApiError),
+        (status = 404, description = "Cookie not found", body = ApiError)
+    ),
+    tag = "Cookies"
+)]
+pub async fn delete_cookies(
+    State(storage): State<Arc<Storage>>,
+    Path(workspace_id): Path<String>,
+    Query(query): Query<CookieQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Some(name) = query.name else {
+        storage.clear_cookies(&workspace_id, query.domain.as_deref())
+            .map_err(|e| ApiError::internal_error(e))?;
+        return Ok(Json(SuccessResponse::with_message("Cookies cleared")));
+    };
+
+    let domain = query.domain
+        .ok_or_else(|| ApiError::bad_request("domain is required when deleting a single cookie"))?;
+    let path = query.path.unwrap_or_else(default_path);
+
+    let cookies = storage.get_cookies(&workspace_id)
+        .map_err(|e| ApiError::internal_error(e))?;
+
+    if !cookies.iter().any(|c| c.name == name && c.domain == domain && c.path == path) {
+        return Err(ApiError::not_found("Cookie not found"));
+    }
+
+    storage.delete_cookie(&workspace_id, &name, &domain, &path)
+        .map_err(|e| ApiError::internal_error(e))?;
+
+    Ok(Json(SuccessResponse::ok()))
+}
@@ -9,6 +9,7 @@ use utoipa::ToSchema;
 use std::sync::Arc;
 
 use crate::storage::{Storage, HistoryItem};
+use crate::timing::ResponseTiming;
 use super::{ApiError, PaginatedResponse, PaginationQuery, SuccessResponse};
 
 // Request/Response types
@@ -19,6 +20,9 @@ pub struct HistoryItemResponse {
     pub request: Value,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response: Option<Value>,
+    /// Phase timing breakdown (DNS, connect, TLS, TTFB, download)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<ResponseTiming>,
     pub timestamp: i64,
 }
 
@@ -28,6 +32,7 @@ impl From<HistoryItem> for HistoryItemResponse {
             id: h.id,
             request: h.request,
             response: h.response,
+            timing: h.timing,
             timestamp: h.timestamp,
         }
     }
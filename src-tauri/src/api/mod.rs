@@ -5,7 +5,9 @@ pub mod environments;
 pub mod variables;
 pub mod integrations;
 pub mod history;
+pub mod cookies;
 pub mod tests;
+pub mod requests;
 
 use axum::{
     http::StatusCode,
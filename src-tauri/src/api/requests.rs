@@ -0,0 +1,24 @@
+use axum::{extract::Path, Json};
+
+use crate::cancel;
+use super::{ApiError, SuccessResponse};
+
+/// Cancel an in-flight request or test run
+#[utoipa::path(
+    post,
+    path = "/api/requests/{request_id}/cancel",
+    params(
+        ("request_id" = String, Path, description = "Request or test run ID")
+    ),
+    responses(
+        (status = 200, description = "Cancelled", body = SuccessResponse),
+        (status = 404, description = "No request in flight with this ID", body = ApiError)
+    ),
+    tag = "Requests"
+)]
+pub async fn cancel_request(Path(request_id): Path<String>) -> Result<Json<SuccessResponse>, ApiError> {
+    if !cancel::cancel(&request_id) {
+        return Err(ApiError::not_found("No request in flight with this ID"));
+    }
+    Ok(Json(SuccessResponse::ok()))
+}
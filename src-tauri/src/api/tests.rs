@@ -1,6 +1,8 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    http::header,
     response::sse::{Event, Sse},
+    response::{IntoResponse, Response},
     Json,
 };
 use futures_util::Stream;
@@ -12,7 +14,20 @@ use std::sync::Arc;
 use std::time::Instant;
 use utoipa::ToSchema;
 
+use crate::auth::{self, RequestAuth};
+use crate::cancel;
+use crate::cookies::CookieJar;
+use crate::digest::DigestAuth;
+use crate::http_client::{self, HttpClientSettings};
+use crate::iteration_data::IterationSummary;
+use crate::oauth;
+use crate::redirect::RedirectOptions;
+use crate::report::{self, ReportFormat};
+use crate::retry::{RetryAttempt, RetryPolicy};
+use crate::run_plan::{self, Scheduler};
 use crate::storage::Storage;
+use crate::test_runner;
+use crate::timing::{ResponseTiming, TimingPhase};
 use crate::api::ApiError;
 
 // ============ Request/Response Types ============
@@ -26,17 +41,12 @@ pub enum AssertionType {
     Contains,
     ResponseTime,
     Header,
+    Timing,
+    JsonSchema,
+    BodySize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
-#[serde(rename_all = "snake_case")]
-pub enum JsonPathOperator {
-    Equals,
-    NotEquals,
-    Contains,
-    Exists,
-    NotExists,
-}
+pub use crate::test_runner::JsonPathOperator;
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
@@ -62,9 +72,14 @@ pub struct Assertion {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_time_ms: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing_phase: Option<TimingPhase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub header_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub header_value: Option<String>,
+    /// Inline schema for json_schema (the request's response schema when not set)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_schema: Option<Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -103,6 +118,24 @@ pub struct TestRequest {
     pub assertions: Option<Vec<Assertion>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extract_variables: Option<Vec<VariableExtraction>>,
+    /// Measure DNS/connect/TLS on a dedicated connection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detailed_timing: Option<bool>,
+    /// Redirect handling (follows up to 10 redirects by default)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirects: Option<RedirectOptions>,
+    /// Retry policy (overrides the one of the run)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+    /// Auth profile whose OAuth 2.0 token is sent (overrides the one of the run)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_profile_id: Option<String>,
+    /// Authentication applied when the request is sent (e.g. AWS Signature V4)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<RequestAuth>,
+    /// Response schema (e.g. from an OpenAPI import) for json_schema assertions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<Value>,
 }
 
 fn default_body_type() -> String {
@@ -121,6 +154,24 @@ pub struct RunTestsRequest {
     /// Initial variables to use in tests (e.g., from environment)
     #[serde(default)]
     pub variables: HashMap<String, String>,
+    /// Id to cancel the run with `POST /api/requests/{run_id}/cancel` (generated when omitted)
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// Retry policy for requests without their own
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    /// Auth profile for requests without their own
+    #[serde(default)]
+    pub auth_profile_id: Option<String>,
+    /// One full pass through the requests per row, seeded with its variables
+    #[serde(default)]
+    pub iteration_data: Vec<HashMap<String, String>>,
+    /// Requests running at once (1, the default, runs them in order); requests
+    /// using an extracted variable still wait for the one extracting it, and
+    /// nothing starts alongside a request that may set cookies (each request the
+    /// first time it runs, and those that set cookies)
+    #[serde(default)]
+    pub concurrency: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, PartialEq)]
@@ -142,6 +193,17 @@ pub struct AssertionResult {
     pub actual: String,
 }
 
+impl From<test_runner::AssertionResult> for AssertionResult {
+    fn from(result: test_runner::AssertionResult) -> Self {
+        AssertionResult {
+            name: result.name,
+            passed: result.passed,
+            expected: result.expected,
+            actual: result.actual,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExtractedVariable {
@@ -176,6 +238,14 @@ pub struct TestResult {
     pub assertions: Vec<AssertionResult>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub extracted_variables: Option<Vec<ExtractedVariable>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<ResponseTiming>,
+    /// Every attempt when a retry policy applies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<RetryAttempt>,
+    /// Iteration of a data-driven run (1-based)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iteration: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -189,6 +259,112 @@ pub struct TestRunSummary {
     pub errors: usize,
     pub total_time: u64,
     pub results: Vec<TestResult>,
+    /// The run was cancelled before all requests finished
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Per-iteration counts of a data-driven run
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub iterations: Vec<IterationSummary>,
+}
+
+// The Tauri runner's types, for the report exporters
+
+impl From<TestStatus> for test_runner::TestStatus {
+    fn from(status: TestStatus) -> Self {
+        match status {
+            TestStatus::Pending => test_runner::TestStatus::Pending,
+            TestStatus::Running => test_runner::TestStatus::Running,
+            TestStatus::Passed => test_runner::TestStatus::Passed,
+            TestStatus::Failed => test_runner::TestStatus::Failed,
+            TestStatus::Error => test_runner::TestStatus::Error,
+        }
+    }
+}
+
+impl From<TestResult> for test_runner::TestResult {
+    fn from(result: TestResult) -> Self {
+        test_runner::TestResult {
+            request_id: result.request_id,
+            request_name: result.request_name,
+            method: result.method,
+            url: result.url,
+            status: result.status.into(),
+            request_headers: None,
+            request_body: None,
+            response_status: result.response_status,
+            response_time: result.response_time,
+            response_size: result.response_size,
+            response_body: result.response_body,
+            response_headers: result.response_headers,
+            error: result.error,
+            assertions: result
+                .assertions
+                .into_iter()
+                .map(|a| test_runner::AssertionResult {
+                    name: a.name,
+                    passed: a.passed,
+                    expected: a.expected,
+                    actual: a.actual,
+                })
+                .collect(),
+            extracted_variables: result.extracted_variables.map(|vars| {
+                vars.into_iter()
+                    .map(|v| test_runner::ExtractedVariable {
+                        variable_name: v.variable_name,
+                        json_path: v.json_path,
+                        value: v.value,
+                        success: v.success,
+                        error: v.error,
+                    })
+                    .collect()
+            }),
+            timing: result.timing,
+            attempts: result.attempts,
+            iteration: result.iteration,
+        }
+    }
+}
+
+impl From<TestRunSummary> for test_runner::TestRunSummary {
+    fn from(summary: TestRunSummary) -> Self {
+        test_runner::TestRunSummary {
+            run_id: summary.run_id,
+            name: summary.name,
+            total: summary.total,
+            passed: summary.passed,
+            failed: summary.failed,
+            errors: summary.errors,
+            total_time: summary.total_time,
+            results: summary.results.into_iter().map(Into::into).collect(),
+            cancelled: summary.cancelled,
+            iterations: summary.iterations,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportQuery {
+    /// junit, tap or html; the JSON summary when not given
+    pub format: Option<String>,
+}
+
+fn report_format(query: &ReportQuery) -> Result<Option<ReportFormat>, ApiError> {
+    query
+        .format
+        .as_deref()
+        .map(ReportFormat::parse)
+        .transpose()
+        .map_err(ApiError::bad_request)
+}
+
+/// The JSON summary, or a report of it in `format`
+fn summary_response(summary: TestRunSummary, format: Option<ReportFormat>) -> Response {
+    let Some(format) = format else {
+        return Json(summary).into_response();
+    };
+    let started = chrono::Utc::now().timestamp_millis() - summary.total_time as i64;
+    let report = report::render(&summary.into(), format, started);
+    ([(header::CONTENT_TYPE, format.content_type())], report).into_response()
 }
 
 // ============ Helper Functions ============
@@ -265,8 +441,10 @@ fn parse_request_json(r: &Value) -> Option<ParsedRequest> {
                         expected_value: a.get("expectedValue").and_then(|v| v.as_str()).map(|s| s.to_string()),
                         search_string: a.get("searchString").and_then(|v| v.as_str()).map(|s| s.to_string()),
                         max_time_ms: a.get("maxTimeMs").and_then(|v| v.as_u64()),
+                        timing_phase: a.get("timingPhase").and_then(|v| serde_json::from_value(v.clone()).ok()),
                         header_name: a.get("headerName").and_then(|v| v.as_str()).map(|s| s.to_string()),
                         header_value: a.get("headerValue").and_then(|v| v.as_str()).map(|s| s.to_string()),
+                        json_schema: a.get("jsonSchema").cloned(),
                     })
                 }).collect()),
             extract_variables: extract_vars_array
@@ -278,10 +456,45 @@ fn parse_request_json(r: &Value) -> Option<ParsedRequest> {
                         enabled: e.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true),
                     })
                 }).collect()),
+            detailed_timing: r.get("detailedTiming").and_then(|v| v.as_bool()),
+            redirects: r.get("redirects").and_then(|v| serde_json::from_value(v.clone()).ok()),
+            retry: r.get("retry").and_then(|v| serde_json::from_value(v.clone()).ok()),
+            auth_profile_id: r.get("authProfileId").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            auth: r.get("auth").and_then(parse_auth),
+            response_schema: r.get("responseSchema").cloned(),
         },
     })
 }
 
+/// Backend-applied auth from a stored request's auth config
+/// (`{ type, enabled, username, password, awsSigV4, hawk, jwt }`)
+fn parse_auth(auth: &Value) -> Option<RequestAuth> {
+    if auth.get("enabled").and_then(|v| v.as_bool()) == Some(false) {
+        return None;
+    }
+    let field = |name: &str| auth.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    match auth.get("type")?.as_str()? {
+        "aws-sigv4" => serde_json::from_value(auth.get("awsSigV4")?.clone()).ok().map(RequestAuth::AwsSigV4),
+        "digest" => Some(RequestAuth::Digest(DigestAuth {
+            username: field("username"),
+            password: field("password"),
+        })),
+        "hawk" => serde_json::from_value(auth.get("hawk")?.clone()).ok().map(RequestAuth::Hawk),
+        "jwt" => {
+            // The UI stores claims and header as JSON text
+            let mut config = auth.get("jwt")?.clone();
+            for key in ["claims", "header"] {
+                if let Some(text) = config.get(key).and_then(|v| v.as_str()) {
+                    let parsed = serde_json::from_str::<Value>(text).ok().filter(|v| v.is_object());
+                    config[key] = parsed.unwrap_or_else(|| Value::Object(Default::default()));
+                }
+            }
+            serde_json::from_value(config).ok().map(RequestAuth::Jwt)
+        }
+        _ => None,
+    }
+}
+
 /// Parse requests from JSON including folders
 /// If folder_id is provided, only requests from that folder (and its subfolders) are included
 /// Requests are sorted by testOrder field (if present)
@@ -414,11 +627,14 @@ fn value_to_string(value: &serde_json::Value) -> String {
 fn evaluate_assertion(
     assertion: &Assertion,
     response_status: u16,
-    response_time_ms: u64,
+    timing: &ResponseTiming,
     response_body: &str,
+    body_size: usize,
     response_headers: &HashMap<String, String>,
-) -> AssertionResult {
-    match assertion.assertion_type {
+    response_schema: Option<&Value>,
+) -> Vec<AssertionResult> {
+    let response_time_ms = timing.total_ms.round() as u64;
+    let result = match assertion.assertion_type {
         AssertionType::Status => {
             let expected = assertion.expected_status.unwrap_or(200);
             let passed = response_status == expected;
@@ -446,26 +662,14 @@ fn evaluate_assertion(
             let expected_value = assertion.expected_value.as_deref().unwrap_or("");
             
             match evaluate_jsonpath(response_body, path) {
-                Ok(actual_value) => {
-                    let actual_str = value_to_string(&actual_value);
-                    let passed = match operator {
-                        JsonPathOperator::Equals => actual_str == expected_value,
-                        JsonPathOperator::NotEquals => actual_str != expected_value,
-                        JsonPathOperator::Contains => actual_str.contains(expected_value),
-                        JsonPathOperator::Exists => actual_value != serde_json::Value::Null,
-                        JsonPathOperator::NotExists => actual_value == serde_json::Value::Null,
-                    };
-                    AssertionResult {
-                        name: format!("JSONPath {} {:?}", path, operator),
-                        passed,
-                        expected: match operator {
-                            JsonPathOperator::Exists => "exists".to_string(),
-                            JsonPathOperator::NotExists => "not exists".to_string(),
-                            _ => expected_value.to_string(),
-                        },
-                        actual: actual_str,
-                    }
-                }
+                Ok(actual_value) => test_runner::operator_assertion(
+                    format!("JSONPath {} {:?}", path, operator),
+                    operator,
+                    &actual_value,
+                    expected_value,
+                    |actual| actual,
+                )
+                .into(),
                 Err(e) => AssertionResult {
                     name: format!("JSONPath {}", path),
                     passed: false,
@@ -484,48 +688,48 @@ fn evaluate_assertion(
                 actual: if passed { "found".to_string() } else { "not found".to_string() },
             }
         }
-        AssertionType::ResponseTime => {
+        AssertionType::ResponseTime => test_runner::response_time_result(
+            assertion.operator.as_ref(),
+            assertion.expected_value.as_deref(),
+            assertion.max_time_ms,
+            response_time_ms,
+        )
+        .into(),
+        AssertionType::Header => test_runner::header_result(
+            assertion.header_name.as_deref().unwrap_or(""),
+            assertion.header_value.as_deref(),
+            assertion.operator.as_ref(),
+            response_headers,
+        )
+        .into(),
+        AssertionType::BodySize => test_runner::body_size_result(
+            assertion.operator.as_ref(),
+            assertion.expected_value.as_deref(),
+            body_size,
+        )
+        .into(),
+        AssertionType::Timing => {
+            let phase = assertion.timing_phase.unwrap_or(TimingPhase::Total);
             let max_time = assertion.max_time_ms.unwrap_or(5000);
-            let passed = response_time_ms <= max_time;
+            let actual = timing.phase_ms(phase);
             AssertionResult {
-                name: format!("Response time < {}ms", max_time),
-                passed,
+                name: format!("{:?} time < {}ms", phase, max_time),
+                passed: actual.is_some_and(|ms| ms <= max_time as f64),
                 expected: format!("< {}ms", max_time),
-                actual: format!("{}ms", response_time_ms),
+                actual: actual
+                    .map(|ms| format!("{:.1}ms", ms))
+                    .unwrap_or_else(|| "not measured".to_string()),
             }
         }
-        AssertionType::Header => {
-            let header_name = assertion.header_name.as_deref().unwrap_or("");
-            let expected_value = assertion.header_value.as_deref();
-            
-            let actual_value = response_headers
-                .iter()
-                .find(|(k, _)| k.to_lowercase() == header_name.to_lowercase())
-                .map(|(_, v)| v.as_str());
-            
-            let (passed, expected_str, actual_str) = match (expected_value, actual_value) {
-                (Some(expected), Some(actual)) => {
-                    (expected == actual, expected.to_string(), actual.to_string())
-                }
-                (Some(expected), None) => {
-                    (false, expected.to_string(), "header not found".to_string())
-                }
-                (None, Some(actual)) => {
-                    (true, "exists".to_string(), actual.to_string())
-                }
-                (None, None) => {
-                    (false, "exists".to_string(), "header not found".to_string())
-                }
-            };
-            
-            AssertionResult {
-                name: format!("Header '{}'", header_name),
-                passed,
-                expected: expected_str,
-                actual: actual_str,
-            }
+        AssertionType::JsonSchema => {
+            let schema = assertion.json_schema.as_ref().or(response_schema);
+            return test_runner::evaluate_json_schema(schema, response_body)
+                .into_iter()
+                .map(AssertionResult::from)
+                .collect();
         }
-    }
+    };
+    vec![result]
 }
 
 fn extract_variables(
@@ -563,35 +767,46 @@ fn extract_variables(
         .collect()
 }
 
+/// Which earlier requests each request waits for, from the variables it uses
+fn request_dependencies(requests: &[TestRequest]) -> Vec<Vec<usize>> {
+    let texts: Vec<String> = requests
+        .iter()
+        .map(|request| {
+            // Everything substitute_variables is applied to
+            let mut text = format!("{} {}", request.url, request.body.as_deref().unwrap_or(""));
+            for kv in request.headers.iter().chain(&request.params) {
+                text.push_str(&format!(" {} {}", kv.key, kv.value));
+            }
+            if let Some(auth) = &request.auth {
+                text.push_str(&serde_json::to_string(auth).unwrap_or_default());
+            }
+            text
+        })
+        .collect();
+    let extracts: Vec<Vec<String>> = requests
+        .iter()
+        .map(|request| {
+            request
+                .extract_variables
+                .iter()
+                .flatten()
+                .filter(|e| e.enabled)
+                .map(|e| e.variable_name.clone())
+                .collect()
+        })
+        .collect();
+    run_plan::dependencies(&texts, &extracts)
+}
+
 async fn execute_single_request(
+    client: &reqwest::Client,
+    settings: &HttpClientSettings,
+    cookie_jar: &mut CookieJar,
     request: &TestRequest,
     run_context: &HashMap<String, String>,
+    retry: Option<&RetryPolicy>,
+    authorization: Result<Option<String>, String>,
 ) -> TestResult {
-    let client = match reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            return TestResult {
-                request_id: request.id.clone(),
-                request_name: request.name.clone(),
-                method: request.method.clone(),
-                url: request.url.clone(),
-                status: TestStatus::Error,
-                response_status: None,
-                response_time: None,
-                response_size: None,
-                response_body: None,
-                response_headers: None,
-                error: Some(format!("Failed to create HTTP client: {}", e)),
-                assertions: vec![],
-                extracted_variables: None,
-            };
-        }
-    };
-
     // Substitute variables in URL
     let mut request_url = substitute_variables(&request.url, run_context);
     
@@ -623,15 +838,10 @@ async fn execute_single_request(
     }
 
     // Build request
-    let mut req = match request.method.to_uppercase().as_str() {
-        "GET" => client.get(&request_url),
-        "POST" => client.post(&request_url),
-        "PUT" => client.put(&request_url),
-        "PATCH" => client.patch(&request_url),
-        "DELETE" => client.delete(&request_url),
-        "HEAD" => client.head(&request_url),
-        "OPTIONS" => client.request(reqwest::Method::OPTIONS, &request_url),
-        _ => {
+    let method = authorization.and_then(|a| http_client::parse_method(&request.method).map(|m| (m, a)));
+    let (mut req, authorization) = match method {
+        Ok((method, authorization)) => (client.request(method, &request_url), authorization),
+        Err(e) => {
             return TestResult {
                 request_id: request.id.clone(),
                 request_name: request.name.clone(),
@@ -643,15 +853,22 @@ async fn execute_single_request(
                 response_size: None,
                 response_body: None,
                 response_headers: None,
-                error: Some(format!("Unsupported method: {}", request.method)),
+                error: Some(e),
                 assertions: vec![],
                 extracted_variables: None,
+                timing: None,
+                attempts: Vec::new(),
+                iteration: None,
             };
         }
     };
 
     // Add headers
     for header in &request.headers {
+        // The auth profile's token replaces any Authorization header
+        if authorization.is_some() && header.key.eq_ignore_ascii_case("authorization") {
+            continue;
+        }
         if header.enabled && !header.key.is_empty() {
             let key = substitute_variables(&header.key, run_context);
             let value = substitute_variables(&header.value, run_context);
@@ -659,6 +876,13 @@ async fn execute_single_request(
         }
     }
 
+    if let Some(authorization) = &authorization {
+        req = req.header(reqwest::header::AUTHORIZATION, authorization);
+    }
+
+    // Test runs never wait longer than 30 seconds per request
+    req = req.timeout(std::time::Duration::from_secs(30));
+
     // Add body
     if let Some(body_content) = &request.body {
         if !body_content.is_empty() {
@@ -686,10 +910,25 @@ async fn execute_single_request(
         }
     }
 
+    // Connection phases (DNS, connect, TLS) can only be asserted on a dedicated connection
+    let detailed = request.detailed_timing.unwrap_or(false)
+        || request.assertions.iter().flatten().any(|a| {
+            a.enabled
+                && matches!(a.assertion_type, AssertionType::Timing)
+                && a.timing_phase.is_some_and(|p| p.needs_detailed_timing())
+        });
+
     // Execute request
     let start = Instant::now();
-    let response = match req.send().await {
-        Ok(r) => r,
+    let redirects = request.redirects.clone().unwrap_or_default();
+    let retry = request.retry.as_ref().or(retry);
+    let auth = request.auth.clone().map(|a| a.resolve(|s| substitute_variables(s, run_context)));
+    let (sent, attempts) = match req.build() {
+        Ok(built) => auth::send(settings, built, detailed, &redirects, cookie_jar, None, retry, auth.as_ref()).await,
+        Err(e) => (Err(e.to_string()), Vec::new()),
+    };
+    let response = match sent {
+        Ok((r, _)) => r,
         Err(e) => {
             return TestResult {
                 request_id: request.id.clone(),
@@ -702,39 +941,43 @@ async fn execute_single_request(
                 response_size: None,
                 response_body: None,
                 response_headers: None,
-                error: Some(e.to_string()),
+                error: Some(e),
                 assertions: vec![],
                 extracted_variables: None,
+                timing: None,
+                attempts,
+                iteration: None,
             };
         }
     };
 
-    let elapsed = start.elapsed().as_millis() as u64;
-    let status_code = response.status().as_u16();
+    let elapsed = response.timing.total_ms.round() as u64;
+    let status_code = response.status.as_u16();
     
     // Collect response headers
     let response_headers: HashMap<String, String> = response
-        .headers()
+        .headers
         .iter()
         .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
         .collect();
 
     // Get body
-    let body_bytes = response.bytes().await.unwrap_or_default();
-    let size = body_bytes.len();
-    let body_str = String::from_utf8_lossy(&body_bytes).to_string();
+    let size = response.body.len();
+    let body_str = String::from_utf8_lossy(&response.body).to_string();
 
     // Evaluate assertions
     let mut assertions = vec![];
     
     if let Some(configured_assertions) = &request.assertions {
         for assertion in configured_assertions.iter().filter(|a| a.enabled) {
-            assertions.push(evaluate_assertion(
+            assertions.extend(evaluate_assertion(
                 assertion,
                 status_code,
-                elapsed,
+                &response.timing,
                 &body_str,
+                size,
                 &response_headers,
+                request.response_schema.as_ref(),
             ));
         }
     }
@@ -783,6 +1026,9 @@ async fn execute_single_request(
         error: None,
         assertions,
         extracted_variables,
+        timing: Some(response.timing),
+        attempts,
+        iteration: None,
     }
 }
 
@@ -794,11 +1040,12 @@ async fn execute_single_request(
     path = "/api/workspaces/{workspace_id}/tests/run",
     tag = "Tests",
     params(
-        ("workspace_id" = String, Path, description = "Workspace ID")
+        ("workspace_id" = String, Path, description = "Workspace ID"),
+        ("format" = Option<String>, Query, description = "Report format instead of the JSON summary: junit, tap or html")
     ),
     request_body = RunTestsRequest,
     responses(
-        (status = 200, description = "Test run completed", body = TestRunSummary),
+        (status = 200, description = "Test run completed (a JUnit XML, TAP or HTML report with format)", body = TestRunSummary),
         (status = 400, description = "Invalid request", body = ApiError),
         (status = 404, description = "Workspace not found", body = ApiError),
         (status = 500, description = "Internal error", body = ApiError)
@@ -807,8 +1054,11 @@ async fn execute_single_request(
 pub async fn run_tests(
     Path(workspace_id): Path<String>,
     State(storage): State<Arc<Storage>>,
+    Query(query): Query<ReportQuery>,
     Json(request): Json<RunTestsRequest>,
-) -> Result<Json<TestRunSummary>, ApiError> {
+) -> Result<Response, ApiError> {
+    let format = report_format(&query)?;
+
     // Verify workspace exists
     let workspace = storage.get_workspace(&workspace_id)
         .map_err(|e| ApiError::internal_error(e))?;
@@ -821,58 +1071,115 @@ pub async fn run_tests(
         return Err(ApiError::bad_request("No requests to test"));
     }
 
-    let run_id = generate_id();
-    let total = request.requests.len();
+    let run_id = request.run_id.clone().unwrap_or_else(generate_id);
+    // Without iteration data the requests run once with the provided variables
+    let rows: Vec<Option<&HashMap<String, String>>> = if request.iteration_data.is_empty() {
+        vec![None]
+    } else {
+        request.iteration_data.iter().map(Some).collect()
+    };
+    let per_iteration = request.requests.len();
+    let total = per_iteration * rows.len();
+    let dependencies = request_dependencies(&request.requests);
+    let concurrency = request.concurrency.unwrap_or(1);
     let mut results: Vec<TestResult> = Vec::new();
+    let mut iterations: Vec<IterationSummary> = Vec::new();
     let mut passed = 0;
     let mut failed = 0;
     let mut errors = 0;
+    let mut completed = 0;
     let start_time = Instant::now();
-    
-    // Initialize run context with provided variables
-    let mut run_context: HashMap<String, String> = request.variables.clone();
-
-    for (index, test_request) in request.requests.iter().enumerate() {
-        // Execute the request
-        let result = execute_single_request(test_request, &run_context).await;
-
-        // Extract variables for subsequent requests
-        if let Some(extracted) = &result.extracted_variables {
-            for var in extracted {
-                if var.success {
-                    run_context.insert(var.variable_name.clone(), var.value.clone());
-                }
-            }
-        }
 
-        // Update counters
-        match result.status {
-            TestStatus::Passed => passed += 1,
-            TestStatus::Failed => failed += 1,
-            TestStatus::Error => errors += 1,
-            _ => {}
-        }
+    let settings = http_client::workspace_settings(&storage, Some(&workspace_id));
+    let client = http_client::get_client(&settings).map_err(ApiError::internal_error)?;
+    let mut cookie_jar = CookieJar::load(&storage, Some(&workspace_id));
+    let cancellation = cancel::Registration::new(&run_id);
+
+    let mut scheduler = Scheduler::new(dependencies, concurrency, request.delay_between_requests);
+
+    for (iteration_index, row) in rows.iter().enumerate() {
+        // Provided variables, overridden by the row, plus what the requests
+        // a request depends on extracted
+        let mut base_context: HashMap<String, String> = request.variables.clone();
+        base_context.extend(row.cloned().unwrap_or_default());
+        scheduler.begin(base_context);
+        let iteration = row.map(|_| iteration_index + 1);
+        let mut iteration_summary = row.map(|data| IterationSummary::new(iteration_index + 1, data.clone(), per_iteration));
+        let iteration_start = Instant::now();
+        let mut iteration_results: Vec<Option<TestResult>> = vec![None; per_iteration];
+
+        loop {
+            // Concurrent requests each use a fork of the jar, merged back when they finish
+            let next = scheduler.next(&cancellation, |index, context| {
+                let test_request = &request.requests[index];
+                let mut jar = cookie_jar.fork();
+                let (storage, client, settings, workspace_id) = (&*storage, &client, &settings, workspace_id.as_str());
+                let retry = request.retry.as_ref();
+                let profile_id = test_request.auth_profile_id.as_deref().or(request.auth_profile_id.as_deref());
+                async move {
+                    let authorization = oauth::authorization_for(storage, Some(workspace_id), profile_id).await;
+                    let result = execute_single_request(client, settings, &mut jar, test_request, &context, retry, authorization).await;
+                    (result, jar)
+                }
+            });
+            let Some((index, (mut result, jar))) = next.await else {
+                break;
+            };
+            let set_cookies = jar.changed();
+            cookie_jar.merge(jar);
+            result.iteration = iteration;
+            completed += 1;
+
+            // Variables extracted for the requests depending on this one
+            let extracted = result
+                .extracted_variables
+                .iter()
+                .flatten()
+                .filter(|var| var.success)
+                .map(|var| (var.variable_name.clone(), var.value.clone()))
+                .collect();
+            scheduler.finish(index, extracted, set_cookies);
 
-        let should_stop = request.stop_on_failure
-            && (result.status == TestStatus::Failed || result.status == TestStatus::Error);
+            // Update counters
+            match result.status {
+                TestStatus::Passed => passed += 1,
+                TestStatus::Failed => failed += 1,
+                TestStatus::Error => errors += 1,
+                _ => {}
+            }
+            if let Some(summary) = iteration_summary.as_mut() {
+                match result.status {
+                    TestStatus::Passed => summary.passed += 1,
+                    TestStatus::Failed => summary.failed += 1,
+                    TestStatus::Error => summary.errors += 1,
+                    _ => {}
+                }
+                summary.total_time = iteration_start.elapsed().as_millis() as u64;
+            }
 
-        results.push(result);
+            // A failed row doesn't stop the next ones
+            if request.stop_on_failure
+                && (result.status == TestStatus::Failed || result.status == TestStatus::Error)
+            {
+                scheduler.stop();
+            }
 
-        if should_stop {
-            break;
+            iteration_results[index] = Some(result);
         }
 
-        // Delay between requests
-        if request.delay_between_requests > 0 && index < total - 1 {
-            tokio::time::sleep(std::time::Duration::from_millis(
-                request.delay_between_requests,
-            ))
-            .await;
+        // Results in request order, whatever order they finished in
+        results.extend(iteration_results.into_iter().flatten());
+        iterations.extend(iteration_summary);
+        if cancellation.is_cancelled() {
+            break;
         }
     }
 
     let total_time = start_time.elapsed().as_millis() as u64;
 
+    cookie_jar.save(&storage, Some(&workspace_id))
+        .map_err(ApiError::internal_error)?;
+
     let summary = TestRunSummary {
         run_id,
         name: request.name,
@@ -882,9 +1189,11 @@ pub async fn run_tests(
         errors,
         total_time,
         results,
+        cancelled: cancellation.is_cancelled(),
+        iterations,
     };
 
-    Ok(Json(summary))
+    Ok(summary_response(summary, format))
 }
 
 /// Run tests for a specific collection by ID
@@ -894,11 +1203,12 @@ pub async fn run_tests(
     tag = "Tests",
     params(
         ("workspace_id" = String, Path, description = "Workspace ID"),
-        ("collection_id" = String, Path, description = "Collection ID")
+        ("collection_id" = String, Path, description = "Collection ID"),
+        ("format" = Option<String>, Query, description = "Report format instead of the JSON summary: junit, tap or html")
     ),
     request_body = RunCollectionTestsRequest,
     responses(
-        (status = 200, description = "Test run completed", body = TestRunSummary),
+        (status = 200, description = "Test run completed (a JUnit XML, TAP or HTML report with format)", body = TestRunSummary),
         (status = 404, description = "Collection not found", body = ApiError),
         (status = 500, description = "Internal error", body = ApiError)
     )
@@ -906,8 +1216,9 @@ pub async fn run_tests(
 pub async fn run_collection_tests(
     Path((workspace_id, collection_id)): Path<(String, String)>,
     State(storage): State<Arc<Storage>>,
+    Query(query): Query<ReportQuery>,
     Json(request): Json<RunCollectionTestsRequest>,
-) -> Result<Json<TestRunSummary>, ApiError> {
+) -> Result<Response, ApiError> {
     // Get collection from storage
     let collection = storage.get_collection(&workspace_id, &collection_id)
         .map_err(|e| ApiError::internal_error(e))?
@@ -960,9 +1271,14 @@ pub async fn run_collection_tests(
         stop_on_failure: request.stop_on_failure,
         delay_between_requests: request.delay_between_requests,
         variables: request.variables,
+        run_id: request.run_id,
+        retry: request.retry,
+        auth_profile_id: request.auth_profile_id,
+        iteration_data: request.iteration_data,
+        concurrency: request.concurrency,
     };
 
-    run_tests(Path(workspace_id), State(storage), Json(run_request)).await
+    run_tests(Path(workspace_id), State(storage), Query(query), Json(run_request)).await
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -977,6 +1293,56 @@ pub struct RunCollectionTestsRequest {
     /// Optional folder ID to filter tests to a specific folder
     #[serde(default)]
     pub folder_id: Option<String>,
+    /// Id to cancel the run with `POST /api/requests/{run_id}/cancel` (generated when omitted)
+    #[serde(default)]
+    pub run_id: Option<String>,
+    /// Retry policy for requests without their own
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    /// Auth profile for requests without their own
+    #[serde(default)]
+    pub auth_profile_id: Option<String>,
+    /// One full pass through the collection per row, seeded with its variables
+    #[serde(default)]
+    pub iteration_data: Vec<HashMap<String, String>>,
+    /// Requests running at once (1, the default, runs them in order); requests
+    /// using an extracted variable still wait for the one extracting it, and
+    /// nothing starts alongside a request that may set cookies (each request the
+    /// first time it runs, and those that set cookies)
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+}
+
+/// Report for a run in the test run history
+#[utoipa::path(
+    get,
+    path = "/api/test-runs/{run_id}/report",
+    tag = "Tests",
+    params(
+        ("run_id" = String, Path, description = "Test run history ID"),
+        ("format" = Option<String>, Query, description = "junit (default), tap or html")
+    ),
+    responses(
+        (status = 200, description = "JUnit XML, TAP or HTML report", body = String),
+        (status = 400, description = "Unsupported format", body = ApiError),
+        (status = 404, description = "Test run not found", body = ApiError)
+    )
+)]
+pub async fn test_run_report(
+    Path(run_id): Path<String>,
+    State(storage): State<Arc<Storage>>,
+    Query(query): Query<ReportQuery>,
+) -> Result<Response, ApiError> {
+    let format = report_format(&query)?.unwrap_or(ReportFormat::Junit);
+    let run = storage.get_test_runs()
+        .map_err(ApiError::internal_error)?
+        .into_iter()
+        .find(|run| run.id == run_id)
+        .ok_or_else(|| ApiError::not_found("Test run not found"))?;
+    let report = report::history_report(&run, format)
+        .map_err(ApiError::internal_error)?;
+
+    Ok(([(header::CONTENT_TYPE, format.content_type())], report).into_response())
 }
 
 // ============ SSE Streaming Types ============
@@ -1060,12 +1426,21 @@ pub async fn run_collection_tests_stream(
         collection_name.clone()
     };
 
-    let run_id = generate_id();
-    let total = test_requests.len();
+    let run_id = request.run_id.clone().unwrap_or_else(generate_id);
+    let iteration_data = request.iteration_data.clone();
+    let per_iteration = test_requests.len();
+    let total = per_iteration * iteration_data.len().max(1);
+    let dependencies = request_dependencies(&test_requests);
+    let concurrency = request.concurrency.unwrap_or(1);
     let name = test_name;
     let stop_on_failure = request.stop_on_failure;
     let delay = request.delay_between_requests;
     let variables = request.variables.clone();
+    let retry = request.retry.clone();
+    let auth_profile_id = request.auth_profile_id.clone();
+    let settings = http_client::workspace_settings(&storage, Some(&workspace_id));
+    let client = http_client::get_client(&settings).map_err(ApiError::internal_error)?;
+    let mut cookie_jar = CookieJar::load(&storage, Some(&workspace_id));
 
     // Create async stream
     let stream = async_stream::stream! {
@@ -1076,58 +1451,113 @@ pub async fn run_collection_tests_stream(
         };
         yield Ok(Event::default().data(serde_json::to_string(&start_event).unwrap()));
 
+        // Without iteration data the requests run once with the provided variables
+        let rows: Vec<Option<&HashMap<String, String>>> = if iteration_data.is_empty() {
+            vec![None]
+        } else {
+            iteration_data.iter().map(Some).collect()
+        };
         let mut results: Vec<TestResult> = Vec::new();
+        let mut iterations: Vec<IterationSummary> = Vec::new();
         let mut passed = 0;
         let mut failed = 0;
         let mut errors = 0;
+        let mut completed = 0;
         let start_time = Instant::now();
-        let mut run_context: HashMap<String, String> = variables;
-
-        for (index, test_request) in test_requests.iter().enumerate() {
-            let result = execute_single_request(test_request, &run_context).await;
-
-            // Extract variables for subsequent requests
-            if let Some(extracted) = &result.extracted_variables {
-                for var in extracted {
-                    if var.success {
-                        run_context.insert(var.variable_name.clone(), var.value.clone());
+        let cancellation = cancel::Registration::new(&run_id);
+
+        let mut scheduler = Scheduler::new(dependencies, concurrency, delay);
+
+        for (iteration_index, row) in rows.iter().enumerate() {
+            let mut base_context: HashMap<String, String> = variables.clone();
+            base_context.extend(row.cloned().unwrap_or_default());
+            scheduler.begin(base_context);
+            let iteration = row.map(|_| iteration_index + 1);
+            let mut iteration_summary = row.map(|data| IterationSummary::new(iteration_index + 1, data.clone(), per_iteration));
+            let iteration_start = Instant::now();
+            let mut iteration_results: Vec<Option<TestResult>> = vec![None; per_iteration];
+
+            loop {
+                // Concurrent requests each use a fork of the jar, merged back when they finish
+                let next = scheduler.next(&cancellation, |index, context| {
+                    let test_request = &test_requests[index];
+                    let mut jar = cookie_jar.fork();
+                    let (storage, client, settings, workspace_id) = (&*storage, &client, &settings, workspace_id.as_str());
+                    let retry = retry.as_ref();
+                    let profile_id = test_request.auth_profile_id.as_deref().or(auth_profile_id.as_deref());
+                    async move {
+                        let authorization = oauth::authorization_for(storage, Some(workspace_id), profile_id).await;
+                        let result = execute_single_request(client, settings, &mut jar, test_request, &context, retry, authorization).await;
+                        (result, jar)
                     }
+                });
+                let Some((index, (mut result, jar))) = next.await else {
+                    break;
+                };
+                let set_cookies = jar.changed();
+                cookie_jar.merge(jar);
+                result.iteration = iteration;
+                completed += 1;
+
+                // Variables extracted for the requests depending on this one
+                let extracted = result
+                    .extracted_variables
+                    .iter()
+                    .flatten()
+                    .filter(|var| var.success)
+                    .map(|var| (var.variable_name.clone(), var.value.clone()))
+                    .collect();
+                scheduler.finish(index, extracted, set_cookies);
+
+                // Update counters
+                match result.status {
+                    TestStatus::Passed => passed += 1,
+                    TestStatus::Failed => failed += 1,
+                    TestStatus::Error => errors += 1,
+                    _ => {}
+                }
+                if let Some(summary) = iteration_summary.as_mut() {
+                    match result.status {
+                        TestStatus::Passed => summary.passed += 1,
+                        TestStatus::Failed => summary.failed += 1,
+                        TestStatus::Error => summary.errors += 1,
+                        _ => {}
+                    }
+                    summary.total_time = iteration_start.elapsed().as_millis() as u64;
                 }
-            }
-
-            // Update counters
-            match result.status {
-                TestStatus::Passed => passed += 1,
-                TestStatus::Failed => failed += 1,
-                TestStatus::Error => errors += 1,
-                _ => {}
-            }
-
-            let should_stop = stop_on_failure
-                && (result.status == TestStatus::Failed || result.status == TestStatus::Error);
 
-            // Send progress event
-            let progress_event = TestEvent::Progress {
-                index: index + 1,
-                total,
-                result: result.clone(),
-            };
-            yield Ok(Event::default().data(serde_json::to_string(&progress_event).unwrap()));
+                // A failed row doesn't stop the next ones
+                if stop_on_failure
+                    && (result.status == TestStatus::Failed || result.status == TestStatus::Error)
+                {
+                    scheduler.stop();
+                }
 
-            results.push(result);
+                // Send progress event (in the order requests finish)
+                let progress_event = TestEvent::Progress {
+                    index: completed,
+                    total,
+                    result: result.clone(),
+                };
+                yield Ok(Event::default().data(serde_json::to_string(&progress_event).unwrap()));
 
-            if should_stop {
-                break;
+                iteration_results[index] = Some(result);
             }
 
-            // Delay between requests
-            if delay > 0 && index < total - 1 {
-                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+            // Results in request order, whatever order they finished in
+            results.extend(iteration_results.into_iter().flatten());
+            iterations.extend(iteration_summary);
+            if cancellation.is_cancelled() {
+                break;
             }
         }
 
         let total_time = start_time.elapsed().as_millis() as u64;
 
+        if let Err(e) = cookie_jar.save(&storage, Some(&workspace_id)) {
+            eprintln!("Failed to save cookies: {}", e);
+        }
+
         let summary = TestRunSummary {
             run_id,
             name,
@@ -1137,6 +1567,8 @@ pub async fn run_collection_tests_stream(
             errors,
             total_time,
             results,
+            cancelled: cancellation.is_cancelled(),
+            iterations,
         };
 
         let complete_event = TestEvent::Complete { summary };
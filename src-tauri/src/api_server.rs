@@ -15,10 +15,21 @@ use crate::api::{
     variables::{self, VariableResponse, CreateVariableRequest, UpdateVariableRequest},
     integrations::{self, IntegrationResponse, CreateIntegrationRequest, UpdateIntegrationRequest, TestIntegrationRequest, TestIntegrationResponse, FetchSecretRequest, FetchSecretResponse},
     history::{self, HistoryItemResponse},
+    cookies::{self, CookieResponse, SaveCookieRequest},
+    requests,
     tests::{self, RunTestsRequest, RunCollectionTestsRequest, TestRunSummary, TestResult, TestRequest, Assertion, AssertionResult, KeyValue as TestKeyValue, TestStatus, AssertionType, JsonPathOperator, VariableExtraction, ExtractedVariable},
     ApiError, ErrorDetail, SuccessResponse, PaginatedResponseSchema,
 };
+use crate::auth::RequestAuth;
+use crate::digest::DigestAuth;
+use crate::hawk::{HawkAlgorithm, HawkAuth};
+use crate::jwt::{JwtAlgorithm, JwtAuth};
+use crate::sigv4::AwsSigV4;
 use crate::storage::Storage;
+use crate::redirect::RedirectOptions;
+use crate::iteration_data::IterationSummary;
+use crate::retry::{BackoffStrategy, RetryAttempt, RetryPolicy};
+use crate::timing::{ResponseTiming, TimingPhase};
 
 const API_PORT: u16 = 47835; // ISTEK in phone keypad: I=4, S=7, T=8, E=3, K=5
 
@@ -38,6 +49,8 @@ const API_PORT: u16 = 47835; // ISTEK in phone keypad: I=4, S=7, T=8, E=3, K=5
         collections::list_collections,
         collections::create_collection,
         collections::get_collection,
+        collections::export_postman,
+        collections::export_openapi,
         collections::update_collection,
         collections::delete_collection,
         collections::add_request,
@@ -70,9 +83,16 @@ const API_PORT: u16 = 47835; // ISTEK in phone keypad: I=4, S=7, T=8, E=3, K=5
         history::get_history_item,
         history::delete_history_item,
         history::clear_history,
+        // Cookies
+        cookies::list_cookies,
+        cookies::save_cookie,
+        cookies::delete_cookies,
         // Tests
         tests::run_tests,
         tests::run_collection_tests,
+        tests::test_run_report,
+        // Requests
+        requests::cancel_request,
     ),
     components(
         schemas(
@@ -112,10 +132,14 @@ const API_PORT: u16 = 47835; // ISTEK in phone keypad: I=4, S=7, T=8, E=3, K=5
             FetchSecretResponse,
             // History
             HistoryItemResponse,
+            // Cookies
+            CookieResponse,
+            SaveCookieRequest,
             // Tests
             RunTestsRequest,
             RunCollectionTestsRequest,
             TestRunSummary,
+            IterationSummary,
             TestResult,
             TestRequest,
             Assertion,
@@ -126,6 +150,19 @@ const API_PORT: u16 = 47835; // ISTEK in phone keypad: I=4, S=7, T=8, E=3, K=5
             JsonPathOperator,
             VariableExtraction,
             ExtractedVariable,
+            ResponseTiming,
+            TimingPhase,
+            RedirectOptions,
+            RetryPolicy,
+            BackoffStrategy,
+            RetryAttempt,
+            RequestAuth,
+            AwsSigV4,
+            DigestAuth,
+            HawkAuth,
+            HawkAlgorithm,
+            JwtAuth,
+            JwtAlgorithm,
         )
     ),
     tags(
@@ -136,7 +173,9 @@ const API_PORT: u16 = 47835; // ISTEK in phone keypad: I=4, S=7, T=8, E=3, K=5
         (name = "Variables", description = "Global variable management"),
         (name = "Integrations", description = "Secret provider integrations"),
         (name = "History", description = "Request history"),
-        (name = "Tests", description = "Test runner endpoints")
+        (name = "Cookies", description = "Workspace cookie jar"),
+        (name = "Tests", description = "Test runner endpoints"),
+        (name = "Requests", description = "In-flight request control")
     ),
     info(
         title = "Istek API",
@@ -164,6 +203,8 @@ pub fn create_router(storage: Arc<Storage>) -> Router {
         // Collections
         .route("/api/workspaces/:workspace_id/collections", get(collections::list_collections).post(collections::create_collection))
         .route("/api/workspaces/:workspace_id/collections/:collection_id", get(collections::get_collection).put(collections::update_collection).delete(collections::delete_collection))
+        .route("/api/workspaces/:workspace_id/collections/:collection_id/export/postman", get(collections::export_postman))
+        .route("/api/workspaces/:workspace_id/collections/:collection_id/export/openapi", get(collections::export_openapi))
         .route("/api/workspaces/:workspace_id/collections/:collection_id/requests", post(collections::add_request))
         .route("/api/workspaces/:workspace_id/collections/:collection_id/requests/:request_id", put(collections::update_request).delete(collections::delete_request))
         // Environments
@@ -183,10 +224,15 @@ pub fn create_router(storage: Arc<Storage>) -> Router {
         // History
         .route("/api/workspaces/:workspace_id/history", get(history::list_history).delete(history::clear_history))
         .route("/api/workspaces/:workspace_id/history/:history_id", get(history::get_history_item).delete(history::delete_history_item))
+        // Cookies
+        .route("/api/workspaces/:workspace_id/cookies", get(cookies::list_cookies).put(cookies::save_cookie).delete(cookies::delete_cookies))
         // Tests
         .route("/api/workspaces/:workspace_id/tests/run", post(tests::run_tests))
         .route("/api/workspaces/:workspace_id/collections/:collection_id/tests/run", post(tests::run_collection_tests))
         .route("/api/workspaces/:workspace_id/collections/:collection_id/tests/stream", post(tests::run_collection_tests_stream))
+        .route("/api/test-runs/:run_id/report", get(tests::test_run_report))
+        // Requests
+        .route("/api/requests/:request_id/cancel", post(requests::cancel_request))
         // Swagger UI (also serves /api/openapi.json)
         .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", ApiDoc::openapi()))
         // State and middleware
@@ -0,0 +1,294 @@
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use utoipa::ToSchema;
+
+use crate::cookies::CookieJar;
+use crate::digest::{self, DigestAuth};
+use crate::hawk::{self, HawkAuth};
+use crate::http_client::HttpClientSettings;
+use crate::jwt::{self, JwtAuth};
+use crate::redirect::{self, RedirectHop, RedirectOptions};
+use crate::retry::{self, RetryAttempt, RetryPolicy};
+use crate::sigv4::{self, AwsSigV4};
+use crate::timing::TimedResponse;
+
+/// Authentication applied by the backend when a request is sent, for schemes
+/// that depend on the final request (signatures, challenges)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum RequestAuth {
+    /// AWS Signature Version 4
+    #[serde(rename = "aws-sigv4")]
+    AwsSigV4(AwsSigV4),
+    /// HTTP Digest (answers the server's challenge)
+    Digest(DigestAuth),
+    Hawk(HawkAuth),
+    /// A JWT bearer token signed for every request
+    Jwt(JwtAuth),
+}
+
+impl RequestAuth {
+    /// Replace `{{variables}}` in every credential field
+    pub fn resolve(self, resolve: impl Fn(&str) -> String) -> Self {
+        let optional = |value: Option<String>| value.as_deref().map(&resolve);
+        match self {
+            RequestAuth::AwsSigV4(credentials) => RequestAuth::AwsSigV4(AwsSigV4 {
+                access_key_id: resolve(&credentials.access_key_id),
+                secret_access_key: resolve(&credentials.secret_access_key),
+                session_token: optional(credentials.session_token),
+                region: resolve(&credentials.region),
+                service: resolve(&credentials.service),
+                unsigned_payload: credentials.unsigned_payload,
+            }),
+            RequestAuth::Digest(credentials) => RequestAuth::Digest(DigestAuth {
+                username: resolve(&credentials.username),
+                password: resolve(&credentials.password),
+            }),
+            RequestAuth::Hawk(credentials) => RequestAuth::Hawk(HawkAuth {
+                id: resolve(&credentials.id),
+                key: resolve(&credentials.key),
+                ext: optional(credentials.ext),
+                app: optional(credentials.app),
+                dlg: optional(credentials.dlg),
+                ..credentials
+            }),
+            RequestAuth::Jwt(auth) => RequestAuth::Jwt(JwtAuth {
+                key: resolve(&auth.key),
+                claims: auth.claims.into_iter().map(|(k, v)| (k, resolve_json(v, &resolve))).collect(),
+                header: auth.header.into_iter().map(|(k, v)| (k, resolve_json(v, &resolve))).collect(),
+                ..auth
+            }),
+        }
+    }
+
+    /// Sign or otherwise authenticate a built request right before it is sent
+    pub fn apply(&self, request: &mut reqwest::Request) -> Result<(), String> {
+        let now = chrono::Utc::now();
+        match self {
+            RequestAuth::AwsSigV4(credentials) => sigv4::sign(request, credentials, now),
+            // Digest credentials can only be sent in answer to a challenge
+            RequestAuth::Digest(_) => Ok(()),
+            RequestAuth::Hawk(credentials) => hawk::sign(request, credentials, now.timestamp(), &hawk::nonce()),
+            RequestAuth::Jwt(auth) => {
+                let token = jwt::token(auth, now.timestamp())?;
+                let value = match auth.prefix.trim() {
+                    "" => token,
+                    prefix => format!("{} {}", prefix, token),
+                };
+                let value = HeaderValue::from_str(&value).map_err(|_| "Invalid JWT header value".to_string())?;
+                request.headers_mut().insert(header::AUTHORIZATION, value);
+                Ok(())
+            }
+        }
+    }
+
+    fn answers_challenges(&self) -> bool {
+        matches!(self, RequestAuth::Digest(_))
+    }
+
+    /// The request to resend when `status` and `headers` carry a challenge this
+    /// auth answers (`request` is a copy of the request before it was sent)
+    fn answer(
+        &self,
+        mut request: reqwest::Request,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> Result<Option<reqwest::Request>, String> {
+        let RequestAuth::Digest(credentials) = self else {
+            return Ok(None);
+        };
+        if status != StatusCode::UNAUTHORIZED {
+            return Ok(None);
+        }
+
+        let uri = match request.url().query() {
+            Some(query) => format!("{}?{}", request.url().path(), query),
+            None => request.url().path().to_string(),
+        };
+        let body = request.body().and_then(|b| b.as_bytes());
+        let Some(authorization) = digest::answer(credentials, headers, request.method().as_str(), &uri, body)? else {
+            return Ok(None);
+        };
+        let value = HeaderValue::from_str(&authorization).map_err(|_| "Invalid Digest header value".to_string())?;
+        request.headers_mut().insert(header::AUTHORIZATION, value);
+        Ok(Some(request))
+    }
+}
+
+fn resolve_json(value: Value, resolve: &impl Fn(&str) -> String) -> Value {
+    match value {
+        Value::String(s) => Value::String(resolve(&s)),
+        Value::Array(items) => Value::Array(items.into_iter().map(|v| resolve_json(v, resolve)).collect()),
+        Value::Object(map) => Value::Object(map.into_iter().map(|(k, v)| (k, resolve_json(v, resolve))).collect()),
+        other => other,
+    }
+}
+
+/// Apply an optional auth to a built request
+pub fn apply(request: &mut reqwest::Request, auth: Option<&RequestAuth>) -> Result<(), String> {
+    match auth {
+        Some(auth) => auth.apply(request),
+        None => Ok(()),
+    }
+}
+
+/// Send a request like `retry::send`, applying `auth` to every attempt and same-origin
+/// redirect hop and answering a Digest challenge by resending the request once with
+/// credentials (to the URL the challenge came from, after any redirects)
+#[allow(clippy::too_many_arguments)]
+pub async fn send(
+    settings: &HttpClientSettings,
+    request: reqwest::Request,
+    detailed: bool,
+    redirects: &RedirectOptions,
+    cookie_jar: &mut CookieJar,
+    save_to: Option<&Path>,
+    policy: Option<&RetryPolicy>,
+    auth: Option<&RequestAuth>,
+) -> (Result<(TimedResponse, Vec<RedirectHop>), String>, Vec<RetryAttempt>) {
+    let sign = |request: &mut reqwest::Request| apply(request, auth);
+    let unanswered = auth.filter(|a| a.answers_challenges()).and_then(|_| request.try_clone());
+
+    let (sent, mut attempts) =
+        retry::send(settings, request, detailed, redirects, cookie_jar, save_to, policy, sign).await;
+    let (Some(auth), Some(unanswered), Ok((response, hops))) = (auth, unanswered, &sent) else {
+        return (sent, attempts);
+    };
+    // The challenge came from the last hop; credentials only go to the origin that was requested
+    let origin = unanswered.url().origin();
+    let Some(unanswered) =
+        redirect::final_request(unanswered, hops, redirects).filter(|r| r.url().origin() == origin)
+    else {
+        return (sent, attempts);
+    };
+    match auth.answer(unanswered, response.status, &response.headers) {
+        Ok(Some(answered)) => {
+            let (sent, retried) =
+                retry::send(settings, answered, detailed, redirects, cookie_jar, save_to, policy, sign).await;
+            let offset = attempts.len() as u32;
+            attempts.extend(retried.into_iter().map(|a| RetryAttempt {
+                attempt: a.attempt + offset,
+                ..a
+            }));
+            (sent, attempts)
+        }
+        Ok(None) => (sent, attempts),
+        Err(e) => (Err(e), attempts),
+    }
+}
+
+/// Send a request with a plain client (GraphQL, SSE), applying `auth` and
+/// answering a Digest challenge
+pub async fn execute(
+    client: &reqwest::Client,
+    request: reqwest::Request,
+    auth: Option<&RequestAuth>,
+) -> Result<reqwest::Response, String> {
+    let mut request = request;
+    apply(&mut request, auth)?;
+    let unanswered = auth.filter(|a| a.answers_challenges()).and_then(|_| request.try_clone());
+
+    let response = client.execute(request).await.map_err(|e| e.to_string())?;
+    // The client follows redirects itself, so answer at the URL the challenge came from
+    let unanswered = unanswered.filter(|r| r.url().origin() == response.url().origin()).map(|mut r| {
+        *r.url_mut() = response.url().clone();
+        r
+    });
+    if let (Some(auth), Some(unanswered)) = (auth, unanswered) {
+        if let Some(answered) = auth.answer(unanswered, response.status(), response.headers())? {
+            return client.execute(answered).await.map_err(|e| e.to_string());
+        }
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_frontend_type_names() {
+        let auth: RequestAuth = serde_json::from_value(serde_json::json!({
+            "type": "aws-sigv4",
+            "accessKeyId": "AKID",
+            "secretAccessKey": "secret",
+            "region": "eu-west-1",
+            "service": "execute-api"
+        }))
+        .unwrap();
+        assert!(matches!(auth, RequestAuth::AwsSigV4(ref c) if c.region == "eu-west-1"));
+
+        let auth: RequestAuth = serde_json::from_value(serde_json::json!({
+            "type": "jwt",
+            "algorithm": "RS256",
+            "key": "{{privateKey}}",
+            "claims": { "sub": "{{user}}" }
+        }))
+        .unwrap();
+        let RequestAuth::Jwt(jwt) = auth.resolve(|s| s.replace("{{user}}", "alice")) else {
+            panic!("expected a JWT auth");
+        };
+        assert_eq!(jwt.claims["sub"], "alice");
+        assert_eq!(jwt.prefix, "Bearer");
+    }
+
+    /// The `Authorization` headers of a GET to `/start` that is redirected to `/next`
+    async fn authorize_redirected(auth: &RequestAuth) -> (String, String, String) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Answers /start with a 307 and /next with a 200, each echoing the Authorization received
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut received = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !received.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    received.extend_from_slice(&buffer[..read]);
+                }
+                let received = String::from_utf8_lossy(&received).to_string();
+                let authorization = received
+                    .lines()
+                    .find_map(|line| line.split_once(": ").filter(|(name, _)| name.eq_ignore_ascii_case("authorization")))
+                    .map(|(_, value)| value)
+                    .unwrap_or_default();
+                let response = if received.starts_with("GET /start ") {
+                    format!("HTTP/1.1 307 Temporary Redirect\r\nLocation: /next\r\nX-Authorization: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", authorization)
+                } else {
+                    format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", authorization.len(), authorization)
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let request = reqwest::Request::new(reqwest::Method::GET, format!("{}/start", base).parse().unwrap());
+        let mut jar = CookieJar::default();
+        let settings = HttpClientSettings::default();
+        let (sent, _) = send(&settings, request, false, &RedirectOptions::default(), &mut jar, None, None, Some(auth)).await;
+        let (response, hops) = sent.unwrap();
+        (hops[0].headers["x-authorization"].clone(), String::from_utf8_lossy(&response.body).to_string(), base)
+    }
+
+    #[tokio::test]
+    async fn recomputes_hawk_and_jwt_per_hop() {
+        let credentials = HawkAuth { id: "dh37fgj492je".to_string(), key: "werxhqb98rpaxn39848xrunpaw3489ruxnpa98w4rxn".to_string(), ..Default::default() };
+        let (first, last, base) = authorize_redirected(&RequestAuth::Hawk(credentials.clone())).await;
+        assert_ne!(first, last);
+        // The MAC of the second hop covers its own URL
+        let field = |name: &str| last.split(&format!("{}=\"", name)).nth(1).unwrap().split('"').next().unwrap().to_string();
+        let mut expected = reqwest::Request::new(reqwest::Method::GET, format!("{}/next", base).parse().unwrap());
+        hawk::sign(&mut expected, &credentials, field("ts").parse().unwrap(), &field("nonce")).unwrap();
+        assert_eq!(expected.headers()[header::AUTHORIZATION], last.as_str());
+
+        // Each hop gets a token of its own (with a fresh `jti`)
+        let jwt: JwtAuth = serde_json::from_value(serde_json::json!({ "key": "secret" })).unwrap();
+        let (first, last, _) = authorize_redirected(&RequestAuth::Jwt(jwt)).await;
+        assert!(first.starts_with("Bearer ") && last.starts_with("Bearer "));
+        assert_ne!(first, last);
+    }
+}
@@ -0,0 +1,140 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use encoding_rs::{Encoding, UTF_8};
+use reqwest::header::{self, HeaderMap};
+
+/// A response body decoded according to its `Content-Type`
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedBody {
+    /// The decoded text, empty for binary bodies
+    pub text: String,
+    /// The raw bytes, set for binary bodies and text that did not decode cleanly
+    pub base64: Option<String>,
+    /// The media type without parameters, lowercased
+    pub content_type: Option<String>,
+    /// The charset the text was decoded with
+    pub charset: Option<String>,
+    pub is_binary: bool,
+}
+
+/// Decode a response body: text types are decoded with the charset from
+/// `Content-Type` (or a BOM, falling back to UTF-8), binary types are only
+/// base64-encoded. Bodies without a known type are sniffed.
+pub fn decode(headers: &HeaderMap, bytes: &[u8]) -> DecodedBody {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    let (media_type, charset) = parse_content_type(content_type);
+
+    let is_binary = match media_type.as_deref().map(classify) {
+        Some(Kind::Text) => false,
+        Some(Kind::Binary) => true,
+        Some(Kind::Unknown) | None => charset.is_none() && looks_binary(bytes),
+    };
+    if is_binary {
+        return DecodedBody {
+            text: String::new(),
+            base64: Some(STANDARD.encode(bytes)),
+            content_type: media_type,
+            charset: None,
+            is_binary,
+        };
+    }
+
+    let encoding = charset
+        .as_deref()
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+    // A BOM takes precedence over the declared charset, as in browsers
+    let (text, used, had_errors) = encoding.decode(bytes);
+
+    DecodedBody {
+        text: text.into_owned(),
+        base64: had_errors.then(|| STANDARD.encode(bytes)),
+        content_type: media_type,
+        charset: Some(used.name().to_lowercase()),
+        is_binary,
+    }
+}
+
+/// Split a `Content-Type` value into the media type and its charset parameter
+fn parse_content_type(value: &str) -> (Option<String>, Option<String>) {
+    let mut parts = value.split(';');
+    let media_type = parts
+        .next()
+        .map(|t| t.trim().to_lowercase())
+        .filter(|t| !t.is_empty());
+    let charset = parts.find_map(|param| {
+        let (name, value) = param.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"').to_string())
+            .filter(|v| !v.is_empty())
+    });
+    (media_type, charset)
+}
+
+enum Kind {
+    Text,
+    Binary,
+    Unknown,
+}
+
+fn classify(media_type: &str) -> Kind {
+    let (kind, subtype) = media_type.split_once('/').unwrap_or((media_type, ""));
+    if kind == "text" || subtype.ends_with("+json") || subtype.ends_with("+xml") || subtype.ends_with("+yaml") {
+        return Kind::Text;
+    }
+    match kind {
+        "image" if subtype == "svg+xml" => Kind::Text,
+        "image" | "audio" | "video" | "font" => Kind::Binary,
+        "application" => match subtype {
+            "json" | "xml" | "javascript" | "ecmascript" | "x-javascript" | "graphql" | "x-www-form-urlencoded"
+            | "yaml" | "x-yaml" | "toml" | "csv" | "x-ndjson" | "ndjson" | "sql" => Kind::Text,
+            "octet-stream" | "pdf" | "zip" | "gzip" | "x-gzip" | "x-tar" | "x-7z-compressed" | "x-bzip2"
+            | "protobuf" | "x-protobuf" | "vnd.google.protobuf" | "grpc" | "grpc+proto" | "msgpack"
+            | "x-msgpack" | "cbor" | "wasm" | "vnd.ms-excel" | "msword" => Kind::Binary,
+            _ if subtype.starts_with("vnd.openxmlformats") || subtype.starts_with("grpc") => Kind::Binary,
+            _ => Kind::Unknown,
+        },
+        _ => Kind::Unknown,
+    }
+}
+
+/// Bodies that are not valid UTF-8 or contain NUL bytes are treated as binary
+fn looks_binary(bytes: &[u8]) -> bool {
+    bytes.contains(&0) || std::str::from_utf8(bytes).is_err()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(content_type: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, content_type.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn decodes_declared_charset() {
+        let body = decode(&headers("text/plain; charset=\"ISO-8859-1\""), b"caf\xe9");
+        assert_eq!(body.text, "café");
+        assert_eq!(body.charset.as_deref(), Some("windows-1252"));
+        assert!(body.base64.is_none());
+
+        let body = decode(&headers("application/json"), b"\xef\xbb\xbf{\"a\":1}");
+        assert_eq!(body.text, "{\"a\":1}");
+    }
+
+    #[test]
+    fn keeps_binary_bodies_intact() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0";
+        let body = decode(&headers("image/png"), png);
+        assert!(body.is_binary);
+        assert_eq!(STANDARD.decode(body.base64.unwrap()).unwrap(), png);
+
+        assert!(decode(&HeaderMap::new(), b"\x1f\x8b\x08\0").is_binary);
+        assert!(!decode(&HeaderMap::new(), "plain ünïcode".as_bytes()).is_binary);
+    }
+}
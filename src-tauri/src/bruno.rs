@@ -0,0 +1,814 @@
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::storage::Environment;
+use crate::test_runner::{Assertion, AssertionType, JsonPathOperator, VariableExtraction};
+
+// ============ .bru Files ============
+
+/// A `key: value` line of a dictionary block; `~key` lines are disabled
+#[derive(Debug, Clone, PartialEq)]
+pub struct BruPair {
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BruBlock {
+    /// `headers { ... }`, `params:query { ... }`, `assert { ... }`
+    Dict(Vec<BruPair>),
+    /// `body:json { ... }`, `script:pre-request { ... }`, `docs { ... }`
+    Text(String),
+    /// `vars:secret [ ... ]`
+    List(Vec<String>),
+}
+
+/// A Bruno `.bru` file: named blocks in file order
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BruFile {
+    pub blocks: Vec<(String, BruBlock)>,
+}
+
+/// Blocks whose content is free text rather than `key: value` lines
+fn is_text_block(name: &str) -> bool {
+    (name.starts_with("body") && name != "body:form-urlencoded" && name != "body:multipart-form")
+        || name.starts_with("script")
+        || name == "tests"
+        || name == "docs"
+}
+
+fn dedent(line: &str, width: usize) -> &str {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    &line[indent.min(width)..]
+}
+
+impl BruFile {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut blocks = Vec::new();
+        let mut lines = text.lines().enumerate();
+
+        while let Some((number, line)) = lines.next() {
+            let line = line.trim_end();
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_suffix('[').map(str::trim) {
+                let mut items = Vec::new();
+                for (_, line) in lines.by_ref() {
+                    if line.trim() == "]" {
+                        break;
+                    }
+                    items.extend(line.split(',').map(str::trim).filter(|s| !s.is_empty()).map(String::from));
+                }
+                blocks.push((name.to_string(), BruBlock::List(items)));
+                continue;
+            }
+            if let Some(name) = line.strip_suffix("[]").map(str::trim) {
+                blocks.push((name.to_string(), BruBlock::List(Vec::new())));
+                continue;
+            }
+            let Some(name) = line.strip_suffix('{').map(str::trim) else {
+                return Err(format!("Line {}: expected a block, found '{}'", number + 1, line));
+            };
+
+            // Blocks end with a closing brace at the start of a line
+            let mut content = Vec::new();
+            for (_, line) in lines.by_ref() {
+                if line.trim_end() == "}" {
+                    break;
+                }
+                content.push(line);
+            }
+
+            let block = if is_text_block(name) {
+                let text: Vec<&str> = content.iter().map(|l| dedent(l, 2)).collect();
+                BruBlock::Text(text.join("\n").trim_end().to_string())
+            } else {
+                BruBlock::Dict(parse_pairs(&content))
+            };
+            blocks.push((name.to_string(), block));
+        }
+        Ok(BruFile { blocks })
+    }
+
+    pub fn block(&self, name: &str) -> Option<&BruBlock> {
+        self.blocks.iter().find(|(n, _)| n == name).map(|(_, b)| b)
+    }
+
+    pub fn pairs(&self, name: &str) -> &[BruPair] {
+        match self.block(name) {
+            Some(BruBlock::Dict(pairs)) => pairs,
+            _ => &[],
+        }
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        match self.block(name) {
+            Some(BruBlock::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn list(&self, name: &str) -> &[String] {
+        match self.block(name) {
+            Some(BruBlock::List(items)) => items,
+            _ => &[],
+        }
+    }
+
+    /// The value of an enabled key in a dictionary block
+    pub fn value(&self, block: &str, key: &str) -> Option<&str> {
+        self.pairs(block)
+            .iter()
+            .find(|p| p.enabled && p.key == key)
+            .map(|p| p.value.as_str())
+    }
+
+    pub fn push_pairs(&mut self, name: &str, pairs: Vec<BruPair>) {
+        if !pairs.is_empty() {
+            self.blocks.push((name.to_string(), BruBlock::Dict(pairs)));
+        }
+    }
+
+    pub fn push_text(&mut self, name: &str, text: &str) {
+        if !text.trim().is_empty() {
+            self.blocks.push((name.to_string(), BruBlock::Text(text.to_string())));
+        }
+    }
+
+    pub fn write(&self) -> String {
+        let mut out = String::new();
+        for (name, block) in &self.blocks {
+            match block {
+                BruBlock::Dict(pairs) => {
+                    out.push_str(&format!("{} {{\n", name));
+                    for pair in pairs {
+                        let key = if pair.enabled { pair.key.clone() } else { format!("~{}", pair.key) };
+                        if pair.value.contains('\n') {
+                            out.push_str(&format!("  {}: '''\n", key));
+                            for line in pair.value.lines() {
+                                out.push_str(&format!("    {}\n", line));
+                            }
+                            out.push_str("  '''\n");
+                        } else {
+                            out.push_str(&format!("  {}: {}\n", key, pair.value));
+                        }
+                    }
+                    out.push_str("}\n\n");
+                }
+                BruBlock::Text(text) => {
+                    out.push_str(&format!("{} {{\n", name));
+                    for line in text.lines() {
+                        if line.is_empty() {
+                            out.push('\n');
+                        } else {
+                            out.push_str(&format!("  {}\n", line));
+                        }
+                    }
+                    out.push_str("}\n\n");
+                }
+                BruBlock::List(items) => {
+                    out.push_str(&format!("{} [\n", name));
+                    out.push_str(&items.iter().map(|i| format!("  {}", i)).collect::<Vec<_>>().join(",\n"));
+                    out.push_str("\n]\n\n");
+                }
+            }
+        }
+        out.trim_end().to_string() + "\n"
+    }
+}
+
+fn parse_pairs(lines: &[&str]) -> Vec<BruPair> {
+    let mut pairs = Vec::new();
+    let mut lines = lines.iter();
+    while let Some(line) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (key, value) = match line.split_once(": ") {
+            Some((key, value)) => (key, value.trim()),
+            None => (line.trim_end_matches(':'), ""),
+        };
+        let (key, enabled) = match key.strip_prefix('~') {
+            Some(key) => (key, false),
+            None => (key, true),
+        };
+        // Multiline values are wrapped in ''' lines
+        let value = if value == "'''" {
+            let mut text = Vec::new();
+            for line in lines.by_ref() {
+                if line.trim() == "'''" {
+                    break;
+                }
+                text.push(dedent(line, 4));
+            }
+            text.join("\n")
+        } else {
+            value.to_string()
+        };
+        pairs.push(BruPair {
+            key: key.trim().to_string(),
+            value,
+            enabled,
+        });
+    }
+    pairs
+}
+
+pub fn pair(key: &str, value: &str, enabled: bool) -> BruPair {
+    BruPair {
+        key: key.to_string(),
+        value: value.to_string(),
+        enabled,
+    }
+}
+
+// ============ Assertions ============
+
+fn new_assertion(assertion_type: AssertionType, enabled: bool) -> Assertion {
+    Assertion {
+        id: uuid::Uuid::new_v4().to_string(),
+        assertion_type,
+        enabled,
+        expected_status: None,
+        min_status: None,
+        max_status: None,
+        json_path: None,
+        operator: None,
+        expected_value: None,
+        search_string: None,
+        max_time_ms: None,
+        timing_phase: None,
+        header_name: None,
+        header_value: None,
+        json_schema: None,
+    }
+}
+
+/// A Bruno operand without the quotes of string literals
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    if value.len() >= 2 && (value.starts_with('"') && value.ends_with('"') || value.starts_with('\'') && value.ends_with('\'')) {
+        return value[1..value.len() - 1].to_string();
+    }
+    value.to_string()
+}
+
+/// A string operand, quoted unless it is a number, boolean or null
+fn quote(value: &str) -> String {
+    if value.parse::<f64>().is_ok() || matches!(value, "true" | "false" | "null") {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('"', "\\\""))
+    }
+}
+
+/// `res.body.items[0].id` as the JSONPath `$.items[0].id`
+fn body_path(expression: &str) -> Option<String> {
+    let rest = expression.strip_prefix("res.body")?;
+    if !rest.is_empty() && !rest.starts_with('.') && !rest.starts_with('[') {
+        return None;
+    }
+    Some(format!("${}", rest))
+}
+
+/// A Bruno operator and its operand as an operator and expected value
+fn parse_operator(operator: &str, operand: String) -> Option<(JsonPathOperator, Option<String>)> {
+    Some(match operator {
+        "eq" => (JsonPathOperator::Equals, Some(operand)),
+        "neq" => (JsonPathOperator::NotEquals, Some(operand)),
+        "contains" => (JsonPathOperator::Contains, Some(operand)),
+        "isDefined" | "isNotEmpty" | "isTruthy" => (JsonPathOperator::Exists, None),
+        "isUndefined" => (JsonPathOperator::NotExists, None),
+        "isNull" => (JsonPathOperator::Equals, Some("null".to_string())),
+        "matches" => (JsonPathOperator::Matches, Some(operand)),
+        "gt" => (JsonPathOperator::Gt, Some(operand)),
+        "gte" => (JsonPathOperator::Gte, Some(operand)),
+        "lt" => (JsonPathOperator::Lt, Some(operand)),
+        "lte" => (JsonPathOperator::Lte, Some(operand)),
+        "in" => (JsonPathOperator::OneOf, Some(operand)),
+        "length" => (JsonPathOperator::LengthEquals, Some(operand)),
+        "isString" => (JsonPathOperator::IsType, Some("string".to_string())),
+        "isNumber" => (JsonPathOperator::IsType, Some("number".to_string())),
+        "isBoolean" => (JsonPathOperator::IsType, Some("boolean".to_string())),
+        "isArray" => (JsonPathOperator::IsType, Some("array".to_string())),
+        _ => return None,
+    })
+}
+
+/// An operator and expected value as a Bruno operator with its operand, when Bruno has one
+fn operator_value(operator: &JsonPathOperator, expected: &str) -> Option<String> {
+    let quoted = quote(expected);
+    Some(match operator {
+        JsonPathOperator::Equals => format!("eq {}", quoted),
+        JsonPathOperator::NotEquals => format!("neq {}", quoted),
+        JsonPathOperator::Contains => format!("contains {}", quoted),
+        JsonPathOperator::Exists => "isDefined".to_string(),
+        JsonPathOperator::NotExists => "isUndefined".to_string(),
+        JsonPathOperator::Matches => format!("matches {}", expected),
+        JsonPathOperator::Gt => format!("gt {}", expected),
+        JsonPathOperator::Gte => format!("gte {}", expected),
+        JsonPathOperator::Lt => format!("lt {}", expected),
+        JsonPathOperator::Lte => format!("lte {}", expected),
+        JsonPathOperator::OneOf => format!("in {}", expected),
+        JsonPathOperator::LengthEquals => format!("length {}", expected),
+        JsonPathOperator::IsType => match expected.trim() {
+            "string" => "isString".to_string(),
+            "number" => "isNumber".to_string(),
+            "boolean" => "isBoolean".to_string(),
+            "array" => "isArray".to_string(),
+            _ => return None,
+        },
+        JsonPathOperator::LengthGt | JsonPathOperator::In => return None,
+    })
+}
+
+/// A Bruno `assert` line (`res.status: eq 200`) as an assertion, when it has an equivalent
+pub fn parse_assertion(pair: &BruPair) -> Option<Assertion> {
+    let (operator, operand) = match pair.value.split_once(' ') {
+        Some((operator, operand)) => (operator, unquote(operand)),
+        None => (pair.value.as_str(), String::new()),
+    };
+    let expression = pair.key.as_str();
+
+    let mut assertion;
+    if expression == "res.status" {
+        match operator {
+            "eq" => {
+                assertion = new_assertion(AssertionType::Status, pair.enabled);
+                assertion.expected_status = Some(operand.parse().ok()?);
+            }
+            "between" => {
+                let (min, max) = operand.split_once(',')?;
+                assertion = new_assertion(AssertionType::StatusRange, pair.enabled);
+                assertion.min_status = Some(min.trim().parse().ok()?);
+                assertion.max_status = Some(max.trim().parse().ok()?);
+            }
+            "gte" | "lte" | "gt" | "lt" => {
+                let status: u16 = operand.parse().ok()?;
+                assertion = new_assertion(AssertionType::StatusRange, pair.enabled);
+                let (min, max) = match operator {
+                    "gte" => (status, 599),
+                    "gt" => (status + 1, 599),
+                    "lte" => (100, status),
+                    _ => (100, status.saturating_sub(1)),
+                };
+                assertion.min_status = Some(min);
+                assertion.max_status = Some(max);
+            }
+            _ => return None,
+        }
+    } else if expression == "res.responseTime" {
+        let max: u64 = operand.parse().ok()?;
+        assertion = new_assertion(AssertionType::ResponseTime, pair.enabled);
+        match operator {
+            "lte" => assertion.max_time_ms = Some(max),
+            "lt" => assertion.max_time_ms = Some(max.saturating_sub(1)),
+            "gt" => {
+                assertion.operator = Some(JsonPathOperator::Gt);
+                assertion.expected_value = Some(operand);
+            }
+            "gte" => {
+                assertion.operator = Some(JsonPathOperator::Gte);
+                assertion.expected_value = Some(operand);
+            }
+            _ => return None,
+        }
+    } else if let Some(header) = expression.strip_prefix("res.headers.") {
+        assertion = new_assertion(AssertionType::Header, pair.enabled);
+        assertion.header_name = Some(unquote(header.trim_start_matches('[').trim_end_matches(']')));
+        match operator {
+            "eq" => assertion.header_value = Some(operand),
+            "isDefined" => {}
+            _ => {
+                let (operator, expected) = parse_operator(operator, operand)?;
+                assertion.operator = Some(operator);
+                assertion.header_value = expected;
+            }
+        }
+    } else if expression == "res.body" && operator == "contains" {
+        assertion = new_assertion(AssertionType::Contains, pair.enabled);
+        assertion.search_string = Some(operand);
+    } else if let Some(path) = body_path(expression) {
+        assertion = new_assertion(AssertionType::Jsonpath, pair.enabled);
+        assertion.json_path = Some(path);
+        let (operator, expected) = parse_operator(operator, operand)?;
+        assertion.operator = Some(operator);
+        assertion.expected_value = expected;
+    } else {
+        return None;
+    }
+    Some(assertion)
+}
+
+/// An assertion as a Bruno `assert` line, when Bruno has an equivalent
+pub fn assertion_pair(assertion: &Assertion) -> Option<BruPair> {
+    let (key, value) = match assertion.assertion_type {
+        AssertionType::Status => ("res.status".to_string(), format!("eq {}", assertion.expected_status.unwrap_or(200))),
+        AssertionType::StatusRange => (
+            "res.status".to_string(),
+            format!(
+                "between {}, {}",
+                assertion.min_status.unwrap_or(200),
+                assertion.max_status.unwrap_or(299)
+            ),
+        ),
+        AssertionType::ResponseTime => match &assertion.operator {
+            Some(operator) => (
+                "res.responseTime".to_string(),
+                operator_value(operator, assertion.expected_value.as_deref()?)?,
+            ),
+            None => ("res.responseTime".to_string(), format!("lte {}", assertion.max_time_ms?)),
+        },
+        AssertionType::Contains => (
+            "res.body".to_string(),
+            format!("contains {}", quote(assertion.search_string.as_deref().unwrap_or(""))),
+        ),
+        AssertionType::Header => {
+            let key = format!("res.headers.{}", assertion.header_name.as_deref()?.to_lowercase());
+            match (&assertion.operator, &assertion.header_value) {
+                (Some(operator), value) => (key, operator_value(operator, value.as_deref().unwrap_or(""))?),
+                (None, Some(value)) => (key, format!("eq {}", quote(value))),
+                (None, None) => (key, "isDefined".to_string()),
+            }
+        }
+        AssertionType::Jsonpath => {
+            let path = assertion.json_path.as_deref().unwrap_or("$");
+            let key = format!("res.body{}", path.strip_prefix('$')?);
+            let operator = assertion.operator.as_ref().unwrap_or(&JsonPathOperator::Exists);
+            (key, operator_value(operator, assertion.expected_value.as_deref().unwrap_or(""))?)
+        }
+        // Connection phases, schemas and body sizes cannot be asserted in Bruno
+        AssertionType::Timing | AssertionType::JsonSchema | AssertionType::BodySize => return None,
+    };
+    Some(pair(&key, &value, assertion.enabled))
+}
+
+/// A `vars:post-response` line reading the body (`token: res.body.token`) as an extraction
+pub fn parse_extraction(pair: &BruPair) -> Option<VariableExtraction> {
+    Some(VariableExtraction {
+        id: uuid::Uuid::new_v4().to_string(),
+        variable_name: pair.key.clone(),
+        json_path: body_path(pair.value.trim())?,
+        enabled: pair.enabled,
+    })
+}
+
+// ============ Export ============
+
+fn str_field<'a>(value: &'a Value, name: &str) -> &'a str {
+    value.get(name).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+/// Enabled and disabled `{ key, value, enabled }` items with a key
+fn key_values(items: Option<&Value>) -> Vec<BruPair> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter(|item| !str_field(item, "key").is_empty())
+                .map(|item| {
+                    let enabled = item.get("enabled").and_then(|e| e.as_bool()).unwrap_or(true);
+                    pair(str_field(item, "key"), str_field(item, "value"), enabled)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A name usable as a file name on every platform
+fn file_name(name: &str, taken: &mut Vec<String>) -> String {
+    let clean: String = name
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '-' } else { c })
+        .collect();
+    let clean = clean.trim().trim_matches('.').to_string();
+    let base = if clean.is_empty() { "Untitled".to_string() } else { clean };
+    let mut candidate = base.clone();
+    let mut n = 2;
+    while taken.iter().any(|t| t.eq_ignore_ascii_case(&candidate)) {
+        candidate = format!("{} ({})", base, n);
+        n += 1;
+    }
+    taken.push(candidate.clone());
+    candidate
+}
+
+/// Auth blocks for a frontend `AuthConfig`: the mode and its block
+fn auth_block(auth: Option<&Value>) -> (String, Option<(String, Vec<BruPair>)>) {
+    let Some(auth) = auth.filter(|a| a.get("enabled").and_then(|e| e.as_bool()).unwrap_or(true)) else {
+        return ("none".to_string(), None);
+    };
+    let field = |name: &str| str_field(auth, name);
+    let nested = |object: &str, name: &str| auth.get(object).map(|o| str_field(o, name).to_string()).unwrap_or_default();
+    let (mode, pairs) = match field("type") {
+        "inherit" => return ("inherit".to_string(), None),
+        "basic" => ("basic", vec![pair("username", field("username"), true), pair("password", field("password"), true)]),
+        "digest" => ("digest", vec![pair("username", field("username"), true), pair("password", field("password"), true)]),
+        "bearer" => ("bearer", vec![pair("token", field("token"), true)]),
+        "api-key" => (
+            "apikey",
+            vec![
+                pair("key", field("apiKeyName"), true),
+                pair("value", field("apiKeyValue"), true),
+                pair("placement", if field("apiKeyIn") == "query" { "queryparams" } else { "header" }, true),
+            ],
+        ),
+        "aws-sigv4" => (
+            "awsv4",
+            vec![
+                pair("accessKeyId", &nested("awsSigV4", "accessKeyId"), true),
+                pair("secretAccessKey", &nested("awsSigV4", "secretAccessKey"), true),
+                pair("sessionToken", &nested("awsSigV4", "sessionToken"), true),
+                pair("service", &nested("awsSigV4", "service"), true),
+                pair("region", &nested("awsSigV4", "region"), true),
+                pair("profileName", "", true),
+            ],
+        ),
+        "oauth2" => {
+            let grant_type = match nested("oauth2", "grantType").as_str() {
+                "client_credentials" => "client_credentials",
+                "password" => "password",
+                _ => "authorization_code",
+            };
+            (
+                "oauth2",
+                vec![
+                    pair("grant_type", grant_type, true),
+                    pair("callback_url", "", true),
+                    pair("authorization_url", &nested("oauth2", "authUrl"), true),
+                    pair("access_token_url", &nested("oauth2", "tokenUrl"), true),
+                    pair("client_id", &nested("oauth2", "clientId"), true),
+                    pair("client_secret", &nested("oauth2", "clientSecret"), true),
+                    pair("scope", &nested("oauth2", "scope"), true),
+                    pair("username", &nested("oauth2", "username"), true),
+                    pair("password", &nested("oauth2", "password"), true),
+                ],
+            )
+        }
+        _ => return ("none".to_string(), None),
+    };
+    (mode.to_string(), Some((format!("auth:{}", mode), pairs)))
+}
+
+/// A collection request as a `.bru` file, or None for protocols Bruno lacks
+fn request_file(request: &Value, seq: usize) -> Option<BruFile> {
+    let protocol = request.get("protocol").and_then(|p| p.as_str()).unwrap_or("http");
+    let is_graphql = match protocol {
+        "http" => false,
+        "graphql" => true,
+        _ => return None,
+    };
+
+    let mut file = BruFile::default();
+    file.push_pairs(
+        "meta",
+        vec![
+            pair("name", str_field(request, "name"), true),
+            pair("type", if is_graphql { "graphql" } else { "http" }, true),
+            pair("seq", &seq.to_string(), true),
+        ],
+    );
+
+    let params = key_values(request.get("params"));
+    let query: Vec<String> = params
+        .iter()
+        .filter(|p| p.enabled)
+        .map(|p| format!("{}={}", p.key, p.value))
+        .collect();
+    let mut url = str_field(request, "url").to_string();
+    if !query.is_empty() {
+        url = format!("{}{}{}", url, if url.contains('?') { "&" } else { "?" }, query.join("&"));
+    }
+
+    let body_type = if is_graphql { "graphql" } else { str_field(request, "bodyType") };
+    let body_mode = match body_type {
+        "json" => "json",
+        "xml" => "xml",
+        "html" | "raw" => "text",
+        "x-www-form-urlencoded" => "formUrlEncoded",
+        "form-data" => "multipartForm",
+        "graphql" => "graphql",
+        _ => "none",
+    };
+    let (auth_mode, auth) = auth_block(request.get("auth"));
+    let method = if is_graphql { "post".to_string() } else { str_field(request, "method").to_lowercase() };
+    file.push_pairs(
+        &method,
+        vec![pair("url", &url, true), pair("body", body_mode, true), pair("auth", &auth_mode, true)],
+    );
+
+    file.push_pairs("params:query", params);
+    file.push_pairs("headers", key_values(request.get("headers")));
+    if let Some((name, pairs)) = auth {
+        file.push_pairs(&name, pairs);
+    }
+
+    match body_mode {
+        "json" | "xml" | "text" => file.push_text(&format!("body:{}", body_mode), str_field(request, "body")),
+        "formUrlEncoded" => file.push_pairs("body:form-urlencoded", key_values(request.get("urlEncodedData"))),
+        "multipartForm" => {
+            let fields = request
+                .get("formData")
+                .and_then(|f| f.as_array())
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .filter(|f| !str_field(f, "key").is_empty())
+                        .map(|f| {
+                            let enabled = f.get("enabled").and_then(|e| e.as_bool()).unwrap_or(true);
+                            let value = if str_field(f, "type") == "file" {
+                                format!("@file({})", str_field(f, "value"))
+                            } else {
+                                str_field(f, "value").to_string()
+                            };
+                            pair(str_field(f, "key"), &value, enabled)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            file.push_pairs("body:multipart-form", fields);
+        }
+        "graphql" => {
+            file.push_text("body:graphql", str_field(request, "query"));
+            file.push_text("body:graphql:vars", str_field(request, "variables"));
+        }
+        _ => {}
+    }
+
+    let test_config = request.get("testConfig");
+    let assertions: Vec<Assertion> = test_config
+        .and_then(|c| c.get("assertions"))
+        .and_then(|a| serde_json::from_value(a.clone()).ok())
+        .unwrap_or_default();
+    file.push_pairs("assert", assertions.iter().filter_map(assertion_pair).collect());
+
+    let extractions: Vec<VariableExtraction> = test_config
+        .and_then(|c| c.get("extractVariables"))
+        .and_then(|a| serde_json::from_value(a.clone()).ok())
+        .unwrap_or_default();
+    let vars = extractions
+        .iter()
+        .filter_map(|e| {
+            let path = e.json_path.strip_prefix('$')?;
+            Some(pair(&e.variable_name, &format!("res.body{}", path), e.enabled))
+        })
+        .collect();
+    file.push_pairs("vars:post-response", vars);
+
+    Some(file)
+}
+
+fn write_file(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Write requests and subfolders of a collection or folder into `dir`
+fn write_items(item: &Value, dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let mut taken = vec!["folder".to_string(), "collection".to_string()];
+
+    let requests = item.get("requests").and_then(|r| r.as_array()).cloned().unwrap_or_default();
+    for (i, request) in requests.iter().enumerate() {
+        if let Some(file) = request_file(request, i + 1) {
+            let name = file_name(str_field(request, "name"), &mut taken);
+            write_file(&dir.join(format!("{}.bru", name)), &file.write())?;
+        }
+    }
+
+    let folders = item.get("folders").and_then(|f| f.as_array()).cloned().unwrap_or_default();
+    for (i, folder) in folders.iter().enumerate() {
+        let folder_dir = dir.join(file_name(str_field(folder, "name"), &mut taken));
+        write_items(folder, &folder_dir)?;
+        let mut meta = BruFile::default();
+        meta.push_pairs(
+            "meta",
+            vec![pair("name", str_field(folder, "name"), true), pair("seq", &(i + 1).to_string(), true)],
+        );
+        write_file(&folder_dir.join("folder.bru"), &meta.write())?;
+    }
+    Ok(())
+}
+
+/// An environment as a `.bru` file; secret values are listed by name only
+fn environment_file(environment: &Environment) -> BruFile {
+    let (secret, plain): (Vec<_>, Vec<_>) = environment
+        .variables
+        .iter()
+        .filter(|v| !v.key.is_empty())
+        .partition(|v| v.is_secret || v.secret_provider.is_some());
+    let mut file = BruFile::default();
+    file.push_pairs("vars", plain.iter().map(|v| pair(&v.key, &v.value, v.enabled)).collect());
+    if !secret.is_empty() {
+        file.blocks.push((
+            "vars:secret".to_string(),
+            BruBlock::List(secret.iter().map(|v| v.key.clone()).collect()),
+        ));
+    }
+    file
+}
+
+/// Write a collection as a Bruno collection directory named after it inside `parent`
+pub fn export_collection(collection: &Value, environments: &[Environment], parent: &Path) -> Result<PathBuf, String> {
+    let name = str_field(collection, "name");
+    let dir = parent.join(file_name(name, &mut Vec::new()));
+    write_items(collection, &dir)?;
+
+    let manifest = serde_json::json!({
+        "version": "1",
+        "name": name,
+        "type": "collection",
+        "ignore": ["node_modules", ".git"],
+    });
+    let manifest = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    write_file(&dir.join("bruno.json"), &manifest)?;
+
+    if !environments.is_empty() {
+        let env_dir = dir.join("environments");
+        fs::create_dir_all(&env_dir).map_err(|e| format!("Failed to create {}: {}", env_dir.display(), e))?;
+        let mut taken = Vec::new();
+        for environment in environments {
+            let file_path = env_dir.join(format!("{}.bru", file_name(&environment.name, &mut taken)));
+            write_file(&file_path, &environment_file(environment).write())?;
+        }
+    }
+    Ok(dir)
+}
+
+/// Export a collection (and optionally environments) as a Bruno collection
+/// directory inside `directory`; returns the created directory
+#[tauri::command]
+pub async fn export_bruno(
+    collection: Value,
+    environments: Option<Vec<Environment>>,
+    directory: String,
+) -> Result<String, String> {
+    let dir = export_collection(&collection, &environments.unwrap_or_default(), Path::new(&directory))?;
+    Ok(dir.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST: &str = r#"meta {
+  name: Get user
+  type: http
+  seq: 2
+}
+
+get {
+  url: {{baseUrl}}/users/1?verbose=true
+  body: json
+  auth: bearer
+}
+
+params:query {
+  verbose: true
+  ~page: 2
+}
+
+auth:bearer {
+  token: {{token}}
+}
+
+body:json {
+  {
+    "name": "a"
+  }
+}
+
+assert {
+  res.status: eq 200
+  res.body.items[0].id: eq "abc"
+  ~res.responseTime: lt 500
+}
+
+vars:secret [
+  token,
+  apiKey
+]
+"#;
+
+    #[test]
+    fn parses_and_writes_bru_files() {
+        let file = BruFile::parse(REQUEST).unwrap();
+        assert_eq!(file.value("meta", "name"), Some("Get user"));
+        assert_eq!(file.value("get", "url"), Some("{{baseUrl}}/users/1?verbose=true"));
+        assert_eq!(file.pairs("params:query")[1], pair("page", "2", false));
+        assert_eq!(file.text("body:json"), Some("{\n  \"name\": \"a\"\n}"));
+        assert_eq!(file.list("vars:secret"), ["token", "apiKey"]);
+        assert_eq!(BruFile::parse(&file.write()).unwrap(), file);
+
+        let assertions: Vec<Assertion> = file.pairs("assert").iter().filter_map(parse_assertion).collect();
+        assert_eq!(assertions[0].expected_status, Some(200));
+        assert_eq!(assertions[1].json_path.as_deref(), Some("$.items[0].id"));
+        assert_eq!(assertions[1].expected_value.as_deref(), Some("abc"));
+        assert_eq!(assertions[2].max_time_ms, Some(499));
+        assert!(!assertions[2].enabled);
+        assert_eq!(assertion_pair(&assertions[1]), Some(pair("res.body.items[0].id", "eq \"abc\"", true)));
+    }
+}
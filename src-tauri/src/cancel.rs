@@ -0,0 +1,123 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio_util::sync::CancellationToken;
+
+static NEXT_SERIAL: AtomicU64 = AtomicU64::new(0);
+
+/// The entry of an id in `IN_FLIGHT_REQUESTS`. Sends registered under the same
+/// id while it is in flight share its token, so cancelling the id stops all of them.
+pub struct InFlight {
+    serial: u64,
+    token: CancellationToken,
+    registrations: usize,
+}
+
+/// An in-flight request (or test run) registered in `IN_FLIGHT_REQUESTS`.
+/// The registration is removed when the last one sharing its id is dropped.
+pub struct Registration {
+    id: String,
+    serial: u64,
+    token: CancellationToken,
+}
+
+impl Registration {
+    pub fn new(id: &str) -> Self {
+        let mut entry = crate::IN_FLIGHT_REQUESTS
+            .entry(id.to_string())
+            .or_insert_with(|| InFlight {
+                serial: NEXT_SERIAL.fetch_add(1, Ordering::Relaxed),
+                token: CancellationToken::new(),
+                registrations: 0,
+            });
+        // A cancelled id still winding down doesn't cancel new sends
+        if entry.token.is_cancelled() {
+            *entry = InFlight {
+                serial: NEXT_SERIAL.fetch_add(1, Ordering::Relaxed),
+                token: CancellationToken::new(),
+                registrations: 0,
+            };
+        }
+        entry.registrations += 1;
+        Self {
+            id: id.to_string(),
+            serial: entry.serial,
+            token: entry.token.clone(),
+        }
+    }
+
+    /// Run `future` until it completes or the request is cancelled
+    pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, String> {
+        tokio::select! {
+            output = future => Ok(output),
+            _ = self.token.cancelled() => Err("Request cancelled".to_string()),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        // Only the entry this registered with, which a later send may have replaced
+        crate::IN_FLIGHT_REQUESTS.remove_if_mut(&self.id, |_, entry| {
+            if entry.serial == self.serial {
+                entry.registrations -= 1;
+            }
+            entry.serial == self.serial && entry.registrations == 0
+        });
+    }
+}
+
+/// Run `future`, registered under `request_id` when one is given so that
+/// `cancel_request` can abort it
+pub async fn cancellable<T, F>(request_id: Option<&str>, future: F) -> Result<T, String>
+where
+    F: Future<Output = Result<T, String>>,
+{
+    match request_id {
+        Some(id) => Registration::new(id).run(future).await?,
+        None => future.await,
+    }
+}
+
+/// Cancel an in-flight request or test run. Returns false when nothing is
+/// registered under `request_id` (it may already have finished).
+pub fn cancel(request_id: &str) -> bool {
+    match crate::IN_FLIGHT_REQUESTS.get(request_id) {
+        Some(entry) => {
+            entry.token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+#[tauri::command]
+pub fn cancel_request(request_id: String) -> bool {
+    cancel(&request_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicate_ids_share_cancellation() {
+        let first = Registration::new("duplicate-send");
+        let second = Registration::new("duplicate-send");
+        drop(first);
+        assert!(cancel("duplicate-send"));
+        assert!(second.is_cancelled());
+
+        // A send after the cancel starts fresh, and the old one finishing leaves it registered
+        let third = Registration::new("duplicate-send");
+        assert!(!third.is_cancelled());
+        drop(second);
+        assert!(cancel("duplicate-send"));
+        assert!(third.is_cancelled());
+        drop(third);
+        assert!(!cancel("duplicate-send"));
+    }
+}
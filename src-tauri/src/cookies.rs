@@ -0,0 +1,408 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderValue, COOKIE, SET_COOKIE};
+use reqwest::Url;
+use std::collections::HashMap;
+
+use crate::storage::{Cookie, Storage};
+
+/// A workspace's cookies, held in memory for the duration of a request or test run.
+///
+/// Cookies from `Set-Cookie` response headers are stored and sent back on later
+/// requests that match their domain, path and `Secure` flag (RFC 6265).
+///
+/// Only what changed since the jar was loaded (or forked) is written back, so
+/// jars used at the same time and edits from the cookie manager don't overwrite
+/// each other.
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+    changed: bool,
+    /// Cookies when the jar was loaded, forked or last saved
+    base: Option<Vec<Cookie>>,
+}
+
+impl CookieJar {
+    /// Load the jar of a workspace (empty and in-memory only when there is no workspace)
+    pub fn load(storage: &Storage, workspace_id: Option<&str>) -> Self {
+        let cookies = workspace_id
+            .and_then(|id| storage.get_cookies(id).ok())
+            .unwrap_or_default();
+        Self { base: Some(cookies.clone()), cookies, changed: false }
+    }
+
+    /// A copy of the jar for a request running concurrently with others
+    pub fn fork(&self) -> Self {
+        Self {
+            cookies: self.cookies.clone(),
+            changed: false,
+            base: Some(self.cookies.clone()),
+        }
+    }
+
+    /// Apply the cookies this jar set, replaced or removed since its base to `cookies`,
+    /// keeping what others changed in the meantime
+    fn apply_changes(&self, cookies: &mut Vec<Cookie>) {
+        let empty = Vec::new();
+        let base = self.base.as_ref().unwrap_or(&empty);
+        let same = |a: &Cookie, b: &Cookie| a.name == b.name && a.domain == b.domain && a.path == b.path;
+        for removed in base.iter().filter(|c| !self.cookies.iter().any(|f| same(f, c))) {
+            cookies.retain(|c| !same(c, removed));
+        }
+        for cookie in self.cookies.iter().filter(|c| !base.contains(c)) {
+            cookies.retain(|c| !same(c, cookie));
+            cookies.push(cookie.clone());
+        }
+    }
+
+    /// Whether cookies were set or removed since the jar was loaded, forked or saved
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Apply what a forked jar changed
+    pub fn merge(&mut self, fork: CookieJar) {
+        if !fork.changed {
+            return;
+        }
+        fork.apply_changes(&mut self.cookies);
+        self.changed = true;
+    }
+
+    /// Write what changed back to the workspace
+    pub fn save(&mut self, storage: &Storage, workspace_id: Option<&str>) -> Result<(), String> {
+        if !self.changed {
+            return Ok(());
+        }
+        if let Some(id) = workspace_id {
+            let now = Utc::now().timestamp();
+            storage.update_cookies(id, |cookies| {
+                self.apply_changes(cookies);
+                cookies.retain(|c| !is_expired(c, now));
+            })?;
+        }
+        self.base = Some(self.cookies.clone());
+        self.changed = false;
+        Ok(())
+    }
+
+    /// Cookies that would be sent to `url`, longest path first
+    pub fn matching(&self, url: &Url) -> Vec<&Cookie> {
+        let now = Utc::now().timestamp();
+        let mut matching: Vec<&Cookie> = self
+            .cookies
+            .iter()
+            .filter(|c| cookie_matches(c, url, now))
+            .collect();
+        matching.sort_by_key(|c| std::cmp::Reverse(c.path.len()));
+        matching
+    }
+
+    /// Name/value pairs sent to `url` (exposed to scripts as `istek.cookies`)
+    pub fn values_for(&self, url: &Url) -> HashMap<String, String> {
+        let mut values = HashMap::new();
+        // Longest path first, so the most specific cookie wins on duplicate names
+        for cookie in self.matching(url) {
+            values
+                .entry(cookie.name.clone())
+                .or_insert_with(|| cookie.value.clone());
+        }
+        values
+    }
+
+    /// Add matching cookies to the request's `Cookie` header.
+    /// Cookies the user set explicitly in that header take precedence.
+    pub fn add_to_request(&self, request: &mut reqwest::Request) {
+        let matching = self.matching(request.url());
+        if matching.is_empty() {
+            return;
+        }
+
+        let explicit = request
+            .headers()
+            .get(COOKIE)
+            .and_then(|v| v.to_str().ok())
+            .map(|s| s.to_string());
+        let explicit_names: Vec<String> = explicit
+            .as_deref()
+            .unwrap_or("")
+            .split(';')
+            .filter_map(|pair| pair.split('=').next())
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        let mut pairs: Vec<String> = explicit.into_iter().collect();
+        for cookie in matching {
+            if !explicit_names.contains(&cookie.name) {
+                pairs.push(format!("{}={}", cookie.name, cookie.value));
+            }
+        }
+
+        if let Ok(value) = HeaderValue::from_str(&pairs.join("; ")) {
+            request.headers_mut().insert(COOKIE, value);
+        }
+    }
+
+    /// Store the `Set-Cookie` headers of a response received from `url`
+    pub fn store_from_response(&mut self, url: &Url, headers: &HeaderMap) {
+        let now = Utc::now().timestamp();
+        for value in headers.get_all(SET_COOKIE) {
+            if let Some(cookie) = value.to_str().ok().and_then(|v| parse_set_cookie(v, url, now)) {
+                self.insert(cookie, now);
+            }
+        }
+    }
+
+    /// Set a host-only cookie for the host of `url`
+    pub fn set(&mut self, url: &Url, name: &str, value: &str) {
+        let Some(host) = url.host_str() else {
+            return;
+        };
+        let cookie = Cookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: host.to_lowercase(),
+            path: "/".to_string(),
+            host_only: true,
+            expires: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        };
+        self.insert(cookie, Utc::now().timestamp());
+    }
+
+    /// Remove every cookie named `name` that would be sent to `url`
+    pub fn remove(&mut self, url: &Url, name: &str) {
+        let now = Utc::now().timestamp();
+        let before = self.cookies.len();
+        self.cookies
+            .retain(|c| !(c.name == name && cookie_matches(c, url, now)));
+        self.changed |= self.cookies.len() != before;
+    }
+
+    fn insert(&mut self, cookie: Cookie, now: i64) {
+        self.cookies.retain(|c| {
+            !(c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path)
+        });
+        // Servers delete cookies by sending them again with an expiry in the past
+        if !is_expired(&cookie, now) {
+            self.cookies.push(cookie);
+        }
+        self.changed = true;
+    }
+}
+
+/// Parse a `Set-Cookie` header value received from `url`
+pub fn parse_set_cookie(header: &str, url: &Url, now: i64) -> Option<Cookie> {
+    let mut parts = header.split(';');
+    let (name, value) = parts.next()?.split_once('=')?;
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let host = url.host_str()?.to_lowercase();
+
+    let mut cookie = Cookie {
+        name: name.to_string(),
+        value: value.trim().to_string(),
+        domain: host.clone(),
+        path: default_path(url),
+        host_only: true,
+        expires: None,
+        secure: false,
+        http_only: false,
+        same_site: None,
+    };
+    let mut max_age = None;
+
+    for attribute in parts {
+        let (key, val) = match attribute.split_once('=') {
+            Some((k, v)) => (k.trim(), v.trim()),
+            None => (attribute.trim(), ""),
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "domain" => {
+                let domain = val.trim_start_matches('.').to_lowercase();
+                if !domain.is_empty() {
+                    // A server may only set cookies for its own domain or a parent of it
+                    if !domain_matches(&host, &domain) {
+                        return None;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+            }
+            "path" if val.starts_with('/') => cookie.path = val.to_string(),
+            "expires" => {
+                if let Some(timestamp) = parse_cookie_date(val) {
+                    cookie.expires = Some(timestamp);
+                }
+            }
+            "max-age" => {
+                if let Ok(seconds) = val.parse::<i64>() {
+                    max_age = Some(seconds);
+                }
+            }
+            "secure" => cookie.secure = true,
+            "httponly" => cookie.http_only = true,
+            "samesite" => cookie.same_site = Some(val.to_string()),
+            _ => {}
+        }
+    }
+
+    // Max-Age takes precedence over Expires
+    if let Some(seconds) = max_age {
+        cookie.expires = Some(if seconds <= 0 { 0 } else { now + seconds });
+    }
+
+    Some(cookie)
+}
+
+fn cookie_matches(cookie: &Cookie, url: &Url, now: i64) -> bool {
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    let host = host.to_lowercase();
+
+    let host_ok = if cookie.host_only {
+        host == cookie.domain
+    } else {
+        domain_matches(&host, &cookie.domain)
+    };
+    let secure_ok = !cookie.secure
+        || matches!(url.scheme(), "https" | "wss")
+        || host == "localhost"
+        || host == "127.0.0.1";
+
+    host_ok && secure_ok && path_matches(url.path(), &cookie.path) && !is_expired(cookie, now)
+}
+
+fn is_expired(cookie: &Cookie, now: i64) -> bool {
+    cookie.expires.is_some_and(|expires| expires <= now)
+}
+
+/// RFC 6265 section 5.1.3
+fn domain_matches(host: &str, domain: &str) -> bool {
+    if host == domain {
+        return true;
+    }
+    let is_ip = host.parse::<std::net::IpAddr>().is_ok() || host.starts_with('[');
+    !is_ip && host.ends_with(domain) && host[..host.len() - domain.len()].ends_with('.')
+}
+
+/// RFC 6265 section 5.1.4
+fn path_matches(request_path: &str, cookie_path: &str) -> bool {
+    request_path == cookie_path
+        || (request_path.starts_with(cookie_path)
+            && (cookie_path.ends_with('/') || request_path[cookie_path.len()..].starts_with('/')))
+}
+
+/// Directory of the request path, used when Set-Cookie has no Path attribute
+fn default_path(url: &Url) -> String {
+    let path = url.path();
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(index) => path[..index].to_string(),
+    }
+}
+
+fn parse_cookie_date(value: &str) -> Option<i64> {
+    if let Ok(date) = DateTime::parse_from_rfc2822(value) {
+        return Some(date.timestamp());
+    }
+    // Older Netscape-style formats still sent by some servers
+    ["%a, %d-%b-%Y %H:%M:%S GMT", "%A, %d-%b-%y %H:%M:%S GMT", "%a, %d %b %Y %H:%M:%S GMT"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|date| date.and_utc().timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parses_attributes() {
+        let cookie = parse_set_cookie(
+            "sid=abc123; Domain=.example.com; Path=/api; Max-Age=60; Secure; HttpOnly; SameSite=Lax",
+            &url("https://app.example.com/login"),
+            1_000,
+        )
+        .unwrap();
+
+        assert_eq!(cookie.name, "sid");
+        assert_eq!(cookie.value, "abc123");
+        assert_eq!(cookie.domain, "example.com");
+        assert!(!cookie.host_only);
+        assert_eq!(cookie.path, "/api");
+        assert_eq!(cookie.expires, Some(1_060));
+        assert!(cookie.secure && cookie.http_only);
+        assert_eq!(cookie.same_site.as_deref(), Some("Lax"));
+    }
+
+    #[test]
+    fn defaults_to_host_only_and_request_directory() {
+        let cookie = parse_set_cookie("a=1", &url("http://example.com/auth/login"), 0).unwrap();
+        assert_eq!(cookie.domain, "example.com");
+        assert!(cookie.host_only);
+        assert_eq!(cookie.path, "/auth");
+    }
+
+    #[test]
+    fn rejects_foreign_domain() {
+        assert!(parse_set_cookie("a=1; Domain=other.com", &url("http://example.com/"), 0).is_none());
+    }
+
+    #[test]
+    fn parses_http_dates() {
+        assert_eq!(parse_cookie_date("Wed, 21 Oct 2015 07:28:00 GMT"), Some(1_445_412_480));
+        assert_eq!(parse_cookie_date("Wed, 21-Oct-2015 07:28:00 GMT"), Some(1_445_412_480));
+    }
+
+    #[test]
+    fn sends_matching_cookies_and_honors_deletion() {
+        let mut jar = CookieJar::default();
+        let login = url("https://example.com/login");
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("sid=1; Path=/"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("scoped=2; Path=/admin"));
+        jar.store_from_response(&login, &headers);
+
+        let mut request = reqwest::Request::new(reqwest::Method::GET, url("https://example.com/admin/users"));
+        request.headers_mut().insert(COOKIE, HeaderValue::from_static("sid=override"));
+        jar.add_to_request(&mut request);
+        assert_eq!(request.headers()[COOKIE], "sid=override; scoped=2");
+
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("sid=; Max-Age=0"));
+        jar.store_from_response(&url("https://example.com/"), &headers);
+        assert!(jar.values_for(&url("https://example.com/")).is_empty());
+        assert!(jar.changed);
+    }
+
+    #[test]
+    fn merges_concurrent_forks() {
+        let site = url("https://example.com/");
+        let mut jar = CookieJar::default();
+        jar.set(&site, "theme", "dark");
+        jar.set(&site, "sid", "1");
+
+        let mut login = jar.fork();
+        let mut logout = jar.fork();
+        let untouched = jar.fork();
+        login.set(&site, "sid", "2");
+        logout.remove(&site, "theme");
+        jar.merge(logout);
+        jar.merge(login);
+        jar.merge(untouched);
+
+        let values = jar.values_for(&site);
+        assert_eq!(values.get("sid").map(String::as_str), Some("2"));
+        assert!(!values.contains_key("theme"));
+    }
+}
@@ -0,0 +1,267 @@
+use md5::Md5;
+use reqwest::header::{self, HeaderMap};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512_256};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+/// HTTP Digest credentials (RFC 7616). The request is sent once without
+/// credentials and answered when the server responds with a Digest challenge.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DigestAuth {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Algorithm {
+    Md5,
+    Sha256,
+    Sha512_256,
+}
+
+impl Algorithm {
+    fn hash(self, data: &str) -> String {
+        match self {
+            Algorithm::Md5 => hex::encode(Md5::digest(data.as_bytes())),
+            Algorithm::Sha256 => hex::encode(Sha256::digest(data.as_bytes())),
+            Algorithm::Sha512_256 => hex::encode(Sha512_256::digest(data.as_bytes())),
+        }
+    }
+
+    fn hash_bytes(self, data: &[u8]) -> String {
+        match self {
+            Algorithm::Md5 => hex::encode(Md5::digest(data)),
+            Algorithm::Sha256 => hex::encode(Sha256::digest(data)),
+            Algorithm::Sha512_256 => hex::encode(Sha512_256::digest(data)),
+        }
+    }
+}
+
+/// A Digest challenge from `WWW-Authenticate`
+#[derive(Debug, Clone)]
+struct Challenge {
+    params: HashMap<String, String>,
+    algorithm: Algorithm,
+    session: bool,
+}
+
+impl Challenge {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(|s| s.as_str())
+    }
+}
+
+/// The `Authorization` header answering the strongest Digest challenge in
+/// `headers`, or None when the server did not send one we support
+pub fn answer(
+    credentials: &DigestAuth,
+    headers: &HeaderMap,
+    method: &str,
+    uri: &str,
+    body: Option<&[u8]>,
+) -> Result<Option<String>, String> {
+    let challenge = headers
+        .get_all(header::WWW_AUTHENTICATE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(parse_challenges)
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("digest"))
+        .filter_map(|(_, params)| to_challenge(params))
+        // Prefer the strongest algorithm the server offers
+        .max_by_key(|c| match c.algorithm {
+            Algorithm::Md5 => 0,
+            Algorithm::Sha256 => 1,
+            Algorithm::Sha512_256 => 2,
+        });
+    let Some(challenge) = challenge else {
+        return Ok(None);
+    };
+
+    let cnonce = hex::encode(rand::random::<[u8; 16]>());
+    authorization(credentials, &challenge, method, uri, body, &cnonce, 1).map(Some)
+}
+
+fn to_challenge(params: HashMap<String, String>) -> Option<Challenge> {
+    let name = params.get("algorithm").map(|a| a.to_uppercase()).unwrap_or_else(|| "MD5".to_string());
+    let (name, session) = match name.strip_suffix("-SESS") {
+        Some(name) => (name.to_string(), true),
+        None => (name, false),
+    };
+    let algorithm = match name.as_str() {
+        "MD5" => Algorithm::Md5,
+        "SHA-256" => Algorithm::Sha256,
+        "SHA-512-256" => Algorithm::Sha512_256,
+        _ => return None,
+    };
+    params.contains_key("nonce").then_some(Challenge {
+        params,
+        algorithm,
+        session,
+    })
+}
+
+fn authorization(
+    credentials: &DigestAuth,
+    challenge: &Challenge,
+    method: &str,
+    uri: &str,
+    body: Option<&[u8]>,
+    cnonce: &str,
+    nc: u32,
+) -> Result<String, String> {
+    let algorithm = challenge.algorithm;
+    let realm = challenge.param("realm").unwrap_or_default();
+    let nonce = challenge.param("nonce").unwrap_or_default();
+
+    // "auth" is preferred; "auth-int" needs the body, which streaming uploads do not have
+    let offered: Vec<&str> = challenge
+        .param("qop")
+        .map(|q| q.split(',').map(|s| s.trim()).collect())
+        .unwrap_or_default();
+    let qop = if offered.contains(&"auth") {
+        Some("auth")
+    } else if offered.contains(&"auth-int") {
+        body.map(|_| "auth-int")
+    } else if offered.is_empty() {
+        None
+    } else {
+        return Err(format!("Unsupported Digest qop: {}", offered.join(", ")));
+    };
+
+    let mut ha1 = algorithm.hash(&format!("{}:{}:{}", credentials.username, realm, credentials.password));
+    if challenge.session {
+        ha1 = algorithm.hash(&format!("{}:{}:{}", ha1, nonce, cnonce));
+    }
+    let ha2 = match qop {
+        Some("auth-int") => algorithm.hash(&format!(
+            "{}:{}:{}",
+            method,
+            uri,
+            algorithm.hash_bytes(body.unwrap_or_default())
+        )),
+        _ => algorithm.hash(&format!("{}:{}", method, uri)),
+    };
+    let nc = format!("{:08x}", nc);
+    let response = match qop {
+        Some(qop) => algorithm.hash(&format!("{}:{}:{}:{}:{}:{}", ha1, nonce, nc, cnonce, qop, ha2)),
+        None => algorithm.hash(&format!("{}:{}:{}", ha1, nonce, ha2)),
+    };
+
+    let userhash = challenge.param("userhash").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+    let username = if userhash {
+        algorithm.hash(&format!("{}:{}", credentials.username, realm))
+    } else {
+        credentials.username.clone()
+    };
+    let algorithm_name = challenge.param("algorithm").unwrap_or("MD5");
+
+    let mut header = format!(
+        "Digest username={}, realm={}, uri={}, algorithm={}, nonce={}",
+        quote(&username),
+        quote(realm),
+        quote(uri),
+        algorithm_name,
+        quote(nonce)
+    );
+    if let Some(qop) = qop {
+        header.push_str(&format!(", nc={}, cnonce={}, qop={}", nc, quote(cnonce), qop));
+    }
+    header.push_str(&format!(", response={}", quote(&response)));
+    if let Some(opaque) = challenge.param("opaque") {
+        header.push_str(&format!(", opaque={}", quote(opaque)));
+    }
+    if userhash {
+        header.push_str(", userhash=true");
+    }
+    Ok(header)
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Split a `WWW-Authenticate` value into challenges (a header may carry several)
+fn parse_challenges(value: &str) -> Vec<(String, HashMap<String, String>)> {
+    let mut challenges: Vec<(String, HashMap<String, String>)> = Vec::new();
+    let mut rest = value.trim();
+    while !rest.is_empty() {
+        let end = rest.find([' ', ',', '=']).unwrap_or(rest.len());
+        if end == 0 {
+            rest = rest[1..].trim_start();
+            continue;
+        }
+        let token = &rest[..end];
+        let after = rest[end..].trim_start();
+
+        let Some(value_part) = after.strip_prefix('=') else {
+            // An auth scheme starts a new challenge
+            challenges.push((token.to_string(), HashMap::new()));
+            rest = after;
+            continue;
+        };
+        let value_part = value_part.trim_start();
+        let (value, remaining) = match value_part.strip_prefix('"') {
+            Some(quoted) => {
+                let mut value = String::new();
+                let mut escaped = false;
+                let mut consumed = quoted.len();
+                for (i, c) in quoted.char_indices() {
+                    match c {
+                        _ if escaped => {
+                            value.push(c);
+                            escaped = false;
+                        }
+                        '\\' => escaped = true,
+                        '"' => {
+                            consumed = i + 1;
+                            break;
+                        }
+                        _ => value.push(c),
+                    }
+                }
+                (value, &quoted[consumed..])
+            }
+            None => {
+                let end = value_part.find(',').unwrap_or(value_part.len());
+                (value_part[..end].trim().to_string(), &value_part[end..])
+            }
+        };
+        if let Some((_, params)) = challenges.last_mut() {
+            params.insert(token.to_lowercase(), value);
+        }
+        rest = remaining.trim_start();
+    }
+    challenges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Example from RFC 7616 section 3.9.1
+    const CHALLENGES: &str = r#"Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=SHA-256, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS", Digest realm="http-auth@example.org", qop="auth, auth-int", algorithm=MD5, nonce="7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v", opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#;
+
+    #[test]
+    fn answers_rfc_7616_example() {
+        let credentials = DigestAuth {
+            username: "Mufasa".to_string(),
+            password: "Circle of Life".to_string(),
+        };
+        let challenges: Vec<Challenge> = parse_challenges(CHALLENGES)
+            .into_iter()
+            .filter_map(|(_, params)| to_challenge(params))
+            .collect();
+        assert_eq!(challenges.len(), 2);
+
+        let cnonce = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+        let sha256 = authorization(&credentials, &challenges[0], "GET", "/dir/index.html", None, cnonce, 1).unwrap();
+        assert!(sha256.contains(r#"response="753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1""#));
+        assert!(sha256.contains(r#"opaque="FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS""#));
+
+        let md5 = authorization(&credentials, &challenges[1], "GET", "/dir/index.html", None, cnonce, 1).unwrap();
+        assert!(md5.contains(r#"response="8ca523f5e9506fed4657c9700eebdbec""#));
+        assert!(md5.contains("qop=auth,"));
+    }
+}
@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
+use tauri::Manager;
+
+use crate::auth::{self, RequestAuth};
+use crate::cancel;
+use crate::http_client;
+use crate::storage::Storage;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GraphQLResponse {
@@ -35,16 +42,18 @@ struct GraphQLRequest {
 
 #[tauri::command]
 pub async fn send_graphql_request(
+    app: tauri::AppHandle,
     url: String,
     headers: HashMap<String, String>,
     query: String,
     variables: Option<String>,
     operation_name: Option<String>,
+    workspace_id: Option<String>,
+    request_id: Option<String>,
+    auth: Option<RequestAuth>,
 ) -> Result<GraphQLResponse, String> {
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .build()
-        .map_err(|e| e.to_string())?;
+    let storage = app.state::<Arc<Storage>>();
+    let client = http_client::client_for_workspace(&storage, workspace_id.as_deref(), &url).await?;
 
     // Parse variables if provided
     let variables_json: Option<Value> = match variables {
@@ -75,16 +84,18 @@ pub async fn send_graphql_request(
         request = request.header(&key, &value);
     }
 
+    let request = request.json(&gql_request).build().map_err(|e| e.to_string())?;
+
     let start = Instant::now();
-    let response = request
-        .json(&gql_request)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-    
-    let elapsed = start.elapsed().as_millis() as u64;
-
-    let body: Value = response.json().await.map_err(|e| e.to_string())?;
+    let (elapsed, body) = cancel::cancellable(request_id.as_deref(), async {
+        let response = auth::execute(&client, request, auth.as_ref()).await?;
+
+        let elapsed = start.elapsed().as_millis() as u64;
+
+        let body: Value = response.json().await.map_err(|e| e.to_string())?;
+        Ok((elapsed, body))
+    })
+    .await?;
 
     let data = body.get("data").cloned();
     let errors: Option<Vec<GraphQLError>> = body
@@ -1,6 +1,20 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tonic::transport::Channel;
+use std::sync::Arc;
+use std::time::Duration;
+use hyper_util::rt::TokioIo;
+use tauri::Manager;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::TlsConnector;
+use tonic::transport::{Channel, Endpoint};
+use url::Url;
+
+use crate::cancel;
+use crate::http_client::{self, HttpClientSettings};
+use crate::proxy;
+use crate::storage::Storage;
+use crate::tls;
 
 // For reflection
 use tonic_reflection::pb::v1::{
@@ -65,7 +79,11 @@ pub struct GrpcCallResponse {
 
 /// Discover services using gRPC server reflection
 #[tauri::command]
-pub async fn grpc_discover_services(url: String) -> Result<GrpcDiscoveryResult, String> {
+pub async fn grpc_discover_services(
+    app: tauri::AppHandle,
+    url: String,
+    workspace_id: Option<String>,
+) -> Result<GrpcDiscoveryResult, String> {
     // Normalize URL - gRPC uses http:// for plaintext, https:// for TLS
     let url = url
         .trim()
@@ -78,12 +96,15 @@ pub async fn grpc_discover_services(url: String) -> Result<GrpcDiscoveryResult,
         url
     };
     
+    let storage = app.state::<Arc<Storage>>();
+    let settings = http_client::workspace_settings(&storage, workspace_id.as_deref());
+    
     // Connect to the server with timeout
-    let channel = Channel::from_shared(url.clone())
+    let endpoint = Endpoint::from_shared(url.clone())
         .map_err(|e| format!("Invalid URL: {}", e))?
-        .connect_timeout(std::time::Duration::from_secs(5))
-        .timeout(std::time::Duration::from_secs(10))
-        .connect()
+        .connect_timeout(Duration::from_secs(5))
+        .timeout(Duration::from_secs(10));
+    let channel = connect_channel(endpoint, &settings)
         .await
         .map_err(|e| format!("Failed to connect to {}: {}. Make sure the server is running and the port is correct.", url, e))?;
     
@@ -129,9 +150,9 @@ pub async fn grpc_discover_services(url: String) -> Result<GrpcDiscoveryResult,
     
     for service_name in service_names {
         // Request file descriptor for this service
-        let channel = Channel::from_shared(url.clone())
-            .map_err(|e| format!("Invalid URL: {}", e))?
-            .connect()
+        let endpoint = Endpoint::from_shared(url.clone())
+            .map_err(|e| format!("Invalid URL: {}", e))?;
+        let channel = connect_channel(endpoint, &settings)
             .await
             .map_err(|e| format!("Failed to connect: {}", e))?;
         
@@ -261,14 +282,36 @@ async fn get_file_descriptors_via_reflection(channel: Channel, service: &str) ->
 /// When `proto_content` is provided, uses protox to compile the proto and get type
 /// descriptors locally instead of relying on server reflection. This allows calling
 /// gRPC servers that don't have reflection enabled.
+/// With a `request_id`, the call can be aborted with `cancel_request`.
 #[tauri::command]
 pub async fn grpc_call(
+    app: tauri::AppHandle,
     url: String,
     service: String,
     method: String,
     message: String,
     metadata: HashMap<String, String>,
     proto_content: Option<String>,
+    workspace_id: Option<String>,
+    request_id: Option<String>,
+) -> Result<GrpcCallResponse, String> {
+    cancel::cancellable(
+        request_id.as_deref(),
+        call(app, url, service, method, message, metadata, proto_content, workspace_id),
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn call(
+    app: tauri::AppHandle,
+    url: String,
+    service: String,
+    method: String,
+    message: String,
+    metadata: HashMap<String, String>,
+    proto_content: Option<String>,
+    workspace_id: Option<String>,
 ) -> Result<GrpcCallResponse, String> {
     let start = std::time::Instant::now();
     
@@ -288,12 +331,8 @@ pub async fn grpc_call(
     let request_json: serde_json::Value = serde_json::from_str(&message)
         .map_err(|e| format!("Invalid JSON message: {}", e))?;
     
-    // Connect to the server
-    let channel = Channel::from_shared(url.clone())
-        .map_err(|e| format!("Invalid URL: {}", e))?
-        .connect()
-        .await
-        .map_err(|e| format!("Failed to connect: {}", e))?;
+    let storage = app.state::<Arc<Storage>>();
+    let settings = http_client::workspace_settings(&storage, workspace_id.as_deref());
     
     // Get file descriptors either from proto_content (protox) or server reflection
     let file_descriptors = if let Some(proto) = &proto_content {
@@ -301,7 +340,12 @@ pub async fn grpc_call(
         compile_proto_to_descriptors(proto)?
     } else {
         // Fall back to server reflection
-        get_file_descriptors_via_reflection(channel.clone(), &service).await?
+        let endpoint = Endpoint::from_shared(url.clone())
+            .map_err(|e| format!("Invalid URL: {}", e))?;
+        let channel = connect_channel(endpoint, &settings)
+            .await
+            .map_err(|e| format!("Failed to connect: {}", e))?;
+        get_file_descriptors_via_reflection(channel, &service).await?
     };
     
     // Find the method and its input/output types
@@ -354,7 +398,7 @@ pub async fn grpc_call(
     let path = format!("/{}/{}", service, method);
     
     // Use hyper for raw gRPC call
-    let response = make_raw_grpc_call(&url, &path, request_bytes, metadata).await?;
+    let response = make_raw_grpc_call(&url, &path, request_bytes, metadata, &settings).await?;
     
     // Decode protobuf response(s) to JSON
     // For streaming responses, we may have multiple messages
@@ -914,17 +958,62 @@ fn decode_varint(data: &[u8], start: usize) -> Option<(u64, usize)> {
     Some((result, pos))
 }
 
+/// A plaintext or TLS connection to a gRPC server
+trait GrpcIo: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> GrpcIo for T {}
+
+/// Open a connection to the server of `url`, negotiating TLS (with the workspace's
+/// CA roots, client certificate and verification mode) for https:// URLs
+async fn connect_stream(url: &Url, settings: &HttpClientSettings) -> Result<Box<dyn GrpcIo>, String> {
+    let tcp = proxy::connect(url, None, settings.connect_timeout_ms).await?;
+    let _ = tcp.set_nodelay(true);
+    if url.scheme() != "https" {
+        return Ok(Box::new(tcp));
+    }
+
+    let host = url.host_str().ok_or("No host in URL")?;
+    let server_name = ServerName::try_from(host.trim_start_matches('[').trim_end_matches(']').to_string())
+        .map_err(|e| format!("Invalid host name {}: {}", host, e))?;
+    let identity = settings.tls.identity_for(url).await?;
+    let config = tls::rustls_config(settings.accept_invalid_certs, &settings.tls, identity.as_deref(), &[b"h2"])?;
+    let stream = TlsConnector::from(config)
+        .connect(server_name, tcp)
+        .await
+        .map_err(|e| format!("TLS handshake failed: {}", e))?;
+    Ok(Box::new(stream))
+}
+
+/// Connect a tonic channel through `connect_stream`
+async fn connect_channel(endpoint: Endpoint, settings: &HttpClientSettings) -> Result<Channel, String> {
+    let settings = settings.clone();
+    let connector = tower::service_fn(move |uri: hyper::Uri| {
+        let settings = settings.clone();
+        async move {
+            let url = Url::parse(&uri.to_string()).map_err(std::io::Error::other)?;
+            connect_stream(&url, &settings)
+                .await
+                .map(TokioIo::new)
+                .map_err(std::io::Error::other)
+        }
+    });
+    endpoint
+        .connect_with_connector(connector)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Make a raw gRPC call using hyper
 async fn make_raw_grpc_call(
     url: &str,
     path: &str,
     body: Vec<u8>,
     metadata: HashMap<String, String>,
+    settings: &HttpClientSettings,
 ) -> Result<RawGrpcResponse, String> {
     use hyper::Request;
     use hyper::body::Bytes;
     use http_body_util::{Full, BodyExt};
-    use hyper_util::client::legacy::Client;
     use hyper_util::rt::TokioExecutor;
     
     // Parse URL
@@ -958,14 +1047,19 @@ async fn make_raw_grpc_call(
         .body(Full::new(Bytes::from(grpc_frame)))
         .map_err(|e| format!("Failed to build request: {}", e))?;
     
-    // Create client with HTTP/2
-    let client: Client<_, Full<Bytes>> = Client::builder(TokioExecutor::new())
-        .http2_only(true)
-        .build_http();
+    // Open an HTTP/2 connection
+    let target = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let stream = connect_stream(&target, settings).await?;
+    let (mut sender, connection) = hyper::client::conn::http2::handshake(TokioExecutor::new(), TokioIo::new(stream))
+        .await
+        .map_err(|e| format!("HTTP/2 handshake failed: {}", e))?;
+    tokio::spawn(async move {
+        let _ = connection.await;
+    });
     
     // Send request
-    let response = client
-        .request(request)
+    let response = sender
+        .send_request(request)
         .await
         .map_err(|e| format!("Request failed: {}", e))?;
     
@@ -0,0 +1,539 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::Manager;
+
+use crate::storage::{HistoryItem, Storage};
+use crate::test_runner::{TestResult, TestRunSummary};
+use crate::timing::ResponseTiming;
+
+// ============ HAR 1.2 ============
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Har {
+    pub log: HarLog,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarLog {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub creator: HarCreator,
+    #[serde(default)]
+    pub entries: Vec<HarEntry>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarCreator {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    #[serde(default)]
+    pub started_date_time: String,
+    /// Total time in milliseconds
+    #[serde(default)]
+    pub time: f64,
+    pub request: HarRequest,
+    #[serde(default)]
+    pub response: HarResponse,
+    #[serde(default)]
+    pub cache: Value,
+    #[serde(default)]
+    pub timings: HarTimings,
+    #[serde(rename = "serverIPAddress", default, skip_serializing_if = "Option::is_none")]
+    pub server_ip_address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<Value>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub query_string: Vec<HarNameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    #[serde(default)]
+    pub status: u16,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default)]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<Value>,
+    #[serde(default)]
+    pub headers: Vec<HarNameValue>,
+    #[serde(default)]
+    pub content: HarContent,
+    #[serde(rename = "redirectURL", default)]
+    pub redirect_url: String,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HarNameValue {
+    pub name: String,
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<HarNameValue>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// "base64" for binary bodies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+/// Phases in milliseconds; -1 when a phase was not measured
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HarTimings {
+    #[serde(default = "not_measured")]
+    pub blocked: f64,
+    #[serde(default = "not_measured")]
+    pub dns: f64,
+    #[serde(default = "not_measured")]
+    pub connect: f64,
+    #[serde(default)]
+    pub send: f64,
+    #[serde(default)]
+    pub wait: f64,
+    #[serde(default)]
+    pub receive: f64,
+    #[serde(default = "not_measured")]
+    pub ssl: f64,
+}
+
+impl Default for HarTimings {
+    fn default() -> Self {
+        HarTimings {
+            blocked: -1.0,
+            dns: -1.0,
+            connect: -1.0,
+            send: 0.0,
+            wait: 0.0,
+            receive: 0.0,
+            ssl: -1.0,
+        }
+    }
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+fn not_measured() -> f64 {
+    -1.0
+}
+
+impl Har {
+    fn new(entries: Vec<HarEntry>) -> Self {
+        Har {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: "Istek".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries,
+            },
+        }
+    }
+}
+
+impl From<&ResponseTiming> for HarTimings {
+    fn from(timing: &ResponseTiming) -> Self {
+        let ssl = timing.tls_ms.unwrap_or(-1.0);
+        HarTimings {
+            blocked: -1.0,
+            dns: timing.dns_ms.unwrap_or(-1.0),
+            // HAR counts the TLS handshake as part of connect
+            connect: timing.connect_ms.map(|c| c + ssl.max(0.0)).unwrap_or(-1.0),
+            send: 0.0,
+            wait: timing.ttfb_ms,
+            receive: timing.download_ms,
+            ssl,
+        }
+    }
+}
+
+fn iso_time(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
+fn name_values(map: &HashMap<String, String>) -> Vec<HarNameValue> {
+    let mut pairs: Vec<HarNameValue> = map
+        .iter()
+        .map(|(name, value)| HarNameValue {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    pairs.sort_by(|a, b| a.name.cmp(&b.name));
+    pairs
+}
+
+fn header_value<'a>(headers: &'a [HarNameValue], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+}
+
+/// Enabled `{ key, value, enabled }` items of a stored request
+fn enabled_pairs(items: Option<&Value>) -> Vec<HarNameValue> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter(|item| item.get("enabled").and_then(|e| e.as_bool()).unwrap_or(true))
+                .filter_map(|item| {
+                    let name = item.get("key").and_then(|k| k.as_str()).filter(|k| !k.is_empty())?;
+                    let value = item.get("value").and_then(|v| v.as_str()).unwrap_or("");
+                    Some(HarNameValue {
+                        name: name.to_string(),
+                        value: value.to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn query_string(url: &str) -> Vec<HarNameValue> {
+    url::Url::parse(url)
+        .map(|u| {
+            u.query_pairs()
+                .map(|(name, value)| HarNameValue {
+                    name: name.into_owned(),
+                    value: value.into_owned(),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn post_data(body: &str, mime_type: &str) -> Option<HarPostData> {
+    if body.is_empty() {
+        return None;
+    }
+    Some(HarPostData {
+        mime_type: mime_type.to_string(),
+        text: body.to_string(),
+        params: Vec::new(),
+    })
+}
+
+/// The body type of a stored request as a MIME type
+fn body_mime_type(body_type: &str) -> &'static str {
+    match body_type {
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "html" => "text/html",
+        "x-www-form-urlencoded" => "application/x-www-form-urlencoded",
+        "form-data" => "multipart/form-data",
+        _ => "text/plain",
+    }
+}
+
+/// A HAR entry for an HTTP history item (other protocols have none)
+fn history_entry(item: &HistoryItem) -> Option<HarEntry> {
+    let request = &item.request;
+    if request.get("protocol").and_then(|p| p.as_str()).unwrap_or("http") != "http" {
+        return None;
+    }
+    let str_field = |value: &Value, name: &str| value.get(name).and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    // The stored URL excludes the params table
+    let mut url = str_field(request, "url");
+    let params = enabled_pairs(request.get("params"));
+    if !params.is_empty() {
+        let query = params
+            .iter()
+            .map(|p| format!("{}={}", urlencoding::encode(&p.name), urlencoding::encode(&p.value)))
+            .collect::<Vec<_>>()
+            .join("&");
+        url = format!("{}{}{}", url, if url.contains('?') { '&' } else { '?' }, query);
+    }
+
+    let headers = enabled_pairs(request.get("headers"));
+    let body_type = str_field(request, "bodyType");
+    let post = match body_type.as_str() {
+        "none" | "" => None,
+        "x-www-form-urlencoded" => {
+            let fields = enabled_pairs(request.get("urlEncodedData"));
+            let text = fields
+                .iter()
+                .map(|p| format!("{}={}", urlencoding::encode(&p.name), urlencoding::encode(&p.value)))
+                .collect::<Vec<_>>()
+                .join("&");
+            post_data(&text, body_mime_type(&body_type)).map(|data| HarPostData { params: fields, ..data })
+        }
+        _ => {
+            let mime_type = header_value(&headers, "content-type").unwrap_or(body_mime_type(&body_type));
+            post_data(&str_field(request, "body"), mime_type)
+        }
+    };
+
+    let response = item.response.as_ref();
+    let timing = item
+        .timing
+        .clone()
+        .or_else(|| response.and_then(|r| r.get("timing")).and_then(|t| serde_json::from_value(t.clone()).ok()));
+    let time = timing
+        .as_ref()
+        .map(|t| t.total_ms)
+        .or_else(|| response.and_then(|r| r.get("time")).and_then(|t| t.as_f64()))
+        .unwrap_or(0.0);
+
+    let har_response = match response {
+        Some(response) => {
+            let headers: HashMap<String, String> = response
+                .get("headers")
+                .and_then(|h| serde_json::from_value(h.clone()).ok())
+                .unwrap_or_default();
+            let headers = name_values(&headers);
+            let base64 = response.get("bodyBase64").and_then(|b| b.as_str());
+            let content = HarContent {
+                size: response.get("size").and_then(|s| s.as_i64()).unwrap_or(-1),
+                mime_type: header_value(&headers, "content-type").unwrap_or("").to_string(),
+                text: Some(base64.map(|b| b.to_string()).unwrap_or_else(|| str_field(response, "body"))),
+                encoding: base64.map(|_| "base64".to_string()),
+            };
+            HarResponse {
+                status: response.get("status").and_then(|s| s.as_u64()).unwrap_or(0) as u16,
+                status_text: str_field(response, "statusText"),
+                http_version: timing.as_ref().map(|t| t.http_version.clone()).unwrap_or_default(),
+                redirect_url: header_value(&headers, "location").unwrap_or("").to_string(),
+                body_size: content.size,
+                headers,
+                content,
+                ..Default::default()
+            }
+        }
+        None => HarResponse::default(),
+    };
+
+    Some(HarEntry {
+        started_date_time: iso_time(item.timestamp),
+        time,
+        request: HarRequest {
+            method: str_field(request, "method").to_uppercase(),
+            query_string: query_string(&url),
+            url,
+            http_version: har_response.http_version.clone(),
+            headers,
+            body_size: post.as_ref().map(|p| p.text.len() as i64).unwrap_or(0),
+            post_data: post,
+            headers_size: -1,
+            ..Default::default()
+        },
+        response: har_response,
+        cache: Value::Object(Default::default()),
+        server_ip_address: timing.as_ref().and_then(|t| t.remote_ip.clone()),
+        timings: timing.as_ref().map(HarTimings::from).unwrap_or_default(),
+        comment: request.get("name").and_then(|n| n.as_str()).map(|n| n.to_string()),
+    })
+}
+
+/// A HAR entry for one request of a collection run
+fn test_result_entry(result: &TestResult, started: i64) -> HarEntry {
+    let request_headers = result.request_headers.as_ref().map(name_values).unwrap_or_default();
+    let response_headers = result.response_headers.as_ref().map(name_values).unwrap_or_default();
+    let time = result
+        .timing
+        .as_ref()
+        .map(|t| t.total_ms)
+        .or(result.response_time.map(|t| t as f64))
+        .unwrap_or(0.0);
+    let size = result.response_size.map(|s| s as i64).unwrap_or(-1);
+    let post = result.request_body.as_deref().and_then(|body| {
+        post_data(body, header_value(&request_headers, "content-type").unwrap_or("text/plain"))
+    });
+
+    HarEntry {
+        started_date_time: iso_time(started),
+        time,
+        request: HarRequest {
+            method: result.method.to_uppercase(),
+            url: result.url.clone(),
+            http_version: result.timing.as_ref().map(|t| t.http_version.clone()).unwrap_or_default(),
+            query_string: query_string(&result.url),
+            body_size: post.as_ref().map(|p| p.text.len() as i64).unwrap_or(0),
+            headers: request_headers,
+            post_data: post,
+            headers_size: -1,
+            ..Default::default()
+        },
+        response: HarResponse {
+            status: result.response_status.unwrap_or(0),
+            http_version: result.timing.as_ref().map(|t| t.http_version.clone()).unwrap_or_default(),
+            redirect_url: header_value(&response_headers, "location").unwrap_or("").to_string(),
+            content: HarContent {
+                size,
+                mime_type: header_value(&response_headers, "content-type").unwrap_or("").to_string(),
+                text: result.response_body.clone(),
+                encoding: None,
+            },
+            headers: response_headers,
+            headers_size: -1,
+            body_size: size,
+            ..Default::default()
+        },
+        cache: Value::Object(Default::default()),
+        timings: result.timing.as_ref().map(HarTimings::from).unwrap_or_default(),
+        server_ip_address: result.timing.as_ref().and_then(|t| t.remote_ip.clone()),
+        comment: Some(match &result.error {
+            Some(error) => format!("{}: {}", result.request_name, error),
+            None => result.request_name.clone(),
+        }),
+    }
+}
+
+/// HAR for HTTP history items, oldest first
+pub fn from_history(items: &[HistoryItem]) -> Har {
+    let mut items: Vec<&HistoryItem> = items.iter().collect();
+    items.sort_by_key(|item| item.timestamp);
+    Har::new(items.into_iter().filter_map(history_entry).collect())
+}
+
+/// HAR for the results of a collection run that started at `started` (Unix milliseconds)
+pub fn from_test_run(summary: &TestRunSummary, started: i64) -> Har {
+    let mut offset = 0;
+    let entries = summary
+        .results
+        .iter()
+        .map(|result| {
+            let entry = test_result_entry(result, started + offset);
+            offset += entry.time.round() as i64;
+            entry
+        })
+        .collect();
+    Har::new(entries)
+}
+
+// ============ Commands ============
+
+/// Export history as HAR 1.2 JSON (all HTTP items, or those in `ids`)
+#[tauri::command]
+pub async fn export_history_har(
+    app: tauri::AppHandle,
+    ids: Option<Vec<String>>,
+    workspace_id: Option<String>,
+) -> Result<String, String> {
+    let storage = app.state::<Arc<Storage>>();
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+
+    let mut history = storage.get_history(&ws_id)?;
+    if let Some(ids) = ids {
+        history.retain(|item| ids.contains(&item.id));
+    }
+    serde_json::to_string_pretty(&from_history(&history)).map_err(|e| e.to_string())
+}
+
+/// Export the results of a collection run as HAR 1.2 JSON
+#[tauri::command]
+pub async fn export_test_run_har(summary: TestRunSummary, started_at: Option<i64>) -> Result<String, String> {
+    let started = started_at.unwrap_or_else(|| chrono::Utc::now().timestamp_millis() - summary.total_time as i64);
+    serde_json::to_string_pretty(&from_test_run(&summary, started)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_http_history_items() {
+        let item = HistoryItem {
+            id: "1".to_string(),
+            request: serde_json::json!({
+                "protocol": "http",
+                "name": "Create user",
+                "method": "post",
+                "url": "https://api.example.com/users",
+                "headers": [
+                    { "key": "Content-Type", "value": "application/json", "enabled": true },
+                    { "key": "X-Debug", "value": "1", "enabled": false }
+                ],
+                "params": [{ "key": "dry run", "value": "yes", "enabled": true }],
+                "body": "{\"name\":\"a\"}",
+                "bodyType": "json"
+            }),
+            response: Some(serde_json::json!({
+                "status": 201,
+                "statusText": "Created",
+                "headers": { "content-type": "application/json" },
+                "body": "{\"id\":1}",
+                "time": 42,
+                "size": 8
+            })),
+            timing: None,
+            timestamp: 1_700_000_000_000,
+        };
+        let websocket = HistoryItem {
+            request: serde_json::json!({ "protocol": "websocket", "url": "wss://example.com" }),
+            ..item.clone()
+        };
+
+        let har = from_history(&[item, websocket]);
+        assert_eq!(har.log.entries.len(), 1);
+        let entry = &har.log.entries[0];
+        assert_eq!(entry.started_date_time, "2023-11-14T22:13:20.000Z");
+        assert_eq!(entry.request.method, "POST");
+        assert_eq!(entry.request.url, "https://api.example.com/users?dry%20run=yes");
+        assert_eq!(entry.request.query_string[0].name, "dry run");
+        assert_eq!(entry.request.headers.len(), 1);
+        assert_eq!(entry.request.post_data.as_ref().unwrap().mime_type, "application/json");
+        assert_eq!(entry.response.status, 201);
+        assert_eq!(entry.response.content.text.as_deref(), Some("{\"id\":1}"));
+        assert_eq!(entry.time, 42.0);
+    }
+}
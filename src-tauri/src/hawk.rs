@@ -0,0 +1,172 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hmac::{Hmac, Mac};
+use reqwest::header::{self, HeaderValue};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HawkAlgorithm {
+    #[default]
+    Sha256,
+    Sha1,
+}
+
+/// Hawk credentials and options
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HawkAuth {
+    pub id: String,
+    pub key: String,
+    #[serde(default)]
+    pub algorithm: HawkAlgorithm,
+    /// Application-specific data sent with the request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ext: Option<String>,
+    /// Oz application id and delegated-by application id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dlg: Option<String>,
+    /// Also authenticate the body with a payload hash
+    #[serde(default)]
+    pub include_payload_hash: bool,
+}
+
+impl HawkAlgorithm {
+    fn mac(self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            HawkAlgorithm::Sha256 => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC can take key of any size");
+                mac.update(data);
+                mac.finalize().into_bytes().to_vec()
+            }
+            HawkAlgorithm::Sha1 => {
+                let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC can take key of any size");
+                mac.update(data);
+                mac.finalize().into_bytes().to_vec()
+            }
+        }
+    }
+
+    fn hash(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            HawkAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            HawkAlgorithm::Sha1 => Sha1::digest(data).to_vec(),
+        }
+    }
+}
+
+/// Add a Hawk `Authorization` header to a request
+pub fn sign(request: &mut reqwest::Request, credentials: &HawkAuth, ts: i64, nonce: &str) -> Result<(), String> {
+    if credentials.id.is_empty() || credentials.key.is_empty() {
+        return Err("Hawk needs an id and a key".to_string());
+    }
+
+    let hash = if credentials.include_payload_hash {
+        let content_type = request
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        let body = match request.body().map(|b| b.as_bytes()) {
+            Some(Some(bytes)) => bytes,
+            Some(None) => return Err("Hawk payload hashes cannot be computed for streaming bodies".to_string()),
+            None => &[],
+        };
+        Some(payload_hash(credentials.algorithm, content_type, body))
+    } else {
+        None
+    };
+
+    let url = request.url();
+    let resource = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    let port = url.port_or_known_default().unwrap_or_default();
+
+    let ext = credentials.ext.as_deref().unwrap_or("");
+    let mut normalized = format!(
+        "hawk.1.header\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
+        ts,
+        nonce,
+        request.method().as_str().to_uppercase(),
+        resource,
+        host,
+        port,
+        hash.as_deref().unwrap_or(""),
+        ext.replace('\\', "\\\\").replace('\n', "\\n")
+    );
+    if let Some(app) = &credentials.app {
+        normalized.push_str(&format!("{}\n{}\n", app, credentials.dlg.as_deref().unwrap_or("")));
+    }
+    let mac = STANDARD.encode(credentials.algorithm.mac(credentials.key.as_bytes(), normalized.as_bytes()));
+
+    let mut value = format!("Hawk id=\"{}\", ts=\"{}\", nonce=\"{}\"", escape(&credentials.id), ts, nonce);
+    if let Some(hash) = &hash {
+        value.push_str(&format!(", hash=\"{}\"", hash));
+    }
+    if !ext.is_empty() {
+        value.push_str(&format!(", ext=\"{}\"", escape(ext)));
+    }
+    value.push_str(&format!(", mac=\"{}\"", mac));
+    if let Some(app) = &credentials.app {
+        value.push_str(&format!(", app=\"{}\"", escape(app)));
+        if let Some(dlg) = &credentials.dlg {
+            value.push_str(&format!(", dlg=\"{}\"", escape(dlg)));
+        }
+    }
+
+    let value = HeaderValue::from_str(&value).map_err(|_| "Invalid Hawk header value".to_string())?;
+    request.headers_mut().insert(header::AUTHORIZATION, value);
+    Ok(())
+}
+
+/// A random nonce for a Hawk request
+pub fn nonce() -> String {
+    use rand::distr::{Alphanumeric, SampleString};
+    Alphanumeric.sample_string(&mut rand::rng(), 8)
+}
+
+fn payload_hash(algorithm: HawkAlgorithm, content_type: &str, body: &[u8]) -> String {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    let mut payload = format!("hawk.1.payload\n{}\n", mime).into_bytes();
+    payload.extend_from_slice(body);
+    payload.push(b'\n');
+    STANDARD.encode(algorithm.hash(&payload))
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_hawk_readme_example() {
+        let credentials = HawkAuth {
+            id: "dh37fgj492je".to_string(),
+            key: "werxhqb98rpaxn39848xrunpaw3489ruxnpa98w4rxn".to_string(),
+            ext: Some("some-app-ext-data".to_string()),
+            ..Default::default()
+        };
+        let url = "http://example.com:8000/resource/1?b=1&a=2".parse().unwrap();
+        let mut request = reqwest::Request::new(reqwest::Method::GET, url);
+        sign(&mut request, &credentials, 1353832234, "j4h3g2").unwrap();
+        assert_eq!(
+            request.headers()[header::AUTHORIZATION],
+            "Hawk id=\"dh37fgj492je\", ts=\"1353832234\", nonce=\"j4h3g2\", ext=\"some-app-ext-data\", mac=\"6R4rV5iE+NPoym+WwjeHzjAGXUtLNIxmo1vpMofpLAE=\""
+        );
+
+        assert_eq!(
+            payload_hash(HawkAlgorithm::Sha256, "text/plain", b"Thank you for flying Hawk"),
+            "Yi9LfIIFRtBEPt74PVmbTF/xVAwPn7ub15ePICfgnuY="
+        );
+    }
+}
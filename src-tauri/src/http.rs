@@ -1,6 +1,19 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::time::Instant;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::Manager;
+
+use crate::auth::{self, RequestAuth};
+use crate::body;
+use crate::cancel;
+use crate::cookies::CookieJar;
+use crate::http_client::{self, HttpClientSettings};
+use crate::oauth;
+use crate::redirect::{RedirectHop, RedirectOptions};
+use crate::retry::{RetryAttempt, RetryPolicy};
+use crate::storage::Storage;
+use crate::timing::{ResponseTiming, TimedResponse};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ParsedCurlRequest {
@@ -325,9 +338,28 @@ pub struct HttpResponse {
     #[serde(rename = "statusText")]
     pub status_text: String,
     pub headers: HashMap<String, String>,
+    /// The body decoded as text (empty for binary bodies and saved downloads)
     pub body: String,
+    /// The raw body, set when it is binary or did not decode cleanly as text
+    #[serde(rename = "bodyBase64")]
+    pub body_base64: Option<String>,
+    #[serde(rename = "contentType")]
+    pub content_type: Option<String>,
+    /// The charset the body was decoded with
+    pub charset: Option<String>,
+    #[serde(rename = "isBinary")]
+    pub is_binary: bool,
+    /// The file the body was written to instead of being returned
+    #[serde(rename = "savedTo")]
+    pub saved_to: Option<String>,
     pub time: u64,
-    pub size: usize,
+    pub size: u64,
+    pub timing: ResponseTiming,
+    /// Redirects followed before this response, in order
+    pub redirects: Vec<RedirectHop>,
+    /// Every attempt when a retry policy applies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<RetryAttempt>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -757,18 +789,27 @@ fn generate_ruby(method: &str, url: &str, headers: &[SimpleKeyValue], body: &Opt
     code.push_str("http = Net::HTTP.new(uri.host, uri.port)\n");
     code.push_str("http.use_ssl = uri.scheme == 'https'\n\n");
     
-    let class_name = match method.to_uppercase().as_str() {
-        "GET" => "Get",
-        "POST" => "Post",
-        "PUT" => "Put",
-        "PATCH" => "Patch",
-        "DELETE" => "Delete",
-        "HEAD" => "Head",
-        "OPTIONS" => "Options",
-        _ => "Get",
+    // Standard methods in their canonical case, custom verbs as typed (methods are case-sensitive)
+    let method = http_client::parse_method(method).map(|m| m.to_string()).unwrap_or_else(|_| method.to_string());
+    let class_name = match method.as_str() {
+        "GET" => Some("Get"),
+        "POST" => Some("Post"),
+        "PUT" => Some("Put"),
+        "PATCH" => Some("Patch"),
+        "DELETE" => Some("Delete"),
+        "HEAD" => Some("Head"),
+        "OPTIONS" => Some("Options"),
+        _ => None,
     };
     
-    code.push_str(&format!("request = Net::HTTP::{}::new(uri.request_uri)\n", class_name));
+    match class_name {
+        Some(class_name) => code.push_str(&format!("request = Net::HTTP::{}::new(uri.request_uri)\n", class_name)),
+        // Other methods (PURGE, PROPFIND, custom verbs) go through the generic request class
+        None => code.push_str(&format!(
+            "request = Net::HTTPGenericRequest.new('{}', true, true, uri.request_uri)\n",
+            method
+        )),
+    }
     
     for h in headers {
         code.push_str(&format!("request['{}'] = '{}'\n", h.key, h.value.replace("'", "\\'")));
@@ -789,17 +830,26 @@ fn generate_ruby(method: &str, url: &str, headers: &[SimpleKeyValue], body: &Opt
 
 #[tauri::command]
 pub async fn send_http_request(
+    app: tauri::AppHandle,
     method: String,
     url: String,
     headers: HashMap<String, String>,
     params: HashMap<String, String>,
     body: Option<String>,
     body_type: String,
+    workspace_id: Option<String>,
+    detailed_timing: Option<bool>,
+    redirects: Option<RedirectOptions>,
+    save_to: Option<String>,
+    request_id: Option<String>,
+    auth_profile_id: Option<String>,
+    auth: Option<RequestAuth>,
+    retry: Option<RetryPolicy>,
 ) -> Result<HttpResponse, String> {
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(true) // For testing with self-signed certs
-        .build()
-        .map_err(|e| e.to_string())?;
+    let storage = app.state::<Arc<Storage>>();
+    let workspace_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let settings = http_client::workspace_settings(&storage, workspace_id.as_deref());
+    let client = http_client::get_client(&settings)?;
 
     // Build URL with query params
     let mut request_url = url.clone();
@@ -818,20 +868,17 @@ pub async fn send_http_request(
     }
 
     // Build request
-    let mut request = match method.to_uppercase().as_str() {
-        "GET" => client.get(&request_url),
-        "POST" => client.post(&request_url),
-        "PUT" => client.put(&request_url),
-        "PATCH" => client.patch(&request_url),
-        "DELETE" => client.delete(&request_url),
-        "HEAD" => client.head(&request_url),
-        "OPTIONS" => client.request(reqwest::Method::OPTIONS, &request_url),
-        _ => return Err(format!("Unsupported method: {}", method)),
-    };
+    let mut request = client.request(http_client::parse_method(&method)?, &request_url);
 
-    // Add headers
+    // Add headers (the auth profile's token replaces any Authorization header)
+    let authorization = oauth::authorization_for(&storage, workspace_id.as_deref(), auth_profile_id.as_deref()).await?;
     for (key, value) in headers {
-        request = request.header(&key, &value);
+        if authorization.is_none() || !key.eq_ignore_ascii_case("authorization") {
+            request = request.header(&key, &value);
+        }
+    }
+    if let Some(authorization) = authorization {
+        request = request.header(reqwest::header::AUTHORIZATION, authorization);
     }
 
     // Add body
@@ -860,40 +907,94 @@ pub async fn send_http_request(
         }
     }
 
-    // Send request and measure time
-    let start = Instant::now();
-    let response = request.send().await.map_err(|e| e.to_string())?;
-    let elapsed = start.elapsed().as_millis() as u64;
+    // Send request and measure each phase
+    let request = request.build().map_err(|e| e.to_string())?;
+    let ((response, redirects), attempts) = send_with_cookies(
+        &storage,
+        workspace_id.as_deref(),
+        &settings,
+        request,
+        detailed_timing.unwrap_or(false),
+        &redirects.unwrap_or_default(),
+        save_to.as_deref().map(Path::new),
+        request_id.as_deref(),
+        auth.as_ref(),
+        retry.as_ref(),
+    )
+    .await?;
+
+    Ok(to_http_response(response, redirects, save_to, attempts))
+}
+
+/// Send a request with the workspace cookie jar: matching cookies are added to
+/// the request (and every redirect) and cookies set by the responses are stored.
+/// With a `request_id`, the request can be aborted with `cancel_request`; with
+/// a `retry` policy, every attempt is returned as well.
+#[allow(clippy::too_many_arguments)]
+async fn send_with_cookies(
+    storage: &Storage,
+    workspace_id: Option<&str>,
+    settings: &HttpClientSettings,
+    request: reqwest::Request,
+    detailed_timing: bool,
+    redirects: &RedirectOptions,
+    save_to: Option<&Path>,
+    request_id: Option<&str>,
+    auth: Option<&RequestAuth>,
+    retry: Option<&RetryPolicy>,
+) -> Result<((TimedResponse, Vec<RedirectHop>), Vec<RetryAttempt>), String> {
+    let mut cookie_jar = CookieJar::load(storage, workspace_id);
+    let sent = cancel::cancellable(request_id, async {
+        let (sent, attempts) =
+            auth::send(settings, request, detailed_timing, redirects, &mut cookie_jar, save_to, retry, auth).await;
+        sent.map(|sent| (sent, attempts))
+    })
+    .await;
+    // Cookies set before a failed hop are still kept
+    cookie_jar.save(storage, workspace_id)?;
+    sent
+}
 
-    // Get response details
-    let status = response.status().as_u16();
+fn to_http_response(
+    response: TimedResponse,
+    redirects: Vec<RedirectHop>,
+    saved_to: Option<String>,
+    attempts: Vec<RetryAttempt>,
+) -> HttpResponse {
+    let status = response.status.as_u16();
     let status_text = response
-        .status()
+        .status
         .canonical_reason()
         .unwrap_or("Unknown")
         .to_string();
 
     // Get headers
     let mut response_headers = HashMap::new();
-    for (key, value) in response.headers() {
+    for (key, value) in &response.headers {
         if let Ok(v) = value.to_str() {
             response_headers.insert(key.to_string(), v.to_string());
         }
     }
 
-    // Get body
-    let body_bytes = response.bytes().await.map_err(|e| e.to_string())?;
-    let size = body_bytes.len();
-    let body = String::from_utf8_lossy(&body_bytes).to_string();
+    // Decode the body according to its Content-Type
+    let body = body::decode(&response.headers, &response.body);
 
-    Ok(HttpResponse {
+    HttpResponse {
         status,
         status_text,
         headers: response_headers,
-        body,
-        time: elapsed,
-        size,
-    })
+        body: body.text,
+        body_base64: body.base64.filter(|_| saved_to.is_none()),
+        content_type: body.content_type,
+        charset: body.charset,
+        is_binary: body.is_binary,
+        saved_to,
+        time: response.timing.total_ms.round() as u64,
+        size: response.size,
+        timing: response.timing,
+        redirects,
+        attempts,
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -908,16 +1009,22 @@ pub struct FormDataField {
 
 #[tauri::command]
 pub async fn send_multipart_request(
+    app: tauri::AppHandle,
     method: String,
     url: String,
     headers: HashMap<String, String>,
     params: HashMap<String, String>,
     form_fields: Vec<FormDataField>,
+    workspace_id: Option<String>,
+    redirects: Option<RedirectOptions>,
+    request_id: Option<String>,
+    auth_profile_id: Option<String>,
+    auth: Option<RequestAuth>,
 ) -> Result<HttpResponse, String> {
-    let client = reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .build()
-        .map_err(|e| e.to_string())?;
+    let storage = app.state::<Arc<Storage>>();
+    let workspace_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let settings = http_client::workspace_settings(&storage, workspace_id.as_deref());
+    let client = http_client::get_client(&settings)?;
 
     // Build URL with query params
     let mut request_url = url.clone();
@@ -972,55 +1079,44 @@ pub async fn send_multipart_request(
     }
 
     // Build request
-    let mut request = match method.to_uppercase().as_str() {
-        "POST" => client.post(&request_url),
-        "PUT" => client.put(&request_url),
-        "PATCH" => client.patch(&request_url),
-        _ => return Err(format!("Multipart form not supported for method: {}", method)),
-    };
+    let method = http_client::parse_method(&method)?;
+    if matches!(method, reqwest::Method::GET | reqwest::Method::HEAD) {
+        return Err(format!("Multipart form not supported for method: {}", method));
+    }
+    let mut request = client.request(method, &request_url);
 
-    // Add headers (except Content-Type which is set by multipart)
+    // Add headers (except Content-Type which is set by multipart, and Authorization
+    // when an auth profile provides it)
+    let authorization = oauth::authorization_for(&storage, workspace_id.as_deref(), auth_profile_id.as_deref()).await?;
     for (key, value) in headers {
-        if key.to_lowercase() != "content-type" {
+        let key_lower = key.to_lowercase();
+        if key_lower != "content-type" && (authorization.is_none() || key_lower != "authorization") {
             request = request.header(&key, &value);
         }
     }
+    if let Some(authorization) = authorization {
+        request = request.header(reqwest::header::AUTHORIZATION, authorization);
+    }
 
     // Add multipart form
     request = request.multipart(form);
 
-    // Send request and measure time
-    let start = Instant::now();
-    let response = request.send().await.map_err(|e| e.to_string())?;
-    let elapsed = start.elapsed().as_millis() as u64;
+    // Multipart bodies are streamed, so they always go through the pool
+    let request = request.build().map_err(|e| e.to_string())?;
+    // Streamed bodies cannot be resent, so there is no retry policy
+    let ((response, redirects), _) = send_with_cookies(
+        &storage,
+        workspace_id.as_deref(),
+        &settings,
+        request,
+        false,
+        &redirects.unwrap_or_default(),
+        None,
+        request_id.as_deref(),
+        auth.as_ref(),
+        None,
+    )
+    .await?;
 
-    // Get response details
-    let status = response.status().as_u16();
-    let status_text = response
-        .status()
-        .canonical_reason()
-        .unwrap_or("Unknown")
-        .to_string();
-
-    // Get headers
-    let mut response_headers = HashMap::new();
-    for (key, value) in response.headers() {
-        if let Ok(v) = value.to_str() {
-            response_headers.insert(key.to_string(), v.to_string());
-        }
-    }
-
-    // Get body
-    let body_bytes = response.bytes().await.map_err(|e| e.to_string())?;
-    let size = body_bytes.len();
-    let body = String::from_utf8_lossy(&body_bytes).to_string();
-
-    Ok(HttpResponse {
-        status,
-        status_text,
-        headers: response_headers,
-        body,
-        time: elapsed,
-        size,
-    })
+    Ok(to_http_response(response, redirects, None, Vec::new()))
 }
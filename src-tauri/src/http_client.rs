@@ -0,0 +1,208 @@
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+
+use crate::proxy::ProxySettings;
+use crate::storage::Storage;
+use crate::tls::{self, ClientIdentity, TlsSettings};
+
+// Shared reqwest clients, keyed by the settings they were built with.
+// Reusing a client keeps its connection pool, keep-alive connections and
+// TLS sessions alive between requests to the same host.
+pub static HTTP_CLIENTS: Lazy<DashMap<String, reqwest::Client>> = Lazy::new(DashMap::new);
+
+/// Per-workspace HTTP client configuration (stored in workspace.yaml)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpClientSettings {
+    /// Total request timeout in milliseconds (0 = no timeout)
+    #[serde(default)]
+    pub timeout_ms: u64,
+    /// TCP connect timeout in milliseconds (0 = no timeout)
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    /// How long idle pooled connections are kept open, in seconds (0 = forever)
+    #[serde(default = "default_pool_idle_timeout_secs")]
+    pub pool_idle_timeout_secs: u64,
+    /// Maximum idle connections kept per host
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// TCP keep-alive interval in seconds (0 = disabled)
+    #[serde(default = "default_tcp_keepalive_secs")]
+    pub tcp_keepalive_secs: u64,
+    /// Accept self-signed and otherwise invalid certificates
+    #[serde(default = "default_true")]
+    pub accept_invalid_certs: bool,
+    /// Proxy configuration (stored separately in workspace.yaml, see `Storage::get_proxy_settings`)
+    #[serde(skip)]
+    pub proxy: ProxySettings,
+    /// CA roots and client certificates (stored separately, see `Storage::get_tls_settings`)
+    #[serde(skip)]
+    pub tls: TlsSettings,
+    /// Whether clients follow redirects themselves (off when `redirect::send` follows them hop by hop)
+    #[serde(skip, default = "default_true")]
+    pub follow_redirects: bool,
+}
+
+fn default_connect_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    32
+}
+
+fn default_tcp_keepalive_secs() -> u64 {
+    60
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for HttpClientSettings {
+    fn default() -> Self {
+        Self {
+            timeout_ms: 0,
+            connect_timeout_ms: default_connect_timeout_ms(),
+            pool_idle_timeout_secs: default_pool_idle_timeout_secs(),
+            pool_max_idle_per_host: default_pool_max_idle_per_host(),
+            tcp_keepalive_secs: default_tcp_keepalive_secs(),
+            accept_invalid_certs: true,
+            proxy: ProxySettings::default(),
+            tls: TlsSettings::default(),
+            follow_redirects: true,
+        }
+    }
+}
+
+impl HttpClientSettings {
+    /// Registry key - clients are shared between all callers with identical settings
+    fn cache_key(&self) -> String {
+        serde_json::to_string(&(self, &self.proxy, &self.tls, self.follow_redirects)).unwrap_or_default()
+    }
+
+    fn build_client(&self, identity: Option<&ClientIdentity>) -> Result<reqwest::Client, String> {
+        let mut builder = reqwest::Client::builder()
+            .danger_accept_invalid_certs(self.accept_invalid_certs)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host);
+
+        if self.timeout_ms > 0 {
+            builder = builder.timeout(Duration::from_millis(self.timeout_ms));
+        }
+        if self.connect_timeout_ms > 0 {
+            builder = builder.connect_timeout(Duration::from_millis(self.connect_timeout_ms));
+        }
+        builder = builder.pool_idle_timeout(if self.pool_idle_timeout_secs > 0 {
+            Some(Duration::from_secs(self.pool_idle_timeout_secs))
+        } else {
+            None
+        });
+        if self.tcp_keepalive_secs > 0 {
+            builder = builder.tcp_keepalive(Duration::from_secs(self.tcp_keepalive_secs));
+        }
+        builder = match self.proxy.reqwest_proxy() {
+            Some(proxy) => builder.proxy(proxy),
+            None => builder.no_proxy(),
+        };
+        for cert in self.tls.ca_certificates()? {
+            let cert = reqwest::Certificate::from_der(cert.as_ref())
+                .map_err(|e| format!("Invalid CA certificate: {}", e))?;
+            builder = builder.add_root_certificate(cert);
+        }
+        if let Some(identity) = identity {
+            builder = builder.identity(identity.to_reqwest()?);
+        }
+        if !self.follow_redirects {
+            builder = builder.redirect(reqwest::redirect::Policy::none());
+        }
+
+        builder
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))
+    }
+}
+
+/// Get the shared client for the given settings, building it on first use
+pub fn get_client(settings: &HttpClientSettings) -> Result<reqwest::Client, String> {
+    let key = settings.cache_key();
+    if let Some(client) = HTTP_CLIENTS.get(&key) {
+        return Ok(client.clone());
+    }
+
+    let client = settings.build_client(None)?;
+    Ok(HTTP_CLIENTS.entry(key).or_insert(client).clone())
+}
+
+/// Get the shared client for a request to `url`: the one for the settings, or one
+/// presenting the client certificate configured for the URL's host
+pub async fn client_for_url(settings: &HttpClientSettings, url: &Url) -> Result<reqwest::Client, String> {
+    let Some(certificate) = settings.tls.certificate_for(url) else {
+        return get_client(settings);
+    };
+
+    let key = format!("{}#{}", settings.cache_key(), certificate.id);
+    if let Some(client) = HTTP_CLIENTS.get(&key) {
+        return Ok(client.clone());
+    }
+
+    let identity = tls::load_identity(certificate, &settings.tls.secret_providers).await?;
+    let client = settings.build_client(Some(&identity))?;
+    Ok(HTTP_CLIENTS.entry(key).or_insert(client).clone())
+}
+
+/// Load the HTTP client settings for a workspace (falls back to the active workspace)
+pub fn workspace_settings(storage: &Storage, workspace_id: Option<&str>) -> HttpClientSettings {
+    let ws_id = workspace_id
+        .map(|s| s.to_string())
+        .or_else(|| storage.get_active_workspace_id().ok().flatten());
+
+    let Some(ws_id) = ws_id else {
+        return HttpClientSettings::default();
+    };
+
+    let mut settings = storage.get_http_settings(&ws_id).unwrap_or_default();
+    settings.proxy = storage.get_proxy_settings(&ws_id).unwrap_or_default();
+    settings.tls = storage.get_tls_settings(&ws_id).unwrap_or_default();
+    if settings.tls.client_certificates.iter().any(|c| c.passphrase_secret.is_some()) {
+        settings.tls.secret_providers = storage.get_secret_providers().unwrap_or_default();
+    }
+    settings
+}
+
+/// Get the shared client configured for a workspace, for requests to `url`
+pub async fn client_for_workspace(
+    storage: &Storage,
+    workspace_id: Option<&str>,
+    url: &str,
+) -> Result<reqwest::Client, String> {
+    let url = Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+    client_for_url(&workspace_settings(storage, workspace_id), &url).await
+}
+
+/// Drop all pooled clients and loaded client certificates (e.g. after settings change)
+pub fn clear_clients() {
+    HTTP_CLIENTS.clear();
+    tls::clear_identities();
+}
+
+/// Parse a request method. Any valid token is accepted (WebDAV methods, PURGE,
+/// custom verbs). Method names are case-sensitive, so only the standard methods
+/// are uppercased; anything else is sent exactly as typed.
+pub fn parse_method(method: &str) -> Result<reqwest::Method, String> {
+    const STANDARD: [&str; 9] = [
+        "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "CONNECT", "TRACE",
+    ];
+    let method = method.trim();
+    let method = match STANDARD.iter().find(|m| m.eq_ignore_ascii_case(method)) {
+        Some(standard) => *standard,
+        None => method,
+    };
+    reqwest::Method::from_bytes(method.as_bytes()).map_err(|_| format!("Invalid method: {}", method))
+}
@@ -1,7 +1,16 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use reqwest;
+use std::sync::Arc;
+use tauri::Manager;
 use crate::fake_data;
+use crate::har::{Har, HarEntry};
+use crate::storage::{Environment, SensitiveValue, Storage, Variable};
+use crate::template_functions;
+use crate::test_runner::{Assertion, VariableExtraction};
+use crate::bruno::{self, BruFile};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -15,9 +24,31 @@ pub struct ImportedRequest {
     pub params: Vec<KeyValueItem>,
     pub body: String,
     pub body_type: String,
+    /// Fields of `x-www-form-urlencoded` bodies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url_encoded_data: Option<Vec<KeyValueItem>>,
+    /// Fields of `form-data` bodies (`{ id, key, value, type, enabled }`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form_data: Option<Vec<Value>>,
+    /// Auth configuration in the frontend's `AuthConfig` shape
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Value>,
     /// Response schema from OpenAPI/Swagger for mock generation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response_schema: Option<Value>,
+    /// Assertions and variable extractions for the test runner
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_config: Option<ImportedTestConfig>,
+    /// Pre-request script (JavaScript)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pre_request_script: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportedTestConfig {
+    pub assertions: Vec<Assertion>,
+    pub extract_variables: Vec<VariableExtraction>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -38,6 +69,8 @@ pub struct ImportedFolder {
     pub id: String,
     pub name: String,
     pub requests: Vec<ImportedRequest>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub folders: Option<Vec<ImportedFolder>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -58,6 +91,12 @@ pub struct ImportResult {
     pub collection: Option<ImportedCollection>,
     pub error: Option<String>,
     pub request_count: usize,
+    /// Environments that came with the collection
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub environments: Vec<Environment>,
+    /// Workspace global variables after collection variables were merged in
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub global_variables: Vec<Variable>,
 }
 
 fn generate_id() -> String {
@@ -86,7 +125,7 @@ fn create_key_value_with_meta(key: &str, value: &str, required: bool, descriptio
     }
 }
 
-// Parse OpenAPI 3.0 format
+// Parse OpenAPI 3.0 and 3.1 format
 fn parse_openapi3(spec: &Value, base_name: &str) -> Result<ImportedCollection, String> {
     use std::collections::HashMap;
     
@@ -102,30 +141,40 @@ fn parse_openapi3(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
         .and_then(|u| u.as_str())
         .unwrap_or("{{BASE_URL}}");
     
-    // Parse paths
-    let paths = spec.get("paths")
-        .and_then(|p| p.as_object())
-        .ok_or("No paths found in OpenAPI spec")?;
+    // Parse paths, and the webhooks of OpenAPI 3.1 (requests the API sends
+    // out, which are imported to be sent to a receiver at {{WEBHOOK_URL}})
+    let paths = spec.get("paths").and_then(|p| p.as_object());
+    let webhooks = spec.get("webhooks").and_then(|w| w.as_object());
+    if paths.is_none() && webhooks.is_none() {
+        return Err("No paths or webhooks found in OpenAPI spec".to_string());
+    }
+    let path_items = paths.into_iter().flatten().map(|(path, item)| (path, item, false));
+    let webhook_items = webhooks.into_iter().flatten().map(|(name, item)| (name, item, true));
     
-    for (path, methods) in paths {
-        let methods_obj = methods.as_object()
+    for (path, methods, is_webhook) in path_items.chain(webhook_items) {
+        // Path items may themselves be references (components/pathItems in 3.1)
+        let methods_obj = deref(spec, methods).as_object()
             .ok_or(format!("Invalid path object for {}", path))?;
         
         for (method, operation) in methods_obj {
             // Skip non-HTTP methods (like parameters, summary, etc.)
-            let http_methods = ["get", "post", "put", "patch", "delete", "head", "options"];
+            let http_methods = ["get", "post", "put", "patch", "delete", "head", "options", "trace"];
             if !http_methods.contains(&method.as_str()) {
                 continue;
             }
             
             let op = operation.as_object();
             
-            // Get tags for this operation
-            let tags: Vec<String> = op
-                .and_then(|o| o.get("tags"))
-                .and_then(|t| t.as_array())
-                .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
-                .unwrap_or_default();
+            // Get tags for this operation; webhooks share a folder
+            let tags: Vec<String> = if is_webhook {
+                vec!["Webhooks".to_string()]
+            } else {
+                op
+                    .and_then(|o| o.get("tags"))
+                    .and_then(|t| t.as_array())
+                    .map(|arr| arr.iter().filter_map(|v| v.as_str().map(|s| s.to_string())).collect())
+                    .unwrap_or_default()
+            };
             
             // Get operation name
             let name = op
@@ -141,6 +190,7 @@ fn parse_openapi3(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
             
             if let Some(parameters) = op.and_then(|o| o.get("parameters")).and_then(|p| p.as_array()) {
                 for param in parameters {
+                    let param = deref(spec, param);
                     let param_name = param.get("name").and_then(|n| n.as_str()).unwrap_or("");
                     let param_in = param.get("in").and_then(|i| i.as_str()).unwrap_or("");
                     let required = param.get("required").and_then(|r| r.as_bool()).unwrap_or(false);
@@ -196,7 +246,7 @@ fn parse_openapi3(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
             let mut body = String::new();
             let mut body_type = "none".to_string();
             
-            if let Some(request_body) = op.and_then(|o| o.get("requestBody")) {
+            if let Some(request_body) = op.and_then(|o| o.get("requestBody")).map(|b| deref(spec, b)) {
                 if let Some(content) = request_body.get("content").and_then(|c| c.as_object()) {
                     // Prefer JSON
                     if let Some(json_content) = content.get("application/json") {
@@ -207,7 +257,7 @@ fn parse_openapi3(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
                             body = serde_json::to_string_pretty(example).unwrap_or_default();
                         } else if let Some(schema) = json_content.get("schema") {
                             // Generate example from schema
-                            body = generate_example_from_schema(schema);
+                            body = generate_example_from_schema(schema, spec);
                         }
                         
                         // Add Content-Type header if not present
@@ -230,7 +280,9 @@ fn parse_openapi3(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
             params.push(create_key_value("", ""));
             
             // Build full URL
-            let url = if base_url.ends_with('/') && path.starts_with('/') {
+            let url = if is_webhook {
+                "{{WEBHOOK_URL}}".to_string()
+            } else if base_url.ends_with('/') && path.starts_with('/') {
                 format!("{}{}", &base_url[..base_url.len()-1], path)
             } else if !base_url.ends_with('/') && !path.starts_with('/') {
                 format!("{}/{}", base_url, path)
@@ -252,7 +304,12 @@ fn parse_openapi3(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
                 params,
                 body,
                 body_type,
+                url_encoded_data: None,
+                form_data: None,
+                auth: None,
                 response_schema,
+                test_config: None,
+                pre_request_script: None,
             };
             
             // Group by first tag, or put in untagged
@@ -277,6 +334,7 @@ fn parse_openapi3(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
             id: generate_id(),
             name: capitalize_first(&tag),
             requests,
+            folders: None,
         })
         .collect();
     
@@ -300,98 +358,336 @@ fn capitalize_first(s: &str) -> String {
     }
 }
 
+/// Resolve a JSON pointer (`/components/schemas/User`) inside a document;
+/// the empty pointer is the document itself
+fn resolve_pointer<'a>(document: &'a Value, pointer: &str) -> Option<&'a Value> {
+    if pointer.is_empty() {
+        return Some(document);
+    }
+
+    let mut current = document;
+    for token in pointer.strip_prefix('/')?.split('/') {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        current = match current {
+            Value::Array(items) => items.get(token.parse::<usize>().ok()?)?,
+            _ => current.get(&token)?,
+        };
+    }
+    Some(current)
+}
+
 /// Resolve a $ref reference in OpenAPI spec
 /// e.g., "#/components/schemas/User" -> actual schema object
 fn resolve_ref<'a>(spec: &'a Value, ref_path: &str) -> Option<&'a Value> {
-    if !ref_path.starts_with("#/") {
-        return None;
-    }
-    
-    let path_parts: Vec<&str> = ref_path[2..].split('/').collect();
-    let mut current = spec;
-    
-    for part in path_parts {
-        current = current.get(part)?;
+    let pointer = ref_path.strip_prefix('#')?;
+    let pointer = urlencoding::decode(pointer).ok()?;
+    resolve_pointer(spec, &pointer)
+}
+
+/// Follow a Reference Object (`{ "$ref": "#/components/parameters/Limit" }`)
+/// to its target; anything else is returned as is
+fn deref<'a>(spec: &'a Value, value: &'a Value) -> &'a Value {
+    let mut current = value;
+    // Bounded so that a reference cycle cannot loop forever
+    for _ in 0..16 {
+        match current.get("$ref").and_then(|r| r.as_str()).and_then(|r| resolve_ref(spec, r)) {
+            Some(target) => current = target,
+            None => break,
+        }
     }
-    
-    Some(current)
+    current
 }
 
-/// Recursively resolve all $ref in a schema, returning a fully resolved schema
-fn resolve_schema_refs(schema: &Value, spec: &Value, depth: usize) -> Value {
-    // Prevent infinite recursion
-    if depth > 10 {
-        return schema.clone();
+/// Merge schemas into one, as for `allOf`: properties and required lists are
+/// combined, any other keyword keeps its first value
+fn merge_schemas(schemas: impl IntoIterator<Item = Value>) -> Value {
+    let mut merged = serde_json::Map::new();
+    for schema in schemas {
+        let Value::Object(schema) = schema else { continue };
+        for (key, value) in schema {
+            match merged.get_mut(&key) {
+                None => {
+                    merged.insert(key, value);
+                }
+                Some(Value::Object(properties)) if key == "properties" => {
+                    if let Value::Object(more) = value {
+                        properties.extend(more);
+                    }
+                }
+                Some(Value::Array(required)) if key == "required" => {
+                    for name in value.as_array().into_iter().flatten() {
+                        if !required.contains(name) {
+                            required.push(name.clone());
+                        }
+                    }
+                }
+                Some(_) => {}
+            }
+        }
     }
-    
-    // If it's a $ref, resolve it
+    if merged.contains_key("properties") && !merged.contains_key("type") {
+        merged.insert("type".to_string(), Value::String("object".to_string()));
+    }
+    Value::Object(merged)
+}
+
+/// The variant of a `oneOf`/`anyOf` used for examples and mocks: the first
+/// one that is not just `null`
+fn first_variant(variants: &[Value]) -> Option<&Value> {
+    variants
+        .iter()
+        .find(|v| v.get("type").and_then(|t| t.as_str()) != Some("null"))
+        .or_else(|| variants.first())
+}
+
+/// Recursively resolve all $ref in a schema, returning a fully resolved schema.
+/// `seen` holds the references being resolved; a reference back into one of
+/// them is a cycle and resolves to an empty schema.
+fn resolve_schema_refs(schema: &Value, spec: &Value, seen: &mut Vec<String>) -> Value {
+    // If it's a $ref, resolve it; sibling keywords (OpenAPI 3.1) take precedence
     if let Some(ref_path) = schema.get("$ref").and_then(|r| r.as_str()) {
-        if let Some(resolved) = resolve_ref(spec, ref_path) {
-            return resolve_schema_refs(resolved, spec, depth + 1);
+        if seen.iter().any(|s| s == ref_path) {
+            return Value::Object(serde_json::Map::new());
         }
-        return schema.clone();
+        let Some(target) = resolve_ref(spec, ref_path) else {
+            return schema.clone();
+        };
+        seen.push(ref_path.to_string());
+        let resolved = resolve_schema_refs(target, spec, seen);
+        seen.pop();
+
+        let mut siblings = schema.clone();
+        if let Some(obj) = siblings.as_object_mut() {
+            obj.remove("$ref");
+            if !obj.is_empty() {
+                return merge_schemas([resolve_schema_refs(&siblings, spec, seen), resolved]);
+            }
+        }
+        return resolved;
     }
     
-    // Handle allOf, oneOf, anyOf by merging/taking first
+    // Merge allOf together with the schema's own keywords
     if let Some(all_of) = schema.get("allOf").and_then(|a| a.as_array()) {
-        let mut merged = serde_json::Map::new();
-        merged.insert("type".to_string(), Value::String("object".to_string()));
-        let mut properties = serde_json::Map::new();
-        
-        for sub_schema in all_of {
-            let resolved = resolve_schema_refs(sub_schema, spec, depth + 1);
-            if let Some(props) = resolved.get("properties").and_then(|p| p.as_object()) {
-                for (k, v) in props {
-                    properties.insert(k.clone(), v.clone());
-                }
-            }
+        let mut own = schema.clone();
+        if let Some(obj) = own.as_object_mut() {
+            obj.remove("allOf");
         }
-        
-        if !properties.is_empty() {
-            merged.insert("properties".to_string(), Value::Object(properties));
-        }
-        return Value::Object(merged);
+        let resolved: Vec<Value> = all_of.iter().map(|sub_schema| resolve_schema_refs(sub_schema, spec, seen)).collect();
+        return merge_schemas(std::iter::once(resolve_schema_refs(&own, spec, seen)).chain(resolved));
     }
     
-    if let Some(one_of) = schema.get("oneOf").and_then(|a| a.as_array()) {
-        if let Some(first) = one_of.first() {
-            return resolve_schema_refs(first, spec, depth + 1);
+    // oneOf/anyOf take their first non-null variant
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(variant) = schema.get(keyword).and_then(|a| a.as_array()).and_then(|a| first_variant(a)) {
+            return resolve_schema_refs(variant, spec, seen);
         }
     }
     
-    if let Some(any_of) = schema.get("anyOf").and_then(|a| a.as_array()) {
-        if let Some(first) = any_of.first() {
-            return resolve_schema_refs(first, spec, depth + 1);
+    let Some(obj) = schema.as_object() else {
+        return schema.clone();
+    };
+    let mut new_schema = obj.clone();
+    
+    // Recursively resolve properties for objects
+    if let Some(properties) = obj.get("properties").and_then(|p| p.as_object()) {
+        let new_props = properties
+            .iter()
+            .map(|(key, prop_schema)| (key.clone(), resolve_schema_refs(prop_schema, spec, seen)))
+            .collect();
+        new_schema.insert("properties".to_string(), Value::Object(new_props));
+    }
+    
+    // Resolve items, tuple items (JSON Schema 2020-12) and additional properties
+    for keyword in ["items", "additionalProperties"] {
+        if let Some(sub_schema) = obj.get(keyword).filter(|s| s.is_object()) {
+            new_schema.insert(keyword.to_string(), resolve_schema_refs(sub_schema, spec, seen));
         }
     }
+    if let Some(prefix_items) = obj.get("prefixItems").and_then(|p| p.as_array()) {
+        let resolved = prefix_items.iter().map(|item| resolve_schema_refs(item, spec, seen)).collect();
+        new_schema.insert("prefixItems".to_string(), Value::Array(resolved));
+    }
     
-    // Recursively resolve properties for objects
-    if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
-        let mut new_schema = schema.clone();
-        let mut new_props = serde_json::Map::new();
-        
-        for (key, prop_schema) in properties {
-            new_props.insert(key.clone(), resolve_schema_refs(prop_schema, spec, depth + 1));
+    Value::Object(new_schema)
+}
+
+// ============ External $ref Resolution ============
+
+/// Upper bound on the files and URLs a single spec may pull in
+const MAX_SPEC_DOCUMENTS: usize = 256;
+
+/// Parse an API spec document, JSON or YAML
+fn parse_spec_document(content: &str) -> Result<Value, String> {
+    serde_json::from_str(content).or_else(|_| {
+        serde_yaml::from_str::<Value>(content).map_err(|e| format!("Failed to parse JSON or YAML: {}", e))
+    })
+}
+
+/// The location external `$ref`s are resolved against: an http(s) or file URL,
+/// or a path to the spec file or the directory holding it
+fn spec_base(location: &str) -> Result<url::Url, String> {
+    if let Ok(url) = url::Url::parse(location) {
+        if matches!(url.scheme(), "http" | "https" | "file") {
+            return Ok(url);
         }
-        
-        if let Some(obj) = new_schema.as_object_mut() {
-            obj.insert("properties".to_string(), Value::Object(new_props));
+    }
+    let path = std::fs::canonicalize(location)
+        .map_err(|e| format!("Invalid spec location {}: {}", location, e))?;
+    if path.is_dir() {
+        url::Url::from_directory_path(&path)
+    } else {
+        url::Url::from_file_path(&path)
+    }
+    .map_err(|_| format!("Invalid spec location: {}", location))
+}
+
+/// The documents (without fragment) referenced by non-local `$ref`s in a value
+fn external_refs(value: &Value, document_url: &url::Url, refs: &mut Vec<url::Url>) {
+    match value {
+        Value::Object(obj) => {
+            if let Some(reference) = obj.get("$ref").and_then(|r| r.as_str()) {
+                if !reference.starts_with('#') {
+                    if let Ok(mut target) = document_url.join(reference) {
+                        target.set_fragment(None);
+                        refs.push(target);
+                    }
+                }
+            }
+            obj.values().for_each(|v| external_refs(v, document_url, refs));
         }
-        return new_schema;
+        Value::Array(items) => items.iter().for_each(|v| external_refs(v, document_url, refs)),
+        _ => {}
     }
-    
-    // Resolve items for arrays
-    if let Some(items) = schema.get("items") {
-        let mut new_schema = schema.clone();
-        let resolved_items = resolve_schema_refs(items, spec, depth + 1);
-        
-        if let Some(obj) = new_schema.as_object_mut() {
-            obj.insert("items".to_string(), resolved_items);
+}
+
+async fn load_spec_document(client: &reqwest::Client, url: &url::Url) -> Result<Value, String> {
+    let content = if url.scheme() == "file" {
+        let path = url.to_file_path().map_err(|_| format!("Invalid file reference: {}", url))?;
+        std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+    } else {
+        let response = client
+            .get(url.clone())
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch {}: HTTP {}", url, response.status()));
+        }
+        response.text().await.map_err(|e| format!("Failed to read {}: {}", url, e))?
+    };
+    parse_spec_document(&content).map_err(|e| format!("{}: {}", url, e))
+}
+
+/// Documents a spec references in other files or URLs, loaded transitively
+struct ExternalDocuments {
+    root: url::Url,
+    documents: std::collections::HashMap<String, Value>,
+}
+
+impl ExternalDocuments {
+    async fn load(spec: &Value, base: url::Url) -> Result<Self, String> {
+        let client = reqwest::Client::new();
+        let mut root = base;
+        root.set_fragment(None);
+        let mut documents = std::collections::HashMap::new();
+
+        let mut pending: Vec<(url::Url, url::Url)> = Vec::new();
+        let mut refs = Vec::new();
+        external_refs(spec, &root, &mut refs);
+        pending.extend(refs.into_iter().map(|target| (root.clone(), target)));
+
+        while let Some((from, url)) = pending.pop() {
+            if url == root || documents.contains_key(url.as_str()) {
+                continue;
+            }
+            // A spec fetched over the network must not read local files
+            if url.scheme() == "file" && from.scheme() != "file" {
+                return Err(format!("{} cannot reference the local file {}", from, url));
+            }
+            if documents.len() >= MAX_SPEC_DOCUMENTS {
+                return Err(format!("Spec references more than {} documents", MAX_SPEC_DOCUMENTS));
+            }
+
+            let document = load_spec_document(&client, &url).await?;
+            let mut refs = Vec::new();
+            external_refs(&document, &url, &mut refs);
+            pending.extend(refs.into_iter().map(|target| (url.clone(), target)));
+            documents.insert(url.to_string(), document);
+        }
+
+        Ok(Self { root, documents })
+    }
+
+    /// Replace every `$ref` into another document with the value it points
+    /// to. References into the root spec stay local `#/` references;
+    /// `stack` holds the references being inlined, and a reference back into
+    /// one of them (a cycle) is dropped.
+    fn inline(&self, value: &Value, document_url: &url::Url, stack: &mut Vec<String>) -> Value {
+        let obj = match value {
+            Value::Object(obj) => obj,
+            Value::Array(items) => return Value::Array(items.iter().map(|v| self.inline(v, document_url, stack)).collect()),
+            _ => return value.clone(),
+        };
+
+        let mut siblings: serde_json::Map<String, Value> = obj
+            .iter()
+            .filter(|(key, _)| *key != "$ref")
+            .map(|(key, v)| (key.clone(), self.inline(v, document_url, stack)))
+            .collect();
+        let Some(reference) = obj.get("$ref").and_then(|r| r.as_str()) else {
+            return Value::Object(siblings);
+        };
+        let Ok(target) = document_url.join(reference) else {
+            return value.clone();
+        };
+        let fragment = target.fragment().unwrap_or("").to_string();
+        let mut target_document = target.clone();
+        target_document.set_fragment(None);
+
+        if target_document == self.root {
+            siblings.insert("$ref".to_string(), Value::String(format!("#{}", fragment)));
+            return Value::Object(siblings);
+        }
+
+        let key = target.to_string();
+        if stack.contains(&key) {
+            return Value::Object(siblings);
+        }
+        let pointer = urlencoding::decode(&fragment).map(|p| p.into_owned()).unwrap_or(fragment);
+        let Some(resolved) = self
+            .documents
+            .get(target_document.as_str())
+            .and_then(|document| resolve_pointer(document, &pointer))
+        else {
+            return Value::Object(siblings);
+        };
+
+        stack.push(key);
+        let resolved = self.inline(resolved, &target_document, stack);
+        stack.pop();
+
+        match resolved {
+            Value::Object(mut resolved) => {
+                resolved.extend(siblings);
+                Value::Object(resolved)
+            }
+            resolved => resolved,
         }
-        return new_schema;
     }
-    
-    schema.clone()
+}
+
+/// Inline the `$ref`s of a spec that point into other files or URLs, relative
+/// to `base`, so the parsers only deal with local `#/` references
+async fn bundle_external_refs(spec: Value, base: Option<url::Url>) -> Result<Value, String> {
+    let Some(base) = base else {
+        return Ok(spec);
+    };
+    let documents = ExternalDocuments::load(&spec, base).await?;
+    if documents.documents.is_empty() {
+        return Ok(spec);
+    }
+    let root = documents.root.clone();
+    Ok(documents.inline(&spec, &root, &mut Vec::new()))
 }
 
 /// Extract response schema from OpenAPI 3.0 operation
@@ -403,6 +699,7 @@ fn extract_response_schema_openapi3(operation: &Value, spec: &Value) -> Option<V
         .or_else(|| responses.get("201"))
         .or_else(|| responses.get("2XX"))
         .or_else(|| responses.get("default"))?;
+    let response = deref(spec, response);
     
     // Get content -> application/json -> schema
     let schema = response.get("content")
@@ -410,7 +707,7 @@ fn extract_response_schema_openapi3(operation: &Value, spec: &Value) -> Option<V
         .and_then(|j| j.get("schema"))?;
     
     // Resolve all $ref in the schema
-    Some(resolve_schema_refs(schema, spec, 0))
+    Some(resolve_schema_refs(schema, spec, &mut Vec::new()))
 }
 
 /// Extract response schema from Swagger 2.0 operation
@@ -421,12 +718,13 @@ fn extract_response_schema_swagger2(operation: &Value, spec: &Value) -> Option<V
     let response = responses.get("200")
         .or_else(|| responses.get("201"))
         .or_else(|| responses.get("default"))?;
+    let response = deref(spec, response);
     
     // In Swagger 2.0, schema is directly under the response
     let schema = response.get("schema")?;
     
     // Resolve all $ref (Swagger 2.0 uses #/definitions/...)
-    Some(resolve_schema_refs(schema, spec, 0))
+    Some(resolve_schema_refs(schema, spec, &mut Vec::new()))
 }
 
 // Parse Swagger 2.0 format
@@ -524,7 +822,7 @@ fn parse_swagger2(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
                         "body" => {
                             body_type = "json".to_string();
                             if let Some(schema) = param.get("schema") {
-                                body = generate_example_from_schema(schema);
+                                body = generate_example_from_schema(schema, spec);
                             }
                             if !headers.iter().any(|h| h.key.to_lowercase() == "content-type") {
                                 headers.insert(0, create_key_value("Content-Type", "application/json"));
@@ -563,7 +861,12 @@ fn parse_swagger2(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
                 params,
                 body,
                 body_type,
+                url_encoded_data: None,
+                form_data: None,
+                auth: None,
                 response_schema,
+                test_config: None,
+                pre_request_script: None,
             };
             
             // Group by first tag, or put in untagged
@@ -587,6 +890,7 @@ fn parse_swagger2(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
             id: generate_id(),
             name: capitalize_first(&tag),
             requests,
+            folders: None,
         })
         .collect();
     
@@ -603,23 +907,72 @@ fn parse_swagger2(spec: &Value, base_name: &str) -> Result<ImportedCollection, S
 }
 
 // Generate example JSON from OpenAPI schema
-fn generate_example_from_schema(schema: &Value) -> String {
-    let example = schema_to_example(schema);
+fn generate_example_from_schema(schema: &Value, spec: &Value) -> String {
+    let example = schema_to_example(&resolve_schema_refs(schema, spec, &mut Vec::new()));
     serde_json::to_string_pretty(&example).unwrap_or_default()
 }
 
+/// The type of a schema; of a JSON Schema 2020-12 type array such as
+/// `["string", "null"]` the first non-null type
+fn schema_type(schema: &Value) -> &str {
+    match schema.get("type") {
+        Some(Value::String(t)) => t,
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(|t| t.as_str())
+            .find(|t| *t != "null")
+            .unwrap_or("null"),
+        _ if schema.get("items").is_some() || schema.get("prefixItems").is_some() => "array",
+        _ => "object",
+    }
+}
+
 fn schema_to_example(schema: &Value) -> Value {
     // If there's an explicit example, use it
     if let Some(example) = schema.get("example") {
         return example.clone();
     }
+    if let Some(example) = schema.get("examples").and_then(|e| e.as_array()).and_then(|e| e.first()) {
+        return example.clone();
+    }
+    if let Some(value) = schema.get("const") {
+        return value.clone();
+    }
     
     // Handle $ref (simplified - just return placeholder)
     if schema.get("$ref").is_some() {
         return Value::Object(serde_json::Map::new());
     }
     
-    let schema_type = schema.get("type").and_then(|t| t.as_str()).unwrap_or("object");
+    // allOf: merge the examples of all subschemas (and the schema's own keywords)
+    if let Some(all_of) = schema.get("allOf").and_then(|a| a.as_array()) {
+        let mut own = schema.clone();
+        if let Some(obj) = own.as_object_mut() {
+            obj.remove("allOf");
+        }
+        let own_example = own.as_object().is_some_and(|o| !o.is_empty()).then(|| schema_to_example(&own));
+        return own_example.into_iter().chain(all_of.iter().map(schema_to_example)).fold(Value::Null, |merged, part| match (merged, part) {
+            (Value::Object(mut merged), Value::Object(part)) => {
+                merged.extend(part);
+                Value::Object(merged)
+            }
+            (Value::Null, part) => part,
+            (merged, _) => merged,
+        });
+    }
+    
+    // oneOf/anyOf: the example of the first non-null variant
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(variant) = schema.get(keyword).and_then(|a| a.as_array()).and_then(|a| first_variant(a)) {
+            return schema_to_example(variant);
+        }
+    }
+    
+    let schema_type = schema_type(schema);
+    
+    if let Some(first) = schema.get("enum").and_then(|e| e.as_array()).and_then(|e| e.first()) {
+        return first.clone();
+    }
     
     match schema_type {
         "object" => {
@@ -632,16 +985,16 @@ fn schema_to_example(schema: &Value) -> Value {
             Value::Object(obj)
         }
         "array" => {
-            if let Some(items) = schema.get("items") {
+            if let Some(prefix_items) = schema.get("prefixItems").and_then(|p| p.as_array()) {
+                Value::Array(prefix_items.iter().map(schema_to_example).collect())
+            } else if let Some(items) = schema.get("items") {
                 Value::Array(vec![schema_to_example(items)])
             } else {
                 Value::Array(vec![])
             }
         }
         "string" => {
-            if let Some(enum_values) = schema.get("enum").and_then(|e| e.as_array()) {
-                enum_values.first().cloned().unwrap_or(Value::String("string".to_string()))
-            } else if let Some(format) = schema.get("format").and_then(|f| f.as_str()) {
+            if let Some(format) = schema.get("format").and_then(|f| f.as_str()) {
                 match format {
                     "date" => Value::String("2024-01-01".to_string()),
                     "date-time" => Value::String("2024-01-01T00:00:00Z".to_string()),
@@ -677,24 +1030,941 @@ fn chrono_timestamp() -> i64 {
         .as_millis() as i64
 }
 
+// Parse a HAR 1.2 log, one folder per host
+fn parse_har(har: &Har, base_name: &str) -> Result<ImportedCollection, String> {
+    // Folders in the order their host first appears
+    let mut folders: Vec<ImportedFolder> = Vec::new();
+
+    for entry in &har.log.entries {
+        let Ok(url) = url::Url::parse(&entry.request.url) else {
+            continue;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            continue;
+        }
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let request = har_request(entry, url);
+        match folders.iter_mut().find(|f| f.name == host) {
+            Some(folder) => folder.requests.push(request),
+            None => folders.push(ImportedFolder {
+                id: generate_id(),
+                name: host,
+                requests: vec![request],
+                folders: None,
+            }),
+        }
+    }
+
+    if folders.is_empty() {
+        return Err("No HTTP requests found in HAR file".to_string());
+    }
+
+    Ok(ImportedCollection {
+        id: generate_id(),
+        name: base_name.to_string(),
+        requests: Vec::new(),
+        folders: Some(folders),
+        created_at: chrono_timestamp(),
+    })
+}
+
+fn har_request(entry: &HarEntry, mut url: url::Url) -> ImportedRequest {
+    let request = &entry.request;
+
+    // HTTP/2 pseudo-headers and headers derived from the URL and body are not kept
+    let mut headers: Vec<KeyValueItem> = request
+        .headers
+        .iter()
+        .filter(|h| {
+            !h.name.starts_with(':')
+                && !h.name.eq_ignore_ascii_case("host")
+                && !h.name.eq_ignore_ascii_case("content-length")
+        })
+        .map(|h| create_key_value(&h.name, &h.value))
+        .collect();
+    headers.push(create_key_value("", ""));
+
+    // Query parameters are edited as params, not in the URL
+    let mut params: Vec<KeyValueItem> = url
+        .query_pairs()
+        .map(|(k, v)| create_key_value(&k, &v))
+        .collect();
+    params.push(create_key_value("", ""));
+    url.set_query(None);
+    url.set_fragment(None);
+
+    let (body, body_type) = match &request.post_data {
+        Some(post) => {
+            let text = if post.text.is_empty() && !post.params.is_empty() {
+                post.params
+                    .iter()
+                    .map(|p| format!("{}={}", urlencoding::encode(&p.name), urlencoding::encode(&p.value)))
+                    .collect::<Vec<_>>()
+                    .join("&")
+            } else {
+                post.text.clone()
+            };
+            let mime = post.mime_type.split(';').next().unwrap_or("").trim().to_lowercase();
+            let body_type = if mime.ends_with("json") {
+                "json"
+            } else if mime.ends_with("xml") {
+                "xml"
+            } else if mime == "text/html" {
+                "html"
+            } else {
+                "raw"
+            };
+            (text, body_type.to_string())
+        }
+        None => (String::new(), "none".to_string()),
+    };
+
+    let method = request.method.to_uppercase();
+    ImportedRequest {
+        id: generate_id(),
+        name: format!("{} {}", method, url.path()),
+        protocol: "http".to_string(),
+        method,
+        url: url.to_string(),
+        headers,
+        params,
+        body,
+        body_type,
+        url_encoded_data: None,
+        form_data: None,
+        auth: None,
+        response_schema: None,
+        test_config: None,
+        pre_request_script: None,
+    }
+}
+
+// ============ Insomnia ============
+
+const ENV_COLORS: [&str; 8] = ["#86efac", "#93c5fd", "#fde047", "#d8b4fe", "#fdba74", "#f9a8d4", "#a5f3fc", "#fca5a5"];
+
+static INSOMNIA_VARIABLE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\{\{\s*(?:_\.([\w.\-]+)|_\[['"]([^'"]+)['"]\]|([\w\-]+))\s*\}\}"#).unwrap());
+static INSOMNIA_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{%\s*(\w+)\s*(.*?)\s*%\}").unwrap());
+
+/// Translate Insomnia template syntax: `{{ _.name }}` becomes `{{name}}` and
+/// tags with an istek equivalent become template functions. Other tags
+/// (responses, prompts, files) are kept as they are.
+fn translate_insomnia_template(text: &str) -> String {
+    let text = INSOMNIA_VARIABLE.replace_all(text, |caps: &regex::Captures| {
+        let name = caps.get(1).or(caps.get(2)).or(caps.get(3)).map(|m| m.as_str()).unwrap_or("");
+        format!("{{{{{}}}}}", name)
+    });
+    INSOMNIA_TAG
+        .replace_all(&text, |caps: &regex::Captures| {
+            let args: Vec<&str> = caps[2]
+                .split(',')
+                .map(|a| a.trim().trim_matches(|c| c == '\'' || c == '"'))
+                .filter(|a| !a.is_empty())
+                .collect();
+            let arg = |i: usize| args.get(i).copied().unwrap_or("");
+            match (&caps[1], arg(0)) {
+                ("uuid", _) => "{{$uuid()}}".to_string(),
+                ("now", "millis") => "{{$timestamp.ms()}}".to_string(),
+                ("now", "unix") => "{{$timestamp()}}".to_string(),
+                ("base64", "encode") => format!("{{{{$base64.encode('{}')}}}}", arg(2)),
+                ("base64", "decode") => format!("{{{{$base64.decode('{}')}}}}", arg(2)),
+                ("hash", algorithm @ ("md5" | "sha1" | "sha256" | "sha512")) if arg(1) == "hex" => {
+                    format!("{{{{$hash.{}('{}')}}}}", algorithm, arg(2))
+                }
+                _ => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+fn insomnia_str(value: &Value, name: &str) -> String {
+    translate_insomnia_template(value.get(name).and_then(|v| v.as_str()).unwrap_or(""))
+}
+
+fn insomnia_disabled(value: &Value) -> bool {
+    value.get("disabled").and_then(|d| d.as_bool()).unwrap_or(false)
+}
+
+/// Insomnia `{ name, value, disabled }` pairs
+fn insomnia_pairs(items: Option<&Value>) -> Vec<KeyValueItem> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter(|p| !insomnia_str(p, "name").is_empty())
+                .map(|p| KeyValueItem {
+                    id: generate_id(),
+                    key: insomnia_str(p, "name"),
+                    value: insomnia_str(p, "value"),
+                    enabled: !insomnia_disabled(p),
+                    required: None,
+                    description: p.get("description").and_then(|d| d.as_str()).map(|d| d.to_string()),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// An Insomnia authentication object as an `AuthConfig`
+fn insomnia_auth(auth: &Value) -> Option<Value> {
+    let field = |name: &str| insomnia_str(auth, name);
+    let config = match auth.get("type")?.as_str()? {
+        "basic" => serde_json::json!({ "type": "basic", "username": field("username"), "password": field("password") }),
+        "digest" => serde_json::json!({ "type": "digest", "username": field("username"), "password": field("password") }),
+        "bearer" => {
+            let prefix = field("prefix");
+            serde_json::json!({
+                "type": "bearer",
+                "token": field("token"),
+                "prefix": if prefix.is_empty() { "Bearer".to_string() } else { prefix },
+            })
+        }
+        "apikey" => serde_json::json!({
+            "type": "api-key",
+            "apiKeyName": field("key"),
+            "apiKeyValue": field("value"),
+            "apiKeyIn": if field("addTo") == "queryParams" { "query" } else { "header" },
+        }),
+        "oauth2" => serde_json::json!({
+            "type": "oauth2",
+            "oauth2": {
+                "grantType": match field("grantType").as_str() {
+                    "client_credentials" => "client_credentials",
+                    "password" => "password",
+                    "implicit" => "implicit",
+                    _ => "authorization_code",
+                },
+                "authUrl": field("authorizationUrl"),
+                "tokenUrl": field("accessTokenUrl"),
+                "clientId": field("clientId"),
+                "clientSecret": field("clientSecret"),
+                "scope": field("scope"),
+                "username": field("username"),
+                "password": field("password"),
+            },
+        }),
+        "hawk" => serde_json::json!({
+            "type": "hawk",
+            "hawk": {
+                "id": field("id"),
+                "key": field("key"),
+                "algorithm": if field("algorithm") == "sha1" { "sha1" } else { "sha256" },
+                "ext": field("ext"),
+                "includePayloadHash": auth.get("validatePayload").and_then(|v| v.as_bool()).unwrap_or(false),
+            },
+        }),
+        "iam" => serde_json::json!({
+            "type": "aws-sigv4",
+            "awsSigV4": {
+                "accessKeyId": field("accessKeyId"),
+                "secretAccessKey": field("secretAccessKey"),
+                "sessionToken": field("sessionToken"),
+                "region": field("region"),
+                "service": field("service"),
+            },
+        }),
+        // NTLM, netrc and ASAP have no equivalent
+        _ => return None,
+    };
+    let mut config = config;
+    config["enabled"] = Value::Bool(!insomnia_disabled(auth));
+    Some(config)
+}
+
+/// An Insomnia request (v4 resource or v5 collection item)
+fn insomnia_request(item: &Value) -> ImportedRequest {
+    let mut headers = insomnia_pairs(item.get("headers"));
+    headers.push(create_key_value("", ""));
+    let mut params = insomnia_pairs(item.get("parameters"));
+    params.push(create_key_value("", ""));
+
+    let body = item.get("body").cloned().unwrap_or(Value::Null);
+    let mime = body.get("mimeType").and_then(|m| m.as_str()).unwrap_or("");
+    let text = insomnia_str(&body, "text");
+    let mut url_encoded_data = None;
+    let mut form_data = None;
+    let body_type = match mime {
+        "" if text.is_empty() => "none",
+        "application/x-www-form-urlencoded" => {
+            url_encoded_data = Some(insomnia_pairs(body.get("params")));
+            "x-www-form-urlencoded"
+        }
+        "multipart/form-data" => {
+            let fields = body.get("params").and_then(|p| p.as_array()).cloned().unwrap_or_default();
+            form_data = Some(
+                fields
+                    .iter()
+                    .map(|p| {
+                        let is_file = p.get("type").and_then(|t| t.as_str()) == Some("file");
+                        let file_name = p.get("fileName").and_then(|f| f.as_str()).unwrap_or("");
+                        serde_json::json!({
+                            "id": generate_id(),
+                            "key": insomnia_str(p, "name"),
+                            "value": if is_file { file_name.to_string() } else { insomnia_str(p, "value") },
+                            "type": if is_file { "file" } else { "text" },
+                            "enabled": !insomnia_disabled(p),
+                        })
+                    })
+                    .collect(),
+            );
+            "form-data"
+        }
+        // GraphQL bodies are JSON with `query` and `variables`
+        m if m.ends_with("json") || m == "application/graphql" => "json",
+        m if m.ends_with("xml") => "xml",
+        "text/html" => "html",
+        _ => "raw",
+    };
+
+    ImportedRequest {
+        id: generate_id(),
+        name: item.get("name").and_then(|n| n.as_str()).unwrap_or("Request").to_string(),
+        protocol: "http".to_string(),
+        method: item.get("method").and_then(|m| m.as_str()).unwrap_or("GET").to_uppercase(),
+        url: insomnia_str(item, "url"),
+        headers,
+        params,
+        body: text,
+        body_type: body_type.to_string(),
+        url_encoded_data,
+        form_data,
+        auth: item.get("authentication").and_then(insomnia_auth),
+        response_schema: None,
+        test_config: None,
+        pre_request_script: None,
+    }
+}
+
+/// Requests and folders of a v5 collection tree (folders have `children`)
+fn insomnia_items(items: &[Value]) -> (Vec<ImportedRequest>, Vec<ImportedFolder>) {
+    let mut requests = Vec::new();
+    let mut folders = Vec::new();
+    for item in items {
+        if let Some(children) = item.get("children").and_then(|c| c.as_array()) {
+            let (folder_requests, subfolders) = insomnia_items(children);
+            folders.push(ImportedFolder {
+                id: generate_id(),
+                name: item.get("name").and_then(|n| n.as_str()).unwrap_or("Folder").to_string(),
+                requests: folder_requests,
+                folders: if subfolders.is_empty() { None } else { Some(subfolders) },
+            });
+        } else if item.get("url").is_some() || item.get("method").is_some() {
+            requests.push(insomnia_request(item));
+        }
+    }
+    (requests, folders)
+}
+
+/// Environment data as variables; nested objects become dotted names
+fn insomnia_variables(data: &Value, prefix: &str, variables: &mut Vec<Variable>) {
+    let Some(map) = data.as_object() else {
+        return;
+    };
+    for (key, value) in map {
+        let name = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        let value = match value {
+            Value::Object(_) => {
+                insomnia_variables(value, &name, variables);
+                continue;
+            }
+            Value::String(s) => translate_insomnia_template(s),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        // Sub-environments override inherited variables
+        if let Some(existing) = variables.iter_mut().find(|v| v.key == name) {
+            existing.value = value;
+            continue;
+        }
+        variables.push(Variable {
+            id: generate_id(),
+            key: name,
+            value,
+            description: None,
+            is_secret: false,
+            secret_provider: None,
+            enabled: true,
+        });
+    }
+}
+
+/// Sub-environments with the variables of the base environment they inherit,
+/// or the base environment alone when it has none
+fn insomnia_environments(base: &Value) -> Vec<Environment> {
+    let mut base_variables = Vec::new();
+    insomnia_variables(base.get("data").unwrap_or(&Value::Null), "", &mut base_variables);
+
+    let environment = |env: &Value, variables: Vec<Variable>, index: usize| Environment {
+        id: generate_id(),
+        name: env.get("name").and_then(|n| n.as_str()).unwrap_or("Base Environment").to_string(),
+        color: env
+            .get("color")
+            .and_then(|c| c.as_str())
+            .unwrap_or(ENV_COLORS[index % ENV_COLORS.len()])
+            .to_string(),
+        variables,
+        is_default: None,
+        created_at: chrono_timestamp(),
+    };
+
+    let subs = base.get("subEnvironments").and_then(|s| s.as_array()).cloned().unwrap_or_default();
+    if subs.is_empty() {
+        return vec![environment(base, base_variables, 0)];
+    }
+    subs.iter()
+        .enumerate()
+        .map(|(i, sub)| {
+            let mut variables: Vec<Variable> = base_variables
+                .iter()
+                .map(|v| Variable { id: generate_id(), ..v.clone() })
+                .collect();
+            insomnia_variables(sub.get("data").unwrap_or(&Value::Null), "", &mut variables);
+            environment(sub, variables, i)
+        })
+        .collect()
+}
+
+/// Rebuild the v4 flat resource list as v5 workspaces: `{ name, collection, environments }`
+fn insomnia_v4_workspaces(resources: &[Value]) -> Vec<Value> {
+    let of_type = |t: &'static str| resources.iter().filter(move |r| r.get("_type").and_then(|v| v.as_str()) == Some(t));
+    let children_of = |parent: &str| -> Vec<&Value> {
+        let mut children: Vec<&Value> = resources
+            .iter()
+            .filter(|r| r.get("parentId").and_then(|p| p.as_str()) == Some(parent))
+            .collect();
+        children.sort_by(|a, b| {
+            let key = |r: &Value| r.get("metaSortKey").and_then(|k| k.as_f64()).unwrap_or(0.0);
+            key(a).partial_cmp(&key(b)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        children
+    };
+
+    fn tree<'a>(id: &str, children_of: &dyn Fn(&str) -> Vec<&'a Value>) -> Vec<Value> {
+        children_of(id)
+            .into_iter()
+            .filter_map(|r| {
+                let id = r.get("_id").and_then(|i| i.as_str()).unwrap_or("");
+                match r.get("_type").and_then(|t| t.as_str()) {
+                    Some("request_group") => {
+                        let mut folder = r.clone();
+                        folder["children"] = Value::Array(tree(id, children_of));
+                        Some(folder)
+                    }
+                    Some("request") => Some(r.clone()),
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    of_type("workspace")
+        .map(|workspace| {
+            let id = workspace.get("_id").and_then(|i| i.as_str()).unwrap_or("");
+            let environments = of_type("environment")
+                .find(|e| e.get("parentId").and_then(|p| p.as_str()) == Some(id))
+                .map(|base| {
+                    let base_id = base.get("_id").and_then(|i| i.as_str()).unwrap_or("");
+                    let mut base = base.clone();
+                    base["subEnvironments"] = Value::Array(
+                        of_type("environment")
+                            .filter(|e| e.get("parentId").and_then(|p| p.as_str()) == Some(base_id))
+                            .cloned()
+                            .collect(),
+                    );
+                    base
+                });
+            serde_json::json!({
+                "name": workspace.get("name").cloned().unwrap_or(Value::Null),
+                "collection": tree(id, &children_of),
+                "environments": environments,
+            })
+        })
+        .collect()
+}
+
+/// Parse an Insomnia v4 (JSON or YAML) or v5 (YAML) export. Several
+/// workspaces in one export become one folder each.
+fn parse_insomnia(export: &Value, base_name: &str) -> Result<(ImportedCollection, Vec<Environment>), String> {
+    let v5_type = export.get("type").and_then(|t| t.as_str()).unwrap_or("");
+    let workspaces = if let Some(resources) = export.get("resources").and_then(|r| r.as_array()) {
+        insomnia_v4_workspaces(resources)
+    } else if v5_type == "environment.insomnia.rest/5.0" {
+        vec![serde_json::json!({ "name": export.get("name"), "environments": export })]
+    } else if v5_type.ends_with("insomnia.rest/5.0") {
+        vec![export.clone()]
+    } else {
+        return Err("Not a valid Insomnia export (v4 or v5 expected)".to_string());
+    };
+
+    let mut requests = Vec::new();
+    let mut folders = Vec::new();
+    let mut environments = Vec::new();
+    for workspace in &workspaces {
+        let items = workspace.get("collection").and_then(|c| c.as_array()).cloned().unwrap_or_default();
+        let (workspace_requests, workspace_folders) = insomnia_items(&items);
+        if workspaces.len() == 1 {
+            requests = workspace_requests;
+            folders = workspace_folders;
+        } else {
+            folders.push(ImportedFolder {
+                id: generate_id(),
+                name: workspace.get("name").and_then(|n| n.as_str()).unwrap_or("Workspace").to_string(),
+                requests: workspace_requests,
+                folders: if workspace_folders.is_empty() { None } else { Some(workspace_folders) },
+            });
+        }
+        if let Some(base) = workspace.get("environments").filter(|e| e.is_object()) {
+            environments.extend(insomnia_environments(base));
+        }
+    }
+
+    let name = match workspaces.as_slice() {
+        [workspace] => workspace.get("name").and_then(|n| n.as_str()).unwrap_or(base_name),
+        _ => base_name,
+    };
+    let collection = ImportedCollection {
+        id: generate_id(),
+        name: name.to_string(),
+        requests,
+        folders: if folders.is_empty() { None } else { Some(folders) },
+        created_at: chrono_timestamp(),
+    };
+    Ok((collection, environments))
+}
+
+fn count_folder_requests(folders: &[ImportedFolder]) -> usize {
+    folders
+        .iter()
+        .map(|f| f.requests.len() + f.folders.as_deref().map(count_folder_requests).unwrap_or(0))
+        .sum()
+}
+
+// ============ Bruno ============
+
+const BRUNO_METHODS: [&str; 9] = ["get", "post", "put", "delete", "patch", "options", "head", "connect", "trace"];
+
+fn bruno_pairs(file: &BruFile, block: &str) -> Vec<KeyValueItem> {
+    file.pairs(block)
+        .iter()
+        .map(|p| KeyValueItem {
+            id: generate_id(),
+            key: p.key.clone(),
+            value: p.value.clone(),
+            enabled: p.enabled,
+            required: None,
+            description: None,
+        })
+        .collect()
+}
+
+/// A Bruno `auth:<mode>` block as an `AuthConfig`
+fn bruno_auth(file: &BruFile, mode: &str) -> Option<Value> {
+    let block = format!("auth:{}", mode);
+    let field = |name: &str| file.value(&block, name).unwrap_or("").to_string();
+    let config = match mode {
+        "inherit" => serde_json::json!({ "type": "inherit" }),
+        "basic" => serde_json::json!({ "type": "basic", "username": field("username"), "password": field("password") }),
+        "digest" => serde_json::json!({ "type": "digest", "username": field("username"), "password": field("password") }),
+        "bearer" => serde_json::json!({ "type": "bearer", "token": field("token"), "prefix": "Bearer" }),
+        "apikey" => serde_json::json!({
+            "type": "api-key",
+            "apiKeyName": field("key"),
+            "apiKeyValue": field("value"),
+            "apiKeyIn": if field("placement") == "queryparams" { "query" } else { "header" },
+        }),
+        "awsv4" => serde_json::json!({
+            "type": "aws-sigv4",
+            "awsSigV4": {
+                "accessKeyId": field("accessKeyId"),
+                "secretAccessKey": field("secretAccessKey"),
+                "sessionToken": field("sessionToken"),
+                "region": field("region"),
+                "service": field("service"),
+            },
+        }),
+        "oauth2" => serde_json::json!({
+            "type": "oauth2",
+            "oauth2": {
+                "grantType": match field("grant_type").as_str() {
+                    "client_credentials" => "client_credentials",
+                    "password" => "password",
+                    "implicit" => "implicit",
+                    _ => "authorization_code",
+                },
+                "authUrl": field("authorization_url"),
+                "tokenUrl": field("access_token_url"),
+                "clientId": field("client_id"),
+                "clientSecret": field("client_secret"),
+                "scope": field("scope"),
+                "username": field("username"),
+                "password": field("password"),
+            },
+        }),
+        // NTLM and WSSE have no equivalent
+        _ => return None,
+    };
+    let mut config = config;
+    config["enabled"] = Value::Bool(true);
+    Some(config)
+}
+
+/// A Bruno request file, or None for files that are not HTTP or GraphQL requests
+fn bruno_request(file: &BruFile, fallback_name: &str) -> Option<ImportedRequest> {
+    let method = BRUNO_METHODS.iter().find(|m| file.block(m).is_some())?;
+    let field = |name: &str| file.value(method, name).unwrap_or("").to_string();
+
+    // params:query mirrors the query string of the url
+    let mut url = field("url");
+    let mut params = bruno_pairs(file, "params:query");
+    if !params.is_empty() {
+        url = url.split('?').next().unwrap_or("").to_string();
+    }
+    params.push(create_key_value("", ""));
+    for param in file.pairs("params:path").iter().filter(|p| !p.value.is_empty()) {
+        let placeholder = format!(":{}", param.key);
+        url = url
+            .split('/')
+            .map(|segment| if segment == placeholder { param.value.as_str() } else { segment })
+            .collect::<Vec<_>>()
+            .join("/");
+    }
+
+    let mut headers = bruno_pairs(file, "headers");
+    headers.push(create_key_value("", ""));
+
+    let mut body = String::new();
+    let mut url_encoded_data = None;
+    let mut form_data = None;
+    let body_type = match field("body").as_str() {
+        "json" => {
+            body = file.text("body:json").unwrap_or("").to_string();
+            "json"
+        }
+        "xml" => {
+            body = file.text("body:xml").unwrap_or("").to_string();
+            "xml"
+        }
+        "text" => {
+            body = file.text("body:text").unwrap_or("").to_string();
+            "raw"
+        }
+        // GraphQL requests are sent as JSON `{ query, variables }` bodies
+        "graphql" => {
+            let variables = file
+                .text("body:graphql:vars")
+                .and_then(|v| serde_json::from_str::<Value>(v).ok())
+                .unwrap_or(serde_json::json!({}));
+            let graphql = serde_json::json!({
+                "query": file.text("body:graphql").unwrap_or(""),
+                "variables": variables,
+            });
+            body = serde_json::to_string_pretty(&graphql).unwrap_or_default();
+            "json"
+        }
+        "formUrlEncoded" => {
+            url_encoded_data = Some(bruno_pairs(file, "body:form-urlencoded"));
+            "x-www-form-urlencoded"
+        }
+        "multipartForm" => {
+            let fields = file
+                .pairs("body:multipart-form")
+                .iter()
+                .map(|p| {
+                    let file_path = p.value.strip_prefix("@file(").and_then(|v| v.strip_suffix(')'));
+                    serde_json::json!({
+                        "id": generate_id(),
+                        "key": p.key,
+                        "value": file_path.unwrap_or(&p.value),
+                        "type": if file_path.is_some() { "file" } else { "text" },
+                        "enabled": p.enabled,
+                    })
+                })
+                .collect();
+            form_data = Some(fields);
+            "form-data"
+        }
+        _ => "none",
+    };
+
+    let assertions: Vec<Assertion> = file.pairs("assert").iter().filter_map(bruno::parse_assertion).collect();
+    let extract_variables: Vec<VariableExtraction> =
+        file.pairs("vars:post-response").iter().filter_map(bruno::parse_extraction).collect();
+    let test_config = if assertions.is_empty() && extract_variables.is_empty() {
+        None
+    } else {
+        Some(ImportedTestConfig { assertions, extract_variables })
+    };
+
+    // Request variables are set before the request is sent
+    let pre_request_script: Vec<String> = file
+        .pairs("vars:pre-request")
+        .iter()
+        .filter(|p| p.enabled)
+        .map(|p| format!("istek.variables.set({}, {});", Value::from(p.key.as_str()), Value::from(p.value.as_str())))
+        .collect();
+
+    Some(ImportedRequest {
+        id: generate_id(),
+        name: file.value("meta", "name").unwrap_or(fallback_name).to_string(),
+        protocol: "http".to_string(),
+        method: method.to_uppercase(),
+        url,
+        headers,
+        params,
+        body,
+        body_type: body_type.to_string(),
+        url_encoded_data,
+        form_data,
+        auth: bruno_auth(file, &field("auth")),
+        response_schema: None,
+        test_config,
+        pre_request_script: if pre_request_script.is_empty() { None } else { Some(pre_request_script.join("\n")) },
+    })
+}
+
+fn read_bru(path: &std::path::Path) -> Result<BruFile, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    BruFile::parse(&text).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn bruno_seq(file: &BruFile) -> f64 {
+    file.value("meta", "seq").and_then(|s| s.parse().ok()).unwrap_or(f64::MAX)
+}
+
+/// Requests and folders of a Bruno collection directory, ordered by `seq`
+fn bruno_items(dir: &std::path::Path) -> Result<(Vec<ImportedRequest>, Vec<ImportedFolder>), String> {
+    let mut entries: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    entries.sort();
+
+    let mut requests = Vec::new();
+    let mut folders = Vec::new();
+    for path in entries {
+        let name = path.file_stem().and_then(|n| n.to_str()).unwrap_or("").to_string();
+        if path.is_dir() {
+            if matches!(name.as_str(), "environments" | "node_modules") || name.starts_with('.') {
+                continue;
+            }
+            let meta_path = path.join("folder.bru");
+            let meta = if meta_path.exists() { read_bru(&meta_path)? } else { BruFile::default() };
+            let (folder_requests, subfolders) = bruno_items(&path)?;
+            let folder = ImportedFolder {
+                id: generate_id(),
+                name: meta.value("meta", "name").unwrap_or(&name).to_string(),
+                requests: folder_requests,
+                folders: if subfolders.is_empty() { None } else { Some(subfolders) },
+            };
+            folders.push((bruno_seq(&meta), folder));
+        } else if path.extension().and_then(|e| e.to_str()) == Some("bru")
+            && !matches!(name.as_str(), "folder" | "collection")
+        {
+            let file = read_bru(&path)?;
+            if let Some(request) = bruno_request(&file, &name) {
+                requests.push((bruno_seq(&file), request));
+            }
+        }
+    }
+
+    requests.sort_by(|a, b| a.0.total_cmp(&b.0));
+    folders.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok((
+        requests.into_iter().map(|(_, r)| r).collect(),
+        folders.into_iter().map(|(_, f)| f).collect(),
+    ))
+}
+
+/// Files in `environments/`; secret variables are imported without values
+fn bruno_environments(dir: &std::path::Path) -> Result<Vec<Environment>, String> {
+    let Ok(entries) = std::fs::read_dir(dir.join("environments")) else {
+        return Ok(Vec::new());
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("bru"))
+        .collect();
+    paths.sort();
+
+    let mut environments = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        let file = read_bru(path)?;
+        let variable = |key: &str, value: &str, is_secret: bool, enabled: bool| Variable {
+            id: generate_id(),
+            key: key.to_string(),
+            value: value.to_string(),
+            description: None,
+            is_secret,
+            secret_provider: None,
+            enabled,
+        };
+        let mut variables: Vec<Variable> = file
+            .pairs("vars")
+            .iter()
+            .map(|p| variable(&p.key, &p.value, false, p.enabled))
+            .collect();
+        variables.extend(file.list("vars:secret").iter().map(|key| {
+            let (key, enabled) = match key.strip_prefix('~') {
+                Some(key) => (key, false),
+                None => (key.as_str(), true),
+            };
+            variable(key, "", true, enabled)
+        }));
+        environments.push(Environment {
+            id: generate_id(),
+            name: path.file_stem().and_then(|n| n.to_str()).unwrap_or("Environment").to_string(),
+            color: ENV_COLORS[i % ENV_COLORS.len()].to_string(),
+            variables,
+            is_default: None,
+            created_at: chrono_timestamp(),
+        });
+    }
+    Ok(environments)
+}
+
+/// Parse a Bruno collection directory (the one containing `bruno.json`)
+fn parse_bruno(dir: &std::path::Path) -> Result<(ImportedCollection, Vec<Environment>), String> {
+    let manifest = std::fs::read_to_string(dir.join("bruno.json"))
+        .map_err(|_| "Not a Bruno collection: bruno.json not found".to_string())?;
+    let manifest: Value = serde_json::from_str(&manifest).map_err(|e| format!("Invalid bruno.json: {}", e))?;
+    let dir_name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("Bruno Collection");
+
+    let (requests, folders) = bruno_items(dir)?;
+    let collection = ImportedCollection {
+        id: generate_id(),
+        name: manifest.get("name").and_then(|n| n.as_str()).unwrap_or(dir_name).to_string(),
+        requests,
+        folders: if folders.is_empty() { None } else { Some(folders) },
+        created_at: chrono_timestamp(),
+    };
+    Ok((collection, bruno_environments(dir)?))
+}
+
+// ============ Postman Variables ============
+
+/// Variables of a Postman environment, globals dump or collection `variable` array.
+/// `secret` values are flagged as secrets and sealed by `seal_secrets` before they are stored.
+fn postman_variables(values: &[Value]) -> Vec<Variable> {
+    values
+        .iter()
+        .filter_map(|v| {
+            let key = v.get("key").and_then(|k| k.as_str()).filter(|k| !k.is_empty())?;
+            let value = match v.get("value") {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Null) | None => String::new(),
+                Some(other) => other.to_string(),
+            };
+            Some(Variable {
+                id: generate_id(),
+                key: key.to_string(),
+                value,
+                description: v.get("description").and_then(|d| d.as_str()).map(|d| d.to_string()),
+                is_secret: v.get("type").and_then(|t| t.as_str()) == Some("secret"),
+                secret_provider: None,
+                enabled: v.get("enabled").and_then(|e| e.as_bool()).unwrap_or(true),
+            })
+        })
+        .collect()
+}
+
+/// What a Postman variables file contains
+enum PostmanVariablesFile {
+    Environment(Environment),
+    Globals(Vec<Variable>),
+}
+
+/// Parse a Postman environment export or globals dump (an object with
+/// `_postman_variable_scope: "globals"`, or a bare array from older versions)
+fn parse_postman_variables_file(export: &Value, base_name: &str) -> Result<PostmanVariablesFile, String> {
+    if let Some(values) = export.as_array() {
+        return Ok(PostmanVariablesFile::Globals(postman_variables(values)));
+    }
+    let values = export
+        .get("values")
+        .and_then(|v| v.as_array())
+        .ok_or("Not a Postman environment or globals export")?;
+    let variables = postman_variables(values);
+
+    match export.get("_postman_variable_scope").and_then(|s| s.as_str()) {
+        Some("globals") => Ok(PostmanVariablesFile::Globals(variables)),
+        _ => Ok(PostmanVariablesFile::Environment(Environment {
+            id: generate_id(),
+            name: export.get("name").and_then(|n| n.as_str()).unwrap_or(base_name).to_string(),
+            color: ENV_COLORS[0].to_string(),
+            variables,
+            is_default: None,
+            created_at: chrono_timestamp(),
+        })),
+    }
+}
+
+/// Encrypt secret values with the workspace master key and keep them as sensitive values.
+/// Without workspace encryption secrets are imported as plain variables, the same as
+/// marking a variable secret fails in the app before a master key is set up.
+fn seal_secrets(storage: &Storage, workspace_id: &str, variables: &mut [Variable]) -> Result<(), String> {
+    let encryption_enabled = template_functions::sensitive_check_encryption_status(workspace_id.to_string()).enabled;
+    for variable in variables.iter_mut().filter(|v| v.is_secret) {
+        if !encryption_enabled || variable.value.is_empty() {
+            variable.is_secret = encryption_enabled;
+            continue;
+        }
+        let encrypted = template_functions::sensitive_encrypt(workspace_id.to_string(), variable.key.clone(), variable.value.clone())?;
+        let now = chrono_timestamp();
+        storage.save_sensitive_value(
+            workspace_id,
+            &SensitiveValue {
+                key: variable.key.clone(),
+                encrypted_value: encrypted.clone(),
+                description: variable.description.clone(),
+                created_at: now,
+                updated_at: now,
+            },
+        )?;
+        variable.value = encrypted;
+    }
+    Ok(())
+}
+
+/// Give imported globals the id of the workspace global with the same key, which is the
+/// variable `merge_global_variables` updates in place, so secrets are sealed for the id that is kept
+fn adopt_global_ids(storage: &Storage, workspace_id: &str, variables: &mut [Variable]) -> Result<(), String> {
+    let existing = storage.get_global_variables(workspace_id)?;
+    for variable in variables.iter_mut() {
+        if let Some(global) = existing.iter().find(|g| g.key == variable.key) {
+            variable.id = global.id.clone();
+        }
+    }
+    Ok(())
+}
+
+/// Import an OpenAPI/Swagger spec. `base_path` is where the spec lives (its
+/// file, its directory or a base URL); `$ref`s into other files are resolved
+/// relative to it.
 #[tauri::command]
-pub async fn import_openapi(content: String, file_name: String) -> Result<ImportResult, String> {
-    // Parse JSON
-    let spec: Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+pub async fn import_openapi(content: String, file_name: String, base_path: Option<String>) -> Result<ImportResult, String> {
+    let spec = parse_spec_document(&content)?;
+    let base = base_path.as_deref().map(spec_base).transpose()?;
+    let spec = bundle_external_refs(spec, base).await?;
     
     // Detect version
     let is_openapi3 = spec.get("openapi").is_some();
     let is_swagger2 = spec.get("swagger").is_some();
     
-    let base_name = file_name.trim_end_matches(".json").trim_end_matches(".yaml").to_string();
+    let base_name = file_name.trim_end_matches(".json").trim_end_matches(".yaml").trim_end_matches(".yml").to_string();
     
     let result = if is_openapi3 {
         parse_openapi3(&spec, &base_name)
     } else if is_swagger2 {
         parse_swagger2(&spec, &base_name)
     } else {
-        Err("Unknown API specification format. Expected OpenAPI 3.0 or Swagger 2.0".to_string())
+        Err("Unknown API specification format. Expected OpenAPI 3.x or Swagger 2.0".to_string())
     };
     
     match result {
@@ -705,6 +1975,8 @@ pub async fn import_openapi(content: String, file_name: String) -> Result<Import
                 collection: Some(collection),
                 error: None,
                 request_count: count,
+                environments: Vec::new(),
+                global_variables: Vec::new(),
             })
         }
         Err(e) => {
@@ -713,13 +1985,20 @@ pub async fn import_openapi(content: String, file_name: String) -> Result<Import
                 collection: None,
                 error: Some(e),
                 request_count: 0,
+                environments: Vec::new(),
+                global_variables: Vec::new(),
             })
         }
     }
 }
 
 #[tauri::command]
-pub async fn import_postman(content: String, _file_name: String) -> Result<ImportResult, String> {
+pub async fn import_postman(
+    app: tauri::AppHandle,
+    content: String,
+    _file_name: String,
+    workspace_id: Option<String>,
+) -> Result<ImportResult, String> {
     let spec: Value = serde_json::from_str(&content)
         .map_err(|e| format!("Failed to parse JSON: {}", e))?;
     
@@ -856,7 +2135,12 @@ pub async fn import_postman(content: String, _file_name: String) -> Result<Impor
                         params,
                         body,
                         body_type,
+                        url_encoded_data: None,
+                        form_data: None,
+                        auth: None,
                         response_schema: None,
+                        test_config: None,
+                        pre_request_script: None,
                     });
                 }
             }
@@ -869,6 +2153,23 @@ pub async fn import_postman(content: String, _file_name: String) -> Result<Impor
     
     let count = requests.len();
     
+    // Collection variables become workspace globals
+    let mut collection_variables = spec.get("variable")
+        .and_then(|v| v.as_array())
+        .map(|values| postman_variables(values))
+        .unwrap_or_default();
+    let global_variables = if collection_variables.is_empty() {
+        Vec::new()
+    } else {
+        let storage = app.state::<Arc<Storage>>();
+        let ws_id = workspace_id
+            .or_else(|| storage.get_active_workspace_id().ok().flatten())
+            .ok_or("No active workspace")?;
+        adopt_global_ids(&storage, &ws_id, &mut collection_variables)?;
+        seal_secrets(&storage, &ws_id, &mut collection_variables)?;
+        storage.merge_global_variables(&ws_id, &collection_variables)?
+    };
+    
     Ok(ImportResult {
         success: true,
         collection: Some(ImportedCollection {
@@ -880,9 +2181,158 @@ pub async fn import_postman(content: String, _file_name: String) -> Result<Impor
         }),
         error: None,
         request_count: count,
+        environments: Vec::new(),
+        global_variables,
     })
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostmanVariablesImport {
+    /// The imported environment, for environment exports
+    pub environment: Option<Environment>,
+    /// Workspace global variables after a globals dump was merged in
+    pub global_variables: Vec<Variable>,
+}
+
+/// Import a Postman environment export (returned for the app to add) or a
+/// globals dump (merged into the workspace global variables)
+#[tauri::command]
+pub async fn import_postman_environment(
+    app: tauri::AppHandle,
+    content: String,
+    file_name: String,
+    workspace_id: Option<String>,
+) -> Result<PostmanVariablesImport, String> {
+    let export: Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    let base_name = file_name
+        .trim_end_matches(".json")
+        .trim_end_matches(".postman_environment")
+        .trim_end_matches(".postman_globals")
+        .to_string();
+
+    let storage = app.state::<Arc<Storage>>();
+    let ws_id = workspace_id
+        .or_else(|| storage.get_active_workspace_id().ok().flatten())
+        .ok_or("No active workspace")?;
+
+    match parse_postman_variables_file(&export, &base_name)? {
+        PostmanVariablesFile::Environment(mut environment) => {
+            seal_secrets(&storage, &ws_id, &mut environment.variables)?;
+            Ok(PostmanVariablesImport {
+                environment: Some(environment),
+                global_variables: Vec::new(),
+            })
+        }
+        PostmanVariablesFile::Globals(mut variables) => {
+            adopt_global_ids(&storage, &ws_id, &mut variables)?;
+            seal_secrets(&storage, &ws_id, &mut variables)?;
+            Ok(PostmanVariablesImport {
+                environment: None,
+                global_variables: storage.merge_global_variables(&ws_id, &variables)?,
+            })
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn import_har(content: String, file_name: String) -> Result<ImportResult, String> {
+    let har: Har = serde_json::from_str(&content)
+        .map_err(|e| format!("Not a valid HAR file: {}", e))?;
+
+    let base_name = file_name.trim_end_matches(".har").trim_end_matches(".json").to_string();
+
+    match parse_har(&har, &base_name) {
+        Ok(collection) => {
+            let count = collection.folders.iter().flatten().map(|f| f.requests.len()).sum();
+            Ok(ImportResult {
+                success: true,
+                collection: Some(collection),
+                error: None,
+                request_count: count,
+                environments: Vec::new(),
+                global_variables: Vec::new(),
+            })
+        }
+        Err(e) => {
+            Ok(ImportResult {
+                success: false,
+                collection: None,
+                error: Some(e),
+                request_count: 0,
+                environments: Vec::new(),
+                global_variables: Vec::new(),
+            })
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn import_insomnia(content: String, file_name: String) -> Result<ImportResult, String> {
+    // v4 exports are JSON or YAML, v5 exports are YAML
+    let export: Value = serde_json::from_str(&content)
+        .or_else(|_| serde_yaml::from_str(&content))
+        .map_err(|e| format!("Failed to parse Insomnia export: {}", e))?;
+
+    let base_name = file_name
+        .trim_end_matches(".json")
+        .trim_end_matches(".yaml")
+        .trim_end_matches(".yml")
+        .to_string();
+
+    match parse_insomnia(&export, &base_name) {
+        Ok((collection, environments)) => {
+            let count = collection.requests.len() + count_folder_requests(collection.folders.as_deref().unwrap_or_default());
+            Ok(ImportResult {
+                success: true,
+                collection: Some(collection),
+                error: None,
+                request_count: count,
+                environments,
+                global_variables: Vec::new(),
+            })
+        }
+        Err(e) => {
+            Ok(ImportResult {
+                success: false,
+                collection: None,
+                error: Some(e),
+                request_count: 0,
+                environments: Vec::new(),
+                global_variables: Vec::new(),
+            })
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn import_bruno(path: String) -> Result<ImportResult, String> {
+    match parse_bruno(std::path::Path::new(&path)) {
+        Ok((collection, environments)) => {
+            let count = collection.requests.len() + count_folder_requests(collection.folders.as_deref().unwrap_or_default());
+            Ok(ImportResult {
+                success: true,
+                collection: Some(collection),
+                error: None,
+                request_count: count,
+                environments,
+                global_variables: Vec::new(),
+            })
+        }
+        Err(e) => {
+            Ok(ImportResult {
+                success: false,
+                collection: None,
+                error: Some(e),
+                request_count: 0,
+                environments: Vec::new(),
+                global_variables: Vec::new(),
+            })
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn import_from_url(url: String) -> Result<ImportResult, String> {
     // Fetch the spec from URL
@@ -903,9 +2353,7 @@ pub async fn import_from_url(url: String) -> Result<ImportResult, String> {
         .await
         .map_err(|e| format!("Failed to read response: {}", e))?;
     
-    // Parse JSON
-    let spec: Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse JSON: {}", e))?;
+    let spec = parse_spec_document(&content)?;
     
     // Detect format
     let is_openapi3 = spec.get("openapi").is_some();
@@ -1047,7 +2495,12 @@ pub async fn import_from_url(url: String) -> Result<ImportResult, String> {
                             params,
                             body,
                             body_type,
+                            url_encoded_data: None,
+                            form_data: None,
+                            auth: None,
                             response_schema: None,
+                            test_config: None,
+                            pre_request_script: None,
                         });
                     }
                 }
@@ -1070,15 +2523,21 @@ pub async fn import_from_url(url: String) -> Result<ImportResult, String> {
             }),
             error: None,
             request_count: count,
+            environments: Vec::new(),
+            global_variables: Vec::new(),
         });
     }
     
+    // Specs may pull in schemas from files next to them
+    let base = url::Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let spec = bundle_external_refs(spec, Some(base)).await?;
+    
     let result = if is_openapi3 {
         parse_openapi3(&spec, &url_name)
     } else if is_swagger2 {
         parse_swagger2(&spec, &url_name)
     } else {
-        Err("Unknown API specification format. Expected OpenAPI 3.0, Swagger 2.0, or Postman Collection".to_string())
+        Err("Unknown API specification format. Expected OpenAPI 3.x, Swagger 2.0, or Postman Collection".to_string())
     };
     
     match result {
@@ -1089,6 +2548,8 @@ pub async fn import_from_url(url: String) -> Result<ImportResult, String> {
                 collection: Some(collection),
                 error: None,
                 request_count: count,
+                environments: Vec::new(),
+                global_variables: Vec::new(),
             })
         }
         Err(e) => {
@@ -1097,6 +2558,8 @@ pub async fn import_from_url(url: String) -> Result<ImportResult, String> {
                 collection: None,
                 error: Some(e),
                 request_count: 0,
+                environments: Vec::new(),
+                global_variables: Vec::new(),
             })
         }
     }
@@ -1114,3 +2577,80 @@ pub fn generate_mock_response(schema: Value) -> Result<String, String> {
 pub fn generate_mock_response_smart(schema: Value) -> Result<Value, String> {
     Ok(fake_data::generate_fake_from_schema(&schema))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn translates_insomnia_templates() {
+        assert_eq!(
+            translate_insomnia_template("{{ _.base_url }}/users/{{ _['user-id'] }}?token={{ token }}"),
+            "{{base_url}}/users/{{user-id}}?token={{token}}"
+        );
+        assert_eq!(
+            translate_insomnia_template("{% uuid 'v4' %}-{% now 'millis', '' %}-{% hash 'sha256', 'hex', 'abc' %}"),
+            "{{$uuid()}}-{{$timestamp.ms()}}-{{$hash.sha256('abc')}}"
+        );
+        // Tags without an equivalent are kept
+        let response_tag = "{% response 'body', 'req_1', 'b64::JC5h::46b', 'never', 60 %}";
+        assert_eq!(translate_insomnia_template(response_tag), response_tag);
+    }
+
+    #[test]
+    fn builds_examples_from_composed_schemas() {
+        let spec = json!({
+            "components": { "schemas": {
+                "Id": { "type": "object", "properties": { "id": { "type": "integer" } }, "required": ["id"] },
+                "Node": { "type": "object", "properties": { "next": { "$ref": "#/components/schemas/Node" } } },
+            } }
+        });
+        let schema = json!({
+            "allOf": [
+                { "$ref": "#/components/schemas/Id" },
+                { "properties": {
+                    "name": { "type": ["string", "null"] },
+                    "kind": { "const": "user" },
+                    "tags": { "type": "array", "examples": [["admin"]] },
+                    "pet": { "oneOf": [{ "type": "null" }, { "type": "object", "properties": { "age": { "type": "number", "default": 3 } } }] },
+                    "node": { "$ref": "#/components/schemas/Node" },
+                } },
+            ]
+        });
+        let resolved = resolve_schema_refs(&schema, &spec, &mut Vec::new());
+        assert_eq!(resolved["required"], json!(["id"]));
+        assert_eq!(
+            schema_to_example(&resolved),
+            json!({ "id": 0, "name": "string", "kind": "user", "tags": ["admin"], "pet": { "age": 3 }, "node": { "next": {} } })
+        );
+    }
+
+    #[test]
+    fn inlines_external_refs_with_cycles() {
+        let root = url::Url::parse("file:///specs/openapi.yaml").unwrap();
+        let documents = ExternalDocuments {
+            root: root.clone(),
+            documents: [
+                ("file:///specs/schemas/user.yaml".to_string(), json!({
+                    "type": "object",
+                    "properties": {
+                        "address": { "$ref": "#/$defs/Address" },
+                        "manager": { "$ref": "user.yaml" },
+                        "error": { "$ref": "../openapi.yaml#/components/schemas/Error" },
+                    },
+                    "$defs": { "Address": { "type": "string" } },
+                })),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let spec = json!({ "schema": { "$ref": "./schemas/user.yaml", "description": "A user" }, "local": { "$ref": "#/components/schemas/Error" } });
+        let bundled = documents.inline(&spec, &root, &mut Vec::new());
+        assert_eq!(bundled["schema"]["description"], "A user");
+        assert_eq!(bundled["schema"]["properties"]["address"], json!({ "type": "string" }));
+        assert_eq!(bundled["schema"]["properties"]["manager"], json!({}));
+        assert_eq!(bundled["schema"]["properties"]["error"], json!({ "$ref": "#/components/schemas/Error" }));
+        assert_eq!(bundled["local"], json!({ "$ref": "#/components/schemas/Error" }));
+    }
+}
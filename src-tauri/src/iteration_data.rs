@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+use utoipa::ToSchema;
+
+/// Counts of one iteration of a data-driven run; its results carry the same `iteration`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IterationSummary {
+    /// 1-based row number in the iteration data
+    pub iteration: usize,
+    /// Variables of the row
+    pub data: HashMap<String, String>,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub total_time: u64,
+}
+
+impl IterationSummary {
+    pub fn new(iteration: usize, data: HashMap<String, String>, total: usize) -> Self {
+        IterationSummary {
+            iteration,
+            data,
+            total,
+            passed: 0,
+            failed: 0,
+            errors: 0,
+            total_time: 0,
+        }
+    }
+}
+
+// ============ CSV ============
+
+/// RFC 4180 records: quoted fields may contain separators, `""` and line breaks
+fn csv_records(content: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("Unterminated quoted field".to_string());
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+
+    // Blank lines separate nothing
+    records.retain(|r| !(r.len() == 1 && r[0].trim().is_empty()));
+    Ok(records)
+}
+
+/// A header row naming the variables, then one row per iteration
+fn parse_csv(content: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let mut records = csv_records(content)?.into_iter();
+    let Some(header) = records.next() else {
+        return Ok(Vec::new());
+    };
+    let header: Vec<String> = header.iter().map(|name| name.trim().to_string()).collect();
+
+    records
+        .enumerate()
+        .map(|(index, record)| {
+            if record.len() > header.len() {
+                return Err(format!(
+                    "Row {} has {} fields but the header has {}",
+                    index + 1,
+                    record.len(),
+                    header.len()
+                ));
+            }
+            // Missing trailing fields are empty
+            Ok(header
+                .iter()
+                .cloned()
+                .zip(record.into_iter().chain(std::iter::repeat(String::new())))
+                .filter(|(name, _)| !name.is_empty())
+                .collect())
+        })
+        .collect()
+}
+
+// ============ JSON ============
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        _ => value.to_string(),
+    }
+}
+
+/// An array of objects, as written by Postman/Newman (`[{ "id": 1 }, ...]`)
+fn parse_json(content: &str) -> Result<Vec<HashMap<String, String>>, String> {
+    let value: Value = serde_json::from_str(content).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let rows = value.as_array().ok_or("Iteration data must be an array of objects")?;
+    rows.iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let fields = row
+                .as_object()
+                .ok_or_else(|| format!("Row {} is not an object", index + 1))?;
+            Ok(fields.iter().map(|(k, v)| (k.clone(), value_to_string(v))).collect())
+        })
+        .collect()
+}
+
+/// Iteration rows of a CSV or JSON file, told apart by the extension or the content
+pub fn parse(content: &str, file_name: Option<&str>) -> Result<Vec<HashMap<String, String>>, String> {
+    let extension = file_name
+        .and_then(|name| Path::new(name).extension())
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    match extension.as_deref() {
+        Some("json") => parse_json(content),
+        Some("csv") => parse_csv(content),
+        _ if content.trim_start().starts_with('[') => parse_json(content),
+        _ => parse_csv(content),
+    }
+}
+
+#[tauri::command]
+pub async fn load_iteration_data(path: String) -> Result<Vec<HashMap<String, String>>, String> {
+    let content = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    parse(&content, Some(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_csv_rows() {
+        let csv = "user,password,note\r\nada,\"p,1\",\"said \"\"hi\"\"\nthen left\"\r\n\r\nbob,secret\n";
+        let rows = parse(csv, Some("users.csv")).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["password"], "p,1");
+        assert_eq!(rows[0]["note"], "said \"hi\"\nthen left");
+        assert_eq!(rows[1]["user"], "bob");
+        assert_eq!(rows[1]["note"], "");
+        assert!(parse("a\n1,2\n", None).is_err());
+    }
+
+    #[test]
+    fn parses_json_rows() {
+        let rows = parse(r#"[{ "id": 1, "name": "Ada", "admin": true, "manager": null }]"#, None).unwrap();
+        assert_eq!(rows[0]["id"], "1");
+        assert_eq!(rows[0]["name"], "Ada");
+        assert_eq!(rows[0]["admin"], "true");
+        assert_eq!(rows[0]["manager"], "");
+        assert!(parse(r#"{ "id": 1 }"#, Some("data.json")).is_err());
+    }
+}
@@ -0,0 +1,168 @@
+use base64::{engine::general_purpose::STANDARD, engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::digest::core_api::BlockSizeUser;
+use hmac::{Mac, SimpleHmac};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::RsaPrivateKey;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use signature::{SignatureEncoding, Signer};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+pub enum JwtAlgorithm {
+    #[default]
+    HS256,
+    HS384,
+    HS512,
+    RS256,
+    RS384,
+    RS512,
+    ES256,
+    ES384,
+}
+
+/// A JWT signed for every request from claims and a key
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JwtAuth {
+    #[serde(default)]
+    pub algorithm: JwtAlgorithm,
+    /// HMAC secret (HS*) or PEM private key: PKCS#8 or PKCS#1 for RS*, PKCS#8 for ES*
+    pub key: String,
+    /// The HMAC secret is base64-encoded
+    #[serde(default)]
+    pub key_base64: bool,
+    /// Claims of the token; `iat`, `exp` and `jti` are added unless present
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub claims: Map<String, Value>,
+    /// Additional header fields, e.g. `kid`
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    #[schema(value_type = Object)]
+    pub header: Map<String, Value>,
+    /// Lifetime of each token in seconds (0 omits `exp`)
+    #[serde(default = "default_expires_in")]
+    pub expires_in: u64,
+    /// Prefix of the `Authorization` value
+    #[serde(default = "default_prefix")]
+    pub prefix: String,
+}
+
+fn default_expires_in() -> u64 {
+    300
+}
+
+fn default_prefix() -> String {
+    "Bearer".to_string()
+}
+
+/// Sign a token for a request issued at `now` (Unix seconds)
+pub fn token(auth: &JwtAuth, now: i64) -> Result<String, String> {
+    let mut header = auth.header.clone();
+    header.insert("alg".to_string(), Value::from(format!("{:?}", auth.algorithm)));
+    header.entry("typ").or_insert_with(|| Value::from("JWT"));
+
+    let mut claims = auth.claims.clone();
+    claims.entry("iat").or_insert_with(|| Value::from(now));
+    if auth.expires_in > 0 {
+        claims.entry("exp").or_insert_with(|| Value::from(now + auth.expires_in as i64));
+    }
+    claims.entry("jti").or_insert_with(|| Value::from(uuid::Uuid::new_v4().to_string()));
+
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(Value::Object(header).to_string()),
+        URL_SAFE_NO_PAD.encode(Value::Object(claims).to_string())
+    );
+    let signature = sign(auth, signing_input.as_bytes())?;
+    Ok(format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(signature)))
+}
+
+fn sign(auth: &JwtAuth, data: &[u8]) -> Result<Vec<u8>, String> {
+    match auth.algorithm {
+        JwtAlgorithm::HS256 => hmac::<Sha256>(&secret(auth)?, data),
+        JwtAlgorithm::HS384 => hmac::<Sha384>(&secret(auth)?, data),
+        JwtAlgorithm::HS512 => hmac::<Sha512>(&secret(auth)?, data),
+        JwtAlgorithm::RS256 => Ok(SigningKey::<Sha256>::new(rsa_key(&auth.key)?).sign(data).to_vec()),
+        JwtAlgorithm::RS384 => Ok(SigningKey::<Sha384>::new(rsa_key(&auth.key)?).sign(data).to_vec()),
+        JwtAlgorithm::RS512 => Ok(SigningKey::<Sha512>::new(rsa_key(&auth.key)?).sign(data).to_vec()),
+        JwtAlgorithm::ES256 => ecdsa(&ring::signature::ECDSA_P256_SHA256_FIXED_SIGNING, &auth.key, data),
+        JwtAlgorithm::ES384 => ecdsa(&ring::signature::ECDSA_P384_SHA384_FIXED_SIGNING, &auth.key, data),
+    }
+}
+
+fn secret(auth: &JwtAuth) -> Result<Vec<u8>, String> {
+    if auth.key.is_empty() {
+        return Err("JWT signing needs a secret".to_string());
+    }
+    if auth.key_base64 {
+        STANDARD
+            .decode(auth.key.trim())
+            .map_err(|e| format!("Invalid base64 JWT secret: {}", e))
+    } else {
+        Ok(auth.key.as_bytes().to_vec())
+    }
+}
+
+fn hmac<D: Digest + BlockSizeUser>(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut mac = SimpleHmac::<D>::new_from_slice(key).map_err(|e| e.to_string())?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+fn rsa_key(pem: &str) -> Result<RsaPrivateKey, String> {
+    RsaPrivateKey::from_pkcs8_pem(pem)
+        .or_else(|_| RsaPrivateKey::from_pkcs1_pem(pem))
+        .map_err(|e| format!("Failed to parse RSA private key: {}", e))
+}
+
+/// ECDSA with the fixed-length `r || s` signature JWS expects
+fn ecdsa(
+    algorithm: &'static ring::signature::EcdsaSigningAlgorithm,
+    pem: &str,
+    data: &[u8],
+) -> Result<Vec<u8>, String> {
+    let (_, document) = rsa::pkcs8::SecretDocument::from_pem(pem)
+        .map_err(|e| format!("Failed to parse EC private key (PKCS#8 PEM expected): {}", e))?;
+    let rng = ring::rand::SystemRandom::new();
+    let key_pair = ring::signature::EcdsaKeyPair::from_pkcs8(algorithm, document.as_bytes(), &rng)
+        .map_err(|e| format!("Failed to load EC private key: {}", e))?;
+    let signature = key_pair
+        .sign(&rng, data)
+        .map_err(|e| format!("Failed to sign JWT: {}", e))?;
+    Ok(signature.as_ref().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(part: &str) -> Value {
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn signs_hmac_tokens_with_registered_claims() {
+        let auth = JwtAuth {
+            key: "your-256-bit-secret".to_string(),
+            claims: serde_json::json!({ "sub": "1234567890", "exp": 99 }).as_object().unwrap().clone(),
+            header: serde_json::json!({ "kid": "k1" }).as_object().unwrap().clone(),
+            expires_in: 60,
+            ..Default::default()
+        };
+        let token = token(&auth, 1_000).unwrap();
+        let parts: Vec<&str> = token.split('.').collect();
+
+        assert_eq!(decode(parts[0]), serde_json::json!({ "alg": "HS256", "typ": "JWT", "kid": "k1" }));
+        let claims = decode(parts[1]);
+        assert_eq!(claims["iat"], 1_000);
+        assert_eq!(claims["exp"], 99);
+        assert!(claims["jti"].is_string());
+
+        let expected = hmac::<Sha256>(b"your-256-bit-secret", format!("{}.{}", parts[0], parts[1]).as_bytes()).unwrap();
+        assert_eq!(URL_SAFE_NO_PAD.decode(parts[2]).unwrap(), expected);
+    }
+}
@@ -1,4 +1,19 @@
 mod http;
+mod http_client;
+mod cookies;
+mod timing;
+mod redirect;
+mod retry;
+mod body;
+mod cancel;
+mod oauth;
+mod auth;
+mod sigv4;
+mod digest;
+mod hawk;
+mod jwt;
+mod proxy;
+mod tls;
 mod websocket;
 mod mqtt;
 mod graphql;
@@ -7,9 +22,14 @@ mod sse;
 mod storage;
 mod storage_commands;
 mod import;
+mod har;
+mod bruno;
 mod mcp;
 mod mock_server;
 mod test_runner;
+mod iteration_data;
+mod run_plan;
+mod report;
 mod secret_providers;
 mod fake_data;
 mod playground;
@@ -17,6 +37,8 @@ mod grpc_client;
 mod template_functions;
 mod scripting;
 mod git_export;
+mod postman;
+mod openapi;
 mod sync;
 mod api;
 mod api_server;
@@ -31,6 +53,7 @@ use tauri::Manager;
 // Global connection managers
 pub static WS_CONNECTIONS: Lazy<DashMap<String, websocket::WsConnection>> = Lazy::new(DashMap::new);
 pub static MQTT_CONNECTIONS: Lazy<DashMap<String, mqtt::MqttConnection>> = Lazy::new(DashMap::new);
+pub static IN_FLIGHT_REQUESTS: Lazy<DashMap<String, cancel::InFlight>> = Lazy::new(DashMap::new);
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -84,6 +107,11 @@ pub fn run() {
             http::generate_curl_command,
             http::generate_code_snippet,
             http::parse_curl_command,
+            cancel::cancel_request,
+            // OAuth 2.0
+            oauth::oauth_authorize,
+            oauth::get_oauth_token,
+            oauth::clear_oauth_tokens,
             // WebSocket
             websocket::ws_connect,
             websocket::ws_send,
@@ -117,6 +145,11 @@ pub fn run() {
             storage_commands::save_history_item,
             storage_commands::clear_history,
             storage_commands::delete_history_item,
+            // Storage - Cookies
+            storage_commands::get_cookies,
+            storage_commands::save_cookie,
+            storage_commands::delete_cookie,
+            storage_commands::clear_cookies,
             // Storage - Global Variables
             storage_commands::save_global_variable,
             storage_commands::delete_global_variable,
@@ -136,6 +169,14 @@ pub fn run() {
             storage_commands::toggle_mcp_server,
             // Storage - Settings
             storage_commands::save_active_environment_id,
+            storage_commands::get_http_settings,
+            storage_commands::save_http_settings,
+            storage_commands::get_proxy_settings,
+            storage_commands::save_proxy_settings,
+            storage_commands::get_tls_settings,
+            storage_commands::save_tls_settings,
+            storage_commands::get_auth_profiles,
+            storage_commands::save_auth_profiles,
             // Storage - Test Runs
             storage_commands::save_test_run,
             storage_commands::load_test_runs,
@@ -151,6 +192,10 @@ pub fn run() {
             // Import
             import::import_openapi,
             import::import_postman,
+            import::import_postman_environment,
+            import::import_har,
+            import::import_insomnia,
+            import::import_bruno,
             import::import_from_url,
             import::generate_mock_response,
             import::generate_mock_response_smart,
@@ -172,6 +217,7 @@ pub fn run() {
             test_runner::run_collection_tests,
             test_runner::create_test_config,
             test_runner::evaluate_jsonpath_test,
+            iteration_data::load_iteration_data,
             // Secret Providers
             secret_providers::fetch_aws_secrets,
             secret_providers::fetch_gcp_secrets,
@@ -228,6 +274,18 @@ pub fn run() {
             git_export::export_collection_yaml,
             git_export::import_collection_yaml,
             git_export::export_all_collections_yaml,
+            // Postman Export
+            postman::export_collection_postman,
+            // OpenAPI Export
+            openapi::export_collection_openapi,
+            // HAR Export
+            har::export_history_har,
+            har::export_test_run_har,
+            // Test Reports (JUnit XML, TAP, HTML)
+            report::export_test_run_report,
+            report::export_test_run_history_report,
+            // Bruno Export
+            bruno::export_bruno,
             // Sync Config
             sync::sync_init,
             sync::sync_get_config,
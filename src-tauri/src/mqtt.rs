@@ -1,8 +1,13 @@
-use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS, Transport};
+use rumqttc::{AsyncClient, Event, MqttOptions, Packet, QoS, TlsConfiguration, Transport};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::oneshot;
+use url::Url;
+
+use crate::http_client;
+use crate::storage::Storage;
 
 pub struct MqttConnection {
     pub client: AsyncClient,
@@ -32,6 +37,31 @@ fn qos_from_u8(qos: u8) -> QoS {
     }
 }
 
+/// TLS transport with the workspace's CA roots and client certificate for the broker.
+///
+/// rumqttc always verifies the broker certificate, so the workspace's
+/// "accept invalid certificates" setting does not apply to MQTT.
+async fn tls_transport(
+    app: &AppHandle,
+    broker: &str,
+    port: u16,
+    workspace_id: Option<&str>,
+) -> Result<Transport, String> {
+    let storage = app.state::<Arc<Storage>>();
+    let tls = http_client::workspace_settings(&storage, workspace_id).tls;
+    let url = Url::parse(&format!("mqtts://{}:{}", broker, port)).map_err(|e| format!("Invalid broker: {}", e))?;
+    if !tls.is_customized_for(&url) {
+        return Ok(Transport::tls_with_default_config());
+    }
+
+    let identity = tls.identity_for(&url).await?;
+    Ok(Transport::tls_with_config(TlsConfiguration::Simple {
+        ca: tls.root_bundle_pem()?,
+        alpn: None,
+        client_auth: identity.map(|i| (i.cert_chain_pem(), i.key_pem())),
+    }))
+}
+
 #[tauri::command]
 pub async fn mqtt_connect(
     app: AppHandle,
@@ -42,6 +72,7 @@ pub async fn mqtt_connect(
     username: Option<String>,
     password: Option<String>,
     use_tls: bool,
+    workspace_id: Option<String>,
 ) -> Result<String, String> {
     let mut mqttoptions = MqttOptions::new(&client_id, &broker, port);
     mqttoptions.set_keep_alive(Duration::from_secs(30));
@@ -53,7 +84,7 @@ pub async fn mqtt_connect(
     }
 
     if use_tls {
-        mqttoptions.set_transport(Transport::tls_with_default_config());
+        mqttoptions.set_transport(tls_transport(&app, &broker, port, workspace_id.as_deref()).await?);
     }
 
     let (client, mut eventloop) = AsyncClient::new(mqttoptions, 10);
@@ -0,0 +1,671 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use url::Url;
+
+use crate::http_client;
+use crate::storage::Storage;
+
+/// How long the loopback listener and device code polling wait for the user
+const AUTHORIZATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Tokens expiring within this many seconds are refreshed before use
+const EXPIRY_MARGIN_SECS: i64 = 30;
+
+/// Held while a profile's token is fetched, refreshed or stored, keyed by
+/// workspace and profile id. Concurrent requests wait for the one refresh in
+/// progress instead of each spending the (possibly rotating) refresh token.
+static TOKEN_LOCKS: Lazy<DashMap<(String, String), Arc<tokio::sync::Mutex<()>>>> = Lazy::new(DashMap::new);
+
+fn token_lock(workspace_id: &str, profile_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+    TOKEN_LOCKS
+        .entry((workspace_id.to_string(), profile_id.to_string()))
+        .or_default()
+        .clone()
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GrantType {
+    #[default]
+    ClientCredentials,
+    Password,
+    AuthorizationCode,
+    DeviceCode,
+}
+
+/// Where the client id and secret go in token requests
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClientAuthentication {
+    /// HTTP Basic `Authorization` header
+    #[default]
+    BasicHeader,
+    /// `client_id` and `client_secret` form parameters
+    RequestBody,
+}
+
+/// An OAuth 2.0 profile requests can authenticate with (stored in workspace.yaml).
+///
+/// The client secret and password are kept in the git-ignored auth.local.yaml.
+/// String fields may reference environment variables as `{{name}}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub grant_type: GrantType,
+    pub token_url: String,
+    /// Authorization endpoint (authorization code grant)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization_url: Option<String>,
+    /// Device authorization endpoint (device code grant)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_authorization_url: Option<String>,
+    pub client_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub client_authentication: ClientAuthentication,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub scope: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+    /// Resource owner credentials (password grant)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Port of the loopback redirect listener (`http://127.0.0.1:{port}/callback`), 0 picks a free port
+    #[serde(default)]
+    pub redirect_port: u16,
+    /// Send a PKCE code challenge with the authorization code grant
+    #[serde(default = "default_true")]
+    pub pkce: bool,
+    /// Additional parameters for authorization and token requests (e.g. `resource`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_params: HashMap<String, String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A token obtained for a profile, cached per environment in oauth-tokens.local.yaml
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthToken {
+    pub profile_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment_id: Option<String>,
+    pub access_token: String,
+    #[serde(default = "default_token_type")]
+    pub token_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// Unix timestamp in seconds (absent when the server did not say)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+fn default_token_type() -> String {
+    "Bearer".to_string()
+}
+
+impl OAuthToken {
+    fn is_fresh(&self, now: i64) -> bool {
+        self.expires_at.is_none_or(|expires_at| expires_at - EXPIRY_MARGIN_SECS > now)
+    }
+
+    /// The `Authorization` header value for this token
+    pub fn authorization(&self) -> String {
+        if self.token_type.is_empty() || self.token_type.eq_ignore_ascii_case("bearer") {
+            format!("Bearer {}", self.access_token)
+        } else {
+            format!("{} {}", self.token_type, self.access_token)
+        }
+    }
+}
+
+/// Sent to the frontend when the user has to act in the browser
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorizationPrompt {
+    pub profile_id: String,
+    pub url: String,
+    /// Code to enter on the verification page (device code grant)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    token_type: Option<String>,
+    refresh_token: Option<String>,
+    expires_in: Option<Value>,
+    scope: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorization {
+    device_code: String,
+    user_code: String,
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: Option<u64>,
+    interval: Option<u64>,
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+// ============ Token Lookup ============
+
+/// The `Authorization` header value for a profile, using the cached token of the
+/// active environment. Expired tokens are refreshed, and client credentials and
+/// password grants fetch a new token when there is none. Interactive grants must
+/// have been authorized with `oauth_authorize` first.
+pub async fn authorization(storage: &Storage, workspace_id: Option<&str>, profile_id: &str) -> Result<String, String> {
+    let workspace_id = workspace_id
+        .map(|s| s.to_string())
+        .or_else(|| storage.get_active_workspace_id().ok().flatten())
+        .ok_or("No active workspace")?;
+    let (profile, environment_id) = load_profile(storage, &workspace_id, profile_id)?;
+    let cached = cached_token(storage, &workspace_id, &profile.id, &environment_id)?;
+    if let Some(token) = cached.filter(|t| t.is_fresh(now())) {
+        return Ok(token.authorization());
+    }
+
+    // Another request may have refreshed the token while this one waited
+    let lock = token_lock(&workspace_id, &profile.id);
+    let _guard = lock.lock().await;
+    let cached = cached_token(storage, &workspace_id, &profile.id, &environment_id)?;
+    if let Some(token) = cached.as_ref().filter(|t| t.is_fresh(now())) {
+        return Ok(token.authorization());
+    }
+
+    let client = http_client::client_for_workspace(storage, Some(&workspace_id), &profile.token_url).await?;
+    let refreshed = match cached.and_then(|t| t.refresh_token) {
+        Some(refresh_token) => refresh(&client, &profile, &refresh_token).await.ok(),
+        None => None,
+    };
+    let response = match (refreshed, profile.grant_type) {
+        (Some(response), _) => response,
+        (None, GrantType::ClientCredentials | GrantType::Password) => request_direct_token(&client, &profile).await?,
+        (None, _) => {
+            return Err(format!(
+                "Auth profile \"{}\" has no valid token, authorize it first",
+                profile.name
+            ))
+        }
+    };
+
+    let token = store_token(storage, &workspace_id, &profile, environment_id, response)?;
+    Ok(token.authorization())
+}
+
+/// The cached token of a profile for an environment, if any
+fn cached_token(
+    storage: &Storage,
+    workspace_id: &str,
+    profile_id: &str,
+    environment_id: &Option<String>,
+) -> Result<Option<OAuthToken>, String> {
+    Ok(storage
+        .get_oauth_tokens(workspace_id)?
+        .into_iter()
+        .find(|t| t.profile_id == profile_id && &t.environment_id == environment_id))
+}
+
+/// `authorization` for a request that may reference a profile
+pub async fn authorization_for(
+    storage: &Storage,
+    workspace_id: Option<&str>,
+    profile_id: Option<&str>,
+) -> Result<Option<String>, String> {
+    match profile_id.filter(|id| !id.is_empty()) {
+        Some(profile_id) => authorization(storage, workspace_id, profile_id).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Load a profile with its secrets and `{{variables}}` of the active environment
+/// resolved. Returns the environment id tokens are cached under.
+fn load_profile(storage: &Storage, workspace_id: &str, profile_id: &str) -> Result<(AuthProfile, Option<String>), String> {
+    let profile = storage
+        .get_auth_profiles(workspace_id)?
+        .into_iter()
+        .find(|p| p.id == profile_id || p.name == profile_id)
+        .ok_or_else(|| format!("Auth profile not found: {}", profile_id))?;
+
+    let environment_id = storage.get_active_environment_id(workspace_id)?;
+    let mut variables: HashMap<String, String> = storage
+        .get_global_variables(workspace_id)?
+        .into_iter()
+        .filter(|v| v.enabled)
+        .map(|v| (v.key, v.value))
+        .collect();
+    if let Some(environment) = storage
+        .get_environments(workspace_id)?
+        .into_iter()
+        .find(|e| Some(&e.id) == environment_id.as_ref())
+    {
+        variables.extend(environment.variables.into_iter().filter(|v| v.enabled).map(|v| (v.key, v.value)));
+    }
+
+    Ok((resolve_variables(profile, &variables), environment_id))
+}
+
+fn resolve_variables(mut profile: AuthProfile, variables: &HashMap<String, String>) -> AuthProfile {
+    let resolve = |value: &mut String| {
+        for (key, replacement) in variables {
+            *value = value.replace(&format!("{{{{{}}}}}", key), replacement);
+        }
+    };
+    for value in [&mut profile.token_url, &mut profile.client_id, &mut profile.scope] {
+        resolve(value);
+    }
+    for value in [
+        &mut profile.authorization_url,
+        &mut profile.device_authorization_url,
+        &mut profile.client_secret,
+        &mut profile.audience,
+        &mut profile.username,
+        &mut profile.password,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        resolve(value);
+    }
+    profile.extra_params.values_mut().for_each(resolve);
+    profile
+}
+
+/// Cache a token response, keeping the previous refresh token when the server did not rotate it.
+/// Callers hold the profile's `token_lock`.
+fn store_token(
+    storage: &Storage,
+    workspace_id: &str,
+    profile: &AuthProfile,
+    environment_id: Option<String>,
+    response: TokenResponse,
+) -> Result<OAuthToken, String> {
+    let previous = cached_token(storage, workspace_id, &profile.id, &environment_id)?;
+
+    let expires_in = match &response.expires_in {
+        Some(Value::Number(n)) => n.as_i64(),
+        Some(Value::String(s)) => s.parse().ok(),
+        _ => None,
+    };
+    let token = OAuthToken {
+        profile_id: profile.id.clone(),
+        environment_id,
+        access_token: response.access_token.ok_or("Token response has no access_token")?,
+        token_type: response.token_type.unwrap_or_else(default_token_type),
+        refresh_token: response.refresh_token.or(previous.and_then(|t| t.refresh_token)),
+        expires_at: expires_in.map(|secs| now() + secs),
+        scope: response.scope,
+    };
+    storage.update_oauth_tokens(workspace_id, |tokens| {
+        tokens.retain(|t| t.profile_id != token.profile_id || t.environment_id != token.environment_id);
+        tokens.push(token.clone());
+    })?;
+    Ok(token)
+}
+
+// ============ Token Endpoint ============
+
+/// POST a token request and parse the response (errors per RFC 6749 section 5.2)
+async fn token_request(
+    client: &reqwest::Client,
+    profile: &AuthProfile,
+    url: &str,
+    mut params: Vec<(String, String)>,
+) -> Result<TokenResponse, String> {
+    let mut request = client.post(url).header("Accept", "application/json");
+    let secret = profile.client_secret.as_deref().filter(|s| !s.is_empty());
+    match (profile.client_authentication, secret) {
+        (ClientAuthentication::BasicHeader, Some(secret)) => {
+            // Credentials are form-encoded before Basic encoding (RFC 6749 section 2.3.1)
+            request = request.basic_auth(
+                urlencoding::encode(&profile.client_id),
+                Some(urlencoding::encode(secret)),
+            );
+        }
+        (_, secret) => {
+            params.push(("client_id".to_string(), profile.client_id.clone()));
+            if let Some(secret) = secret {
+                params.push(("client_secret".to_string(), secret.to_string()));
+            }
+        }
+    }
+
+    let response = request
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| format!("Token request failed: {}", e))?;
+    let status = response.status();
+    let body = response.text().await.map_err(|e| format!("Token request failed: {}", e))?;
+
+    // Some servers answer with a form-encoded body despite the Accept header
+    let token: TokenResponse = serde_json::from_str(&body).or_else(|_| {
+        serde_json::to_value(url::form_urlencoded::parse(body.as_bytes()).into_owned().collect::<HashMap<_, _>>())
+            .and_then(serde_json::from_value)
+            .map_err(|_| format!("Unexpected token response ({}): {}", status, body))
+    })?;
+    if let Some(error) = &token.error {
+        return Err(match &token.error_description {
+            Some(description) => format!("{}: {}", error, description),
+            None => error.clone(),
+        });
+    }
+    if !status.is_success() {
+        return Err(format!("Token request failed ({}): {}", status, body));
+    }
+    Ok(token)
+}
+
+fn with_extra_params(profile: &AuthProfile, mut params: Vec<(String, String)>) -> Vec<(String, String)> {
+    if !profile.scope.is_empty() {
+        params.push(("scope".to_string(), profile.scope.clone()));
+    }
+    if let Some(audience) = profile.audience.as_ref().filter(|a| !a.is_empty()) {
+        params.push(("audience".to_string(), audience.clone()));
+    }
+    params.extend(profile.extra_params.iter().map(|(k, v)| (k.clone(), v.clone())));
+    params
+}
+
+/// Client credentials and password grants
+async fn request_direct_token(client: &reqwest::Client, profile: &AuthProfile) -> Result<TokenResponse, String> {
+    let params = match profile.grant_type {
+        GrantType::ClientCredentials => vec![("grant_type".to_string(), "client_credentials".to_string())],
+        GrantType::Password => vec![
+            ("grant_type".to_string(), "password".to_string()),
+            ("username".to_string(), profile.username.clone().unwrap_or_default()),
+            ("password".to_string(), profile.password.clone().unwrap_or_default()),
+        ],
+        _ => return Err("This grant type needs user interaction".to_string()),
+    };
+    token_request(client, profile, &profile.token_url, with_extra_params(profile, params)).await
+}
+
+async fn refresh(client: &reqwest::Client, profile: &AuthProfile, refresh_token: &str) -> Result<TokenResponse, String> {
+    let params = vec![
+        ("grant_type".to_string(), "refresh_token".to_string()),
+        ("refresh_token".to_string(), refresh_token.to_string()),
+    ];
+    token_request(client, profile, &profile.token_url, params).await
+}
+
+// ============ Authorization Code + PKCE ============
+
+/// A PKCE code verifier and its S256 challenge (RFC 7636)
+fn pkce_pair() -> (String, String) {
+    let verifier = URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>());
+    let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+    (verifier, challenge)
+}
+
+async fn authorize_code(
+    client: &reqwest::Client,
+    profile: &AuthProfile,
+    prompt: impl FnOnce(AuthorizationPrompt),
+) -> Result<TokenResponse, String> {
+    let authorization_url = profile
+        .authorization_url
+        .as_deref()
+        .filter(|u| !u.is_empty())
+        .ok_or("The authorization code grant needs an authorization URL")?;
+
+    let listener = TcpListener::bind(("127.0.0.1", profile.redirect_port))
+        .await
+        .map_err(|e| format!("Failed to start the redirect listener on port {}: {}", profile.redirect_port, e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", port);
+
+    let state = uuid::Uuid::new_v4().to_string();
+    let (verifier, challenge) = pkce_pair();
+    let mut url = Url::parse(authorization_url).map_err(|e| format!("Invalid authorization URL: {}", e))?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", &profile.client_id)
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("state", &state);
+        if profile.pkce {
+            query
+                .append_pair("code_challenge", &challenge)
+                .append_pair("code_challenge_method", "S256");
+        }
+        for (key, value) in with_extra_params(profile, Vec::new()) {
+            query.append_pair(&key, &value);
+        }
+    }
+
+    prompt(AuthorizationPrompt {
+        profile_id: profile.id.clone(),
+        url: url.to_string(),
+        user_code: None,
+    });
+
+    let callback = tokio::time::timeout(AUTHORIZATION_TIMEOUT, wait_for_callback(&listener))
+        .await
+        .map_err(|_| "Timed out waiting for the authorization redirect".to_string())??;
+    let params: HashMap<String, String> = callback.query_pairs().into_owned().collect();
+    if let Some(error) = params.get("error") {
+        return Err(match params.get("error_description") {
+            Some(description) => format!("Authorization failed: {}: {}", error, description),
+            None => format!("Authorization failed: {}", error),
+        });
+    }
+    if params.get("state") != Some(&state) {
+        return Err("Authorization redirect has a mismatched state".to_string());
+    }
+    let code = params.get("code").ok_or("Authorization redirect has no code")?;
+
+    let mut token_params = vec![
+        ("grant_type".to_string(), "authorization_code".to_string()),
+        ("code".to_string(), code.clone()),
+        ("redirect_uri".to_string(), redirect_uri),
+    ];
+    if profile.pkce {
+        token_params.push(("code_verifier".to_string(), verifier));
+    }
+    token_request(client, profile, &profile.token_url, token_params).await
+}
+
+/// Accept connections on the loopback listener until the redirect arrives
+async fn wait_for_callback(listener: &TcpListener) -> Result<Url, String> {
+    loop {
+        let (mut stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        let mut buffer = vec![0u8; 8192];
+        let read = stream.read(&mut buffer).await.unwrap_or(0);
+        let head = String::from_utf8_lossy(&buffer[..read]);
+        let target = head.lines().next().and_then(|line| line.split_whitespace().nth(1)).unwrap_or("");
+
+        let url = Url::parse(&format!("http://127.0.0.1{}", target)).ok();
+        let Some(url) = url.filter(|u| u.path() == "/callback") else {
+            let _ = stream.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+            continue;
+        };
+
+        let body = "<html><body><h3>Authorization complete</h3><p>You can close this window and return to Istek.</p></body></html>";
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        return Ok(url);
+    }
+}
+
+// ============ Device Code ============
+
+async fn authorize_device(
+    client: &reqwest::Client,
+    profile: &AuthProfile,
+    prompt: impl FnOnce(AuthorizationPrompt),
+) -> Result<TokenResponse, String> {
+    let device_url = profile
+        .device_authorization_url
+        .as_deref()
+        .filter(|u| !u.is_empty())
+        .ok_or("The device code grant needs a device authorization URL")?;
+
+    let mut params = with_extra_params(profile, Vec::new());
+    params.push(("client_id".to_string(), profile.client_id.clone()));
+    let response = client
+        .post(device_url)
+        .header("Accept", "application/json")
+        .form(&params)
+        .send()
+        .await
+        .map_err(|e| format!("Device authorization failed: {}", e))?;
+    let status = response.status();
+    let body = response.text().await.map_err(|e| format!("Device authorization failed: {}", e))?;
+    let device: DeviceAuthorization = serde_json::from_str(&body)
+        .map_err(|_| format!("Device authorization failed ({}): {}", status, body))?;
+
+    prompt(AuthorizationPrompt {
+        profile_id: profile.id.clone(),
+        url: device.verification_uri_complete.clone().unwrap_or(device.verification_uri.clone()),
+        user_code: Some(device.user_code.clone()),
+    });
+
+    let expires_in = device.expires_in.map(Duration::from_secs).unwrap_or(AUTHORIZATION_TIMEOUT);
+    let deadline = tokio::time::Instant::now() + expires_in.min(AUTHORIZATION_TIMEOUT);
+    let mut interval = Duration::from_secs(device.interval.unwrap_or(5));
+    let params = vec![
+        ("grant_type".to_string(), "urn:ietf:params:oauth:grant-type:device_code".to_string()),
+        ("device_code".to_string(), device.device_code.clone()),
+    ];
+    loop {
+        tokio::time::sleep(interval).await;
+        if tokio::time::Instant::now() >= deadline {
+            return Err("The device code expired before it was authorized".to_string());
+        }
+        match token_request(client, profile, &profile.token_url, params.clone()).await {
+            Ok(token) => return Ok(token),
+            Err(e) if e.starts_with("authorization_pending") => {}
+            // RFC 8628 section 3.5: increase the interval by 5 seconds
+            Err(e) if e.starts_with("slow_down") => interval += Duration::from_secs(5),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+// ============ Commands ============
+
+/// Obtain a token for a profile, running the browser flow for interactive grants.
+/// Emits `oauth-authorization` with the URL (and user code) the user has to visit.
+#[tauri::command]
+pub async fn oauth_authorize(
+    app: tauri::AppHandle,
+    profile_id: String,
+    workspace_id: Option<String>,
+) -> Result<OAuthToken, String> {
+    let storage = app.state::<Arc<Storage>>();
+    let workspace_id = workspace_id
+        .or_else(|| storage.get_active_workspace_id().ok().flatten())
+        .ok_or("No active workspace")?;
+    let (profile, environment_id) = load_profile(&storage, &workspace_id, &profile_id)?;
+    let client = http_client::client_for_workspace(&storage, Some(&workspace_id), &profile.token_url).await?;
+
+    let prompt = |prompt: AuthorizationPrompt| {
+        if let Err(e) = open::that_detached(&prompt.url) {
+            log::warn!("Failed to open {} in the browser: {}", prompt.url, e);
+        }
+        let _ = app.emit("oauth-authorization", &prompt);
+    };
+    let response = match profile.grant_type {
+        GrantType::ClientCredentials | GrantType::Password => request_direct_token(&client, &profile).await?,
+        GrantType::AuthorizationCode => authorize_code(&client, &profile, prompt).await?,
+        GrantType::DeviceCode => authorize_device(&client, &profile, prompt).await?,
+    };
+    let lock = token_lock(&workspace_id, &profile.id);
+    let _guard = lock.lock().await;
+    store_token(&storage, &workspace_id, &profile, environment_id, response)
+}
+
+/// The cached token of a profile for the active environment, if any
+#[tauri::command]
+pub async fn get_oauth_token(
+    app: tauri::AppHandle,
+    profile_id: String,
+    workspace_id: Option<String>,
+) -> Result<Option<OAuthToken>, String> {
+    let storage = app.state::<Arc<Storage>>();
+    let workspace_id = workspace_id
+        .or_else(|| storage.get_active_workspace_id().ok().flatten())
+        .ok_or("No active workspace")?;
+    let environment_id = storage.get_active_environment_id(&workspace_id)?;
+    cached_token(&storage, &workspace_id, &profile_id, &environment_id)
+}
+
+/// Forget the cached tokens of a profile (in all environments)
+#[tauri::command]
+pub async fn clear_oauth_tokens(
+    app: tauri::AppHandle,
+    profile_id: String,
+    workspace_id: Option<String>,
+) -> Result<(), String> {
+    let storage = app.state::<Arc<Storage>>();
+    let workspace_id = workspace_id
+        .or_else(|| storage.get_active_workspace_id().ok().flatten())
+        .ok_or("No active workspace")?;
+    let lock = token_lock(&workspace_id, &profile_id);
+    let _guard = lock.lock().await;
+    storage.update_oauth_tokens(&workspace_id, |tokens| tokens.retain(|t| t.profile_id != profile_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_pkce_challenge() {
+        let (verifier, challenge) = pkce_pair();
+        assert_eq!(verifier.len(), 43);
+        assert_eq!(challenge, URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes())));
+    }
+
+    #[test]
+    fn resolves_profile_variables() {
+        let profile = AuthProfile {
+            token_url: "{{baseUrl}}/oauth/token".to_string(),
+            client_secret: Some("{{secret}}".to_string()),
+            extra_params: HashMap::from([("resource".to_string(), "{{baseUrl}}".to_string())]),
+            ..Default::default()
+        };
+        let variables = HashMap::from([
+            ("baseUrl".to_string(), "https://auth.example.com".to_string()),
+            ("secret".to_string(), "s3cr3t".to_string()),
+        ]);
+        let profile = resolve_variables(profile, &variables);
+        assert_eq!(profile.token_url, "https://auth.example.com/oauth/token");
+        assert_eq!(profile.client_secret.as_deref(), Some("s3cr3t"));
+        assert_eq!(profile.extra_params["resource"], "https://auth.example.com");
+    }
+}
@@ -0,0 +1,894 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tauri::Manager;
+
+use crate::storage::{HistoryItem, Storage};
+
+// ============ OpenAPI Generation ============
+
+/// `{{variable}}` templates in urls and bodies
+static TEMPLATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{\{\s*([^{}]+?)\s*\}\}").unwrap());
+/// `:id` path segments
+static COLON_PARAM: Lazy<Regex> = Lazy::new(|| Regex::new(r"^:([A-Za-z_][\w.-]*)$").unwrap());
+/// `{id}` path parameters of an OpenAPI path template
+static PATH_PARAM: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{([^{}]+)\}").unwrap());
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OpenApiVersion {
+    V3_0,
+    V3_1,
+}
+
+impl OpenApiVersion {
+    /// "3.0", "3.1" or a full version such as "3.0.3"; 3.1 when not given
+    pub fn parse(version: Option<&str>) -> Result<Self, String> {
+        match version.map(str::trim) {
+            None | Some("") => Ok(Self::V3_1),
+            Some(v) if v == "3.0" || v.starts_with("3.0.") => Ok(Self::V3_0),
+            Some(v) if v == "3.1" || v.starts_with("3.1.") => Ok(Self::V3_1),
+            Some(v) => Err(format!("Unsupported OpenAPI version: {} (expected 3.0 or 3.1)", v)),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::V3_0 => "3.0.3",
+            Self::V3_1 => "3.1.0",
+        }
+    }
+}
+
+/// Serialize `(key, value)` pairs as a map, keeping their order
+fn ordered<S: Serializer, V: Serialize>(entries: &[(String, V)], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_map(entries.iter().map(|(key, value)| (key, value)))
+}
+
+#[derive(Debug, Serialize)]
+struct Document {
+    openapi: &'static str,
+    info: Info,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    servers: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<Value>,
+    #[serde(serialize_with = "ordered")]
+    paths: Vec<(String, PathItem)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    components: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct Info {
+    title: String,
+    version: String,
+}
+
+/// Operations by lowercase method
+#[derive(Debug, Default, Serialize)]
+struct PathItem {
+    #[serde(flatten, serialize_with = "ordered")]
+    operations: Vec<(String, Operation)>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Operation {
+    summary: String,
+    operation_id: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    parameters: Vec<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_body: Option<Value>,
+    #[serde(serialize_with = "ordered")]
+    responses: Vec<(String, Value)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    security: Option<Vec<Value>>,
+}
+
+fn str_field<'a>(value: &'a Value, name: &str) -> &'a str {
+    value.get(name).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn enabled(value: &Value) -> bool {
+    value.get("enabled").and_then(|e| e.as_bool()).unwrap_or(true)
+}
+
+/// Enabled `{ key, value }` items that have a key
+fn pairs(items: Option<&Value>) -> Vec<(&str, &str)> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter(|item| enabled(item) && !str_field(item, "key").is_empty())
+                .map(|item| (str_field(item, "key"), str_field(item, "value")))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// ============ Schema inference ============
+
+/// The type of a schema, ignoring "null" in 3.1 type arrays
+fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => Some(t),
+        Some(Value::Array(types)) => types.iter().filter_map(|t| t.as_str()).find(|t| *t != "null"),
+        _ => None,
+    }
+}
+
+fn null_schema(version: OpenApiVersion) -> Value {
+    match version {
+        OpenApiVersion::V3_0 => json!({ "nullable": true }),
+        OpenApiVersion::V3_1 => json!({ "type": "null" }),
+    }
+}
+
+fn is_null_schema(schema: &Value) -> bool {
+    schema.get("type").and_then(|t| t.as_str()) == Some("null")
+        || (schema.get("type").is_none() && schema.get("nullable") == Some(&Value::Bool(true)))
+}
+
+fn make_nullable(mut schema: Value, version: OpenApiVersion) -> Value {
+    let Some(obj) = schema.as_object_mut() else { return schema };
+    match version {
+        OpenApiVersion::V3_0 => {
+            obj.insert("nullable".to_string(), Value::Bool(true));
+        }
+        OpenApiVersion::V3_1 => match obj.get("type").cloned() {
+            Some(Value::String(t)) => {
+                obj.insert("type".to_string(), json!([t, "null"]));
+            }
+            Some(Value::Array(mut types)) if !types.contains(&json!("null")) => {
+                types.push(json!("null"));
+                obj.insert("type".to_string(), Value::Array(types));
+            }
+            _ => {}
+        },
+    }
+    schema
+}
+
+fn string_format(value: &str) -> Option<&'static str> {
+    static UUID: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$").unwrap()
+    });
+    static DATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\d{4}-\d{2}-\d{2}$").unwrap());
+    static EMAIL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^[^@\s]+@[^@\s]+\.[^@\s]+$").unwrap());
+
+    if UUID.is_match(value) {
+        Some("uuid")
+    } else if DATE.is_match(value) {
+        Some("date")
+    } else if chrono::DateTime::parse_from_rfc3339(value).is_ok() {
+        Some("date-time")
+    } else if EMAIL.is_match(value) {
+        Some("email")
+    } else if value.starts_with("http://") || value.starts_with("https://") {
+        Some("uri")
+    } else {
+        None
+    }
+}
+
+/// A JSON schema describing a sample value
+fn infer_schema(value: &Value, version: OpenApiVersion) -> Value {
+    match value {
+        Value::Null => null_schema(version),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(n) if n.is_f64() => json!({ "type": "number" }),
+        Value::Number(_) => json!({ "type": "integer" }),
+        Value::String(s) => match string_format(s) {
+            Some(format) => json!({ "type": "string", "format": format }),
+            None => json!({ "type": "string" }),
+        },
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| infer_schema(item, version))
+                .reduce(|a, b| merge_schemas(a, b, version))
+                .unwrap_or_else(|| json!({}));
+            json!({ "type": "array", "items": items })
+        }
+        Value::Object(obj) => {
+            let properties: Map<String, Value> = obj
+                .iter()
+                .map(|(key, v)| (key.clone(), infer_schema(v, version)))
+                .collect();
+            let mut schema = json!({ "type": "object", "properties": properties });
+            if !obj.is_empty() {
+                schema["required"] = obj.keys().cloned().collect();
+            }
+            schema
+        }
+    }
+}
+
+/// Combine the schemas of two samples of the same thing: object properties
+/// are merged (required only when present in both), a null sample makes the
+/// other nullable, and otherwise the first schema wins
+fn merge_schemas(a: Value, b: Value, version: OpenApiVersion) -> Value {
+    if a == b {
+        return a;
+    }
+    if is_null_schema(&a) {
+        return make_nullable(b, version);
+    }
+    if is_null_schema(&b) {
+        return make_nullable(a, version);
+    }
+
+    let nullable = |schema: &Value| match schema.get("type") {
+        Some(Value::Array(types)) => types.contains(&json!("null")),
+        _ => schema.get("nullable") == Some(&Value::Bool(true)),
+    };
+    let was_nullable = nullable(&a) || nullable(&b);
+
+    let merged = match (schema_type(&a), schema_type(&b)) {
+        (Some("object"), Some("object")) => {
+            let mut properties = a.get("properties").and_then(|p| p.as_object()).cloned().unwrap_or_default();
+            for (key, schema) in b.get("properties").and_then(|p| p.as_object()).into_iter().flatten() {
+                let merged = match properties.remove(key) {
+                    Some(existing) => merge_schemas(existing, schema.clone(), version),
+                    None => schema.clone(),
+                };
+                properties.insert(key.clone(), merged);
+            }
+            let required_b = b.get("required").and_then(|r| r.as_array()).cloned().unwrap_or_default();
+            let required: Vec<Value> = a
+                .get("required")
+                .and_then(|r| r.as_array())
+                .into_iter()
+                .flatten()
+                .filter(|name| required_b.contains(name))
+                .cloned()
+                .collect();
+            let mut schema = json!({ "type": "object", "properties": properties });
+            if !required.is_empty() {
+                schema["required"] = Value::Array(required);
+            }
+            schema
+        }
+        (Some("array"), Some("array")) => {
+            let items = merge_schemas(a["items"].clone(), b["items"].clone(), version);
+            json!({ "type": "array", "items": items })
+        }
+        (Some("integer"), Some("number")) | (Some("number"), Some("integer")) => json!({ "type": "number" }),
+        _ => a,
+    };
+    if was_nullable {
+        make_nullable(merged, version)
+    } else {
+        merged
+    }
+}
+
+/// Schema of a query, header or path parameter value
+fn scalar_schema(value: &str) -> Value {
+    if value.parse::<i64>().is_ok() {
+        json!({ "type": "integer" })
+    } else if value.parse::<f64>().is_ok() {
+        json!({ "type": "number" })
+    } else if value == "true" || value == "false" {
+        json!({ "type": "boolean" })
+    } else {
+        json!({ "type": "string" })
+    }
+}
+
+fn parameter(name: &str, location: &str, value: &str, required: bool) -> Value {
+    let schema = scalar_schema(value);
+    // Template values are placeholders, not examples
+    let example = (!value.is_empty() && !value.contains("{{")).then(|| match schema_type(&schema) {
+        Some("integer" | "number" | "boolean") => serde_json::from_str(value).unwrap_or_else(|_| Value::from(value)),
+        _ => Value::from(value),
+    });
+    let mut parameter = json!({ "name": name, "in": location, "schema": schema });
+    if required {
+        parameter["required"] = Value::Bool(true);
+    }
+    if let Some(example) = example {
+        parameter["example"] = example;
+    }
+    parameter
+}
+
+// ============ Paths ============
+
+/// A parameter name usable in a path template
+fn parameter_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .collect();
+    if name.is_empty() { "param".to_string() } else { name }
+}
+
+/// Split a request url into its origin (which may be a `{{baseUrl}}`
+/// template) and an OpenAPI path template; `:id`, `{id}` and `{{id}}`
+/// segments all become `{id}`
+fn path_template(url: &str) -> (String, String) {
+    let url = url.split(['?', '#']).next().unwrap_or("");
+    let (origin, path) = if let Some(scheme_end) = url.find("://") {
+        let authority_end = url[scheme_end + 3..].find('/').map(|i| i + scheme_end + 3).unwrap_or(url.len());
+        url.split_at(authority_end)
+    } else if url.starts_with("{{") {
+        let end = url.find("}}").map(|i| i + 2).unwrap_or(url.len());
+        url.split_at(end)
+    } else {
+        ("", url)
+    };
+
+    let segments: Vec<String> = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            if let Some(captures) = COLON_PARAM.captures(segment) {
+                return format!("{{{}}}", &captures[1]);
+            }
+            let segment = TEMPLATE.replace_all(segment, |c: &regex::Captures| format!("{{{}}}", parameter_name(&c[1])));
+            PATH_PARAM
+                .replace_all(&segment, |c: &regex::Captures| format!("{{{}}}", parameter_name(&c[1])))
+                .into_owned()
+        })
+        .collect();
+
+    (origin.trim_end_matches('/').to_string(), format!("/{}", segments.join("/")))
+}
+
+fn path_parameters(path: &str) -> Vec<String> {
+    PATH_PARAM.captures_iter(path).map(|c| c[1].to_string()).collect()
+}
+
+/// An OpenAPI server for a request origin; `{{variables}}` become server
+/// variables, defaulting to the collection variable's value
+fn server(origin: &str, variables: &[(&str, &str)]) -> Value {
+    let url = TEMPLATE.replace_all(origin, |c: &regex::Captures| format!("{{{}}}", parameter_name(&c[1])));
+    let mut server = json!({ "url": url });
+    let names: Vec<(String, String)> = TEMPLATE
+        .captures_iter(origin)
+        .map(|c| {
+            let default = variables
+                .iter()
+                .find(|(key, _)| *key == &c[1])
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_else(|| c[0].to_string());
+            (parameter_name(&c[1]), default)
+        })
+        .collect();
+    if !names.is_empty() {
+        server["variables"] = names
+            .into_iter()
+            .map(|(name, default)| (name, json!({ "default": default })))
+            .collect::<Map<_, _>>()
+            .into();
+    }
+    server
+}
+
+// ============ Bodies ============
+
+/// A JSON body and whether it can serve as an example, which bodies with
+/// `{{variables}}` cannot; those are not even JSON until the variables are
+/// resolved, so they are parsed with placeholders
+fn parse_json_body(body: &str) -> Option<(Value, bool)> {
+    let is_example = !TEMPLATE.is_match(body);
+    if let Ok(value) = serde_json::from_str::<Value>(body) {
+        return Some((value, is_example));
+    }
+    let placeholders = TEMPLATE.replace_all(body, "0");
+    serde_json::from_str::<Value>(&placeholders).ok().map(|value| (value, false))
+}
+
+fn form_schema(fields: &[(String, Value)]) -> Value {
+    let properties: Map<String, Value> = fields.iter().cloned().collect();
+    json!({ "type": "object", "properties": properties })
+}
+
+fn request_body(request: &Value, version: OpenApiVersion) -> Option<Value> {
+    let body = str_field(request, "body");
+    let (content_type, media) = match str_field(request, "bodyType") {
+        "json" => {
+            let media = match parse_json_body(body) {
+                Some((value, true)) => json!({ "schema": infer_schema(&value, version), "example": value }),
+                Some((value, false)) => json!({ "schema": infer_schema(&value, version) }),
+                None => json!({ "schema": { "type": "object" } }),
+            };
+            ("application/json", media)
+        }
+        kind @ ("xml" | "html" | "raw") => {
+            let content_type = match kind {
+                "xml" => "application/xml",
+                "html" => "text/html",
+                _ => "text/plain",
+            };
+            let mut media = json!({ "schema": { "type": "string" } });
+            if !body.is_empty() {
+                media["example"] = Value::from(body);
+            }
+            (content_type, media)
+        }
+        "x-www-form-urlencoded" => {
+            let fields: Vec<(String, Value)> = pairs(request.get("urlEncodedData"))
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), scalar_schema(value)))
+                .collect();
+            ("application/x-www-form-urlencoded", json!({ "schema": form_schema(&fields) }))
+        }
+        "form-data" => {
+            let fields: Vec<(String, Value)> = request
+                .get("formData")
+                .and_then(|f| f.as_array())
+                .into_iter()
+                .flatten()
+                .filter(|f| enabled(f) && !str_field(f, "key").is_empty())
+                .map(|f| {
+                    let schema = if str_field(f, "type") == "file" {
+                        json!({ "type": "string", "format": "binary" })
+                    } else {
+                        scalar_schema(str_field(f, "value"))
+                    };
+                    (str_field(f, "key").to_string(), schema)
+                })
+                .collect();
+            ("multipart/form-data", json!({ "schema": form_schema(&fields) }))
+        }
+        _ => return None,
+    };
+
+    let mut content = Map::new();
+    content.insert(content_type.to_string(), media);
+    Some(json!({ "content": content }))
+}
+
+// ============ Responses ============
+
+fn header_value<'a>(response: &'a Value, name: &str) -> Option<&'a str> {
+    response
+        .get("headers")
+        .and_then(|h| h.as_object())?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_str())
+}
+
+/// The latest history response per status code for a collection request,
+/// matched by request id or by method and url
+fn history_responses<'a>(request: &Value, history: &'a [HistoryItem]) -> Vec<(u16, &'a Value)> {
+    let id = str_field(request, "id");
+    let method = str_field(request, "method");
+    let url = str_field(request, "url");
+
+    let mut items: Vec<&HistoryItem> = history
+        .iter()
+        .filter(|item| {
+            (!id.is_empty() && str_field(&item.request, "id") == id)
+                || (str_field(&item.request, "method").eq_ignore_ascii_case(method) && str_field(&item.request, "url") == url)
+        })
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.timestamp));
+
+    let mut responses: BTreeMap<u16, &Value> = BTreeMap::new();
+    for item in items {
+        let Some(response) = item.response.as_ref() else { continue };
+        let Some(status) = response.get("status").and_then(|s| s.as_u64()).filter(|s| (100..600).contains(s)) else {
+            continue;
+        };
+        responses.entry(status as u16).or_insert(response);
+    }
+    responses.into_iter().collect()
+}
+
+fn status_description(status: u16, response: &Value) -> String {
+    let status_text = str_field(response, "statusText");
+    if !status_text.is_empty() {
+        return status_text.to_string();
+    }
+    reqwest::StatusCode::from_u16(status)
+        .ok()
+        .and_then(|s| s.canonical_reason())
+        .unwrap_or("Response")
+        .to_string()
+}
+
+/// An OpenAPI response object for a recorded response
+fn response_object(status: u16, response: &Value, version: OpenApiVersion) -> Value {
+    let mut object = json!({ "description": status_description(status, response) });
+    let content_type = response
+        .get("contentType")
+        .and_then(|c| c.as_str())
+        .or_else(|| header_value(response, "content-type"))
+        .map(|c| c.split(';').next().unwrap_or("").trim().to_string())
+        .filter(|c| !c.is_empty());
+    let body = str_field(response, "body");
+    let is_binary = response.get("isBinary").and_then(|b| b.as_bool()).unwrap_or(false);
+
+    let media = if let Some(value) = serde_json::from_str::<Value>(body).ok().filter(|_| !is_binary && !body.is_empty()) {
+        Some((content_type.unwrap_or_else(|| "application/json".to_string()), json!({
+            "schema": infer_schema(&value, version),
+            "example": value,
+        })))
+    } else if is_binary {
+        let content_type = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
+        Some((content_type, json!({ "schema": { "type": "string", "format": "binary" } })))
+    } else if !body.is_empty() {
+        Some((content_type.unwrap_or_else(|| "text/plain".to_string()), json!({ "schema": { "type": "string" } })))
+    } else {
+        None
+    };
+    if let Some((content_type, media)) = media {
+        let mut content = Map::new();
+        content.insert(content_type, media);
+        object["content"] = Value::Object(content);
+    }
+    object
+}
+
+/// Responses of an operation: the latest recorded response per status code,
+/// otherwise the response schema saved on the request (from an import)
+fn responses(request: &Value, history: &[HistoryItem], version: OpenApiVersion) -> Vec<(String, Value)> {
+    let recorded = history_responses(request, history);
+    if !recorded.is_empty() {
+        return recorded
+            .into_iter()
+            .map(|(status, response)| (status.to_string(), response_object(status, response, version)))
+            .collect();
+    }
+    if let Some(schema) = request.get("responseSchema").filter(|s| s.is_object()) {
+        return vec![(
+            "200".to_string(),
+            json!({ "description": "OK", "content": { "application/json": { "schema": schema } } }),
+        )];
+    }
+    vec![("default".to_string(), json!({ "description": "Response" }))]
+}
+
+// ============ Security ============
+
+/// The security scheme name and definition for an `AuthConfig`; None for
+/// auth OpenAPI cannot describe (AWS Signature, Hawk)
+fn security_scheme(auth: &Value) -> Option<(String, Value)> {
+    let scheme = match str_field(auth, "type") {
+        "basic" => ("basicAuth".to_string(), json!({ "type": "http", "scheme": "basic" })),
+        "digest" => ("digestAuth".to_string(), json!({ "type": "http", "scheme": "digest" })),
+        "bearer" => ("bearerAuth".to_string(), json!({ "type": "http", "scheme": "bearer" })),
+        "jwt" => ("jwtAuth".to_string(), json!({ "type": "http", "scheme": "bearer", "bearerFormat": "JWT" })),
+        "api-key" => {
+            let name = str_field(auth, "apiKeyName");
+            let location = if str_field(auth, "apiKeyIn") == "query" { "query" } else { "header" };
+            (
+                format!("apiKey_{}", parameter_name(name)),
+                json!({ "type": "apiKey", "name": name, "in": location }),
+            )
+        }
+        "oauth2" => {
+            let oauth2 = auth.get("oauth2").cloned().unwrap_or(Value::Null);
+            let scopes: Map<String, Value> = str_field(&oauth2, "scope")
+                .split_whitespace()
+                .map(|scope| (scope.to_string(), Value::from("")))
+                .collect();
+            let token_url = str_field(&oauth2, "tokenUrl");
+            let auth_url = str_field(&oauth2, "authUrl");
+            let flows = match str_field(&oauth2, "grantType") {
+                "authorization_code" => json!({ "authorizationCode": { "authorizationUrl": auth_url, "tokenUrl": token_url, "scopes": scopes } }),
+                "implicit" => json!({ "implicit": { "authorizationUrl": auth_url, "scopes": scopes } }),
+                "password" => json!({ "password": { "tokenUrl": token_url, "scopes": scopes } }),
+                _ => json!({ "clientCredentials": { "tokenUrl": token_url, "scopes": scopes } }),
+            };
+            ("oauth2".to_string(), json!({ "type": "oauth2", "flows": flows }))
+        }
+        _ => return None,
+    };
+    Some(scheme)
+}
+
+/// The auth that applies to a request: its own, or the closest folder's or
+/// the collection's when it inherits. None means no auth.
+fn effective_auth<'a>(request: &'a Value, parents: &[&'a Value]) -> Option<&'a Value> {
+    let own = request.get("auth").filter(|a| str_field(a, "type") != "inherit");
+    let auth = own.or_else(|| {
+        parents.iter().rev().find_map(|parent| {
+            parent
+                .get("settings")
+                .and_then(|s| s.get("auth"))
+                .filter(|a| !matches!(str_field(a, "type"), "" | "inherit"))
+        })
+    })?;
+    (enabled(auth) && str_field(auth, "type") != "none").then_some(auth)
+}
+
+// ============ Collection walk ============
+
+struct Generator<'a> {
+    version: OpenApiVersion,
+    history: &'a [HistoryItem],
+    variables: Vec<(&'a str, &'a str)>,
+    collection_security: Option<String>,
+    servers: Vec<Value>,
+    tags: Vec<String>,
+    paths: Vec<(String, PathItem)>,
+    security_schemes: BTreeMap<String, Value>,
+    operation_ids: Vec<String>,
+}
+
+impl<'a> Generator<'a> {
+    fn operation_id(&mut self, name: &str, method: &str, path: &str) -> String {
+        let source = if name.trim().is_empty() { format!("{} {}", method, path) } else { name.to_string() };
+        let mut id = String::new();
+        for (i, word) in source
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .enumerate()
+        {
+            let word = word.to_lowercase();
+            if i == 0 {
+                id.push_str(&word);
+            } else {
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    id.extend(first.to_uppercase());
+                    id.push_str(chars.as_str());
+                }
+            }
+        }
+        if id.is_empty() {
+            id = "operation".to_string();
+        }
+        let mut unique = id.clone();
+        let mut n = 2;
+        while self.operation_ids.contains(&unique) {
+            unique = format!("{}{}", id, n);
+            n += 1;
+        }
+        self.operation_ids.push(unique.clone());
+        unique
+    }
+
+    fn security(&mut self, request: &'a Value, parents: &[&'a Value]) -> Option<Vec<Value>> {
+        let scheme = effective_auth(request, parents).and_then(security_scheme);
+        let name = scheme.as_ref().map(|(name, _)| name.clone());
+        if name == self.collection_security {
+            return None;
+        }
+        match scheme {
+            Some((name, definition)) => {
+                self.security_schemes.insert(name.clone(), definition);
+                Some(vec![json!({ name: [] })])
+            }
+            // No auth where the collection requires some
+            None => Some(Vec::new()),
+        }
+    }
+
+    fn add_request(&mut self, request: &'a Value, parents: &[&'a Value]) {
+        // Only HTTP requests are described; WebSocket, gRPC, ... have no OpenAPI form
+        if request.get("protocol").and_then(|p| p.as_str()).unwrap_or("http") != "http" {
+            return;
+        }
+        let method = str_field(request, "method").to_lowercase();
+        if method.is_empty() {
+            return;
+        }
+
+        // Relative urls are resolved against the closest base url
+        let mut url = str_field(request, "url").to_string();
+        if url.starts_with('/') {
+            if let Some(base) = parents
+                .iter()
+                .rev()
+                .find_map(|p| p.get("settings").and_then(|s| s.get("baseUrl")).and_then(|b| b.as_str()).filter(|b| !b.trim().is_empty()))
+            {
+                url = format!("{}{}", base.trim_end_matches('/'), url);
+            }
+        }
+        let (origin, path) = path_template(&url);
+        if !origin.is_empty() {
+            let server = server(&origin, &self.variables);
+            if !self.servers.contains(&server) {
+                self.servers.push(server);
+            }
+        }
+
+        let mut parameters: Vec<Value> = path_parameters(&path)
+            .iter()
+            .map(|name| parameter(name, "path", "", true))
+            .collect();
+        let inline_query = url.split_once('?').map(|(_, q)| q.split('#').next().unwrap_or("")).unwrap_or("");
+        let mut query: Vec<(&str, &str)> = inline_query
+            .split('&')
+            .filter(|p| !p.is_empty())
+            .map(|p| p.split_once('=').unwrap_or((p, "")))
+            .collect();
+        query.extend(pairs(request.get("params")));
+        let mut seen_query: Vec<&str> = Vec::new();
+        for (key, value) in query {
+            if !seen_query.contains(&key) {
+                seen_query.push(key);
+                parameters.push(parameter(key, "query", value, false));
+            }
+        }
+        for (key, value) in pairs(request.get("headers")) {
+            // Described by requestBody, responses and security instead
+            if ["content-type", "accept", "authorization"].contains(&key.to_lowercase().as_str()) {
+                continue;
+            }
+            parameters.push(parameter(key, "header", value, false));
+        }
+
+        let tags = match parents.last().filter(|_| parents.len() > 1) {
+            Some(folder) => {
+                let tag = str_field(folder, "name").to_string();
+                if !self.tags.contains(&tag) {
+                    self.tags.push(tag.clone());
+                }
+                vec![tag]
+            }
+            None => Vec::new(),
+        };
+
+        let name = str_field(request, "name");
+        let operation = Operation {
+            summary: if name.is_empty() { format!("{} {}", method.to_uppercase(), path) } else { name.to_string() },
+            operation_id: self.operation_id(name, &method, &path),
+            tags,
+            parameters,
+            request_body: request_body(request, self.version),
+            responses: responses(request, self.history, self.version),
+            security: self.security(request, parents),
+        };
+
+        let index = match self.paths.iter().position(|(p, _)| *p == path) {
+            Some(index) => index,
+            None => {
+                self.paths.push((path.clone(), PathItem::default()));
+                self.paths.len() - 1
+            }
+        };
+        let item = &mut self.paths[index].1;
+        match item.operations.iter_mut().find(|(m, _)| *m == method) {
+            // Several requests for the same operation: keep the first and
+            // add the status codes only the others recorded
+            Some((_, existing)) => {
+                for (status, response) in operation.responses {
+                    if !existing.responses.iter().any(|(s, _)| *s == status) {
+                        existing.responses.push((status, response));
+                    }
+                }
+            }
+            None => item.operations.push((method, operation)),
+        }
+    }
+
+    fn add_container(&mut self, container: &'a Value, parents: &mut Vec<&'a Value>) {
+        parents.push(container);
+        for request in container.get("requests").and_then(|r| r.as_array()).into_iter().flatten() {
+            self.add_request(request, parents);
+        }
+        for folder in container.get("folders").and_then(|f| f.as_array()).into_iter().flatten() {
+            self.add_container(folder, parents);
+        }
+        parents.pop();
+    }
+}
+
+/// Generate an OpenAPI document from a collection. Paths, path and query
+/// parameters and request bodies come from the requests; response schemas
+/// from the latest matching `history` responses.
+pub fn to_openapi(collection: &Value, history: &[HistoryItem], version: OpenApiVersion) -> Result<String, String> {
+    let name = collection.get("name").and_then(|v| v.as_str()).ok_or("Missing collection name")?;
+    let settings = collection.get("settings");
+
+    let collection_scheme = settings
+        .and_then(|s| s.get("auth"))
+        .filter(|a| enabled(a) && !matches!(str_field(a, "type"), "" | "none" | "inherit"))
+        .and_then(security_scheme);
+
+    let mut generator = Generator {
+        version,
+        history,
+        variables: pairs(settings.and_then(|s| s.get("variables"))),
+        collection_security: collection_scheme.as_ref().map(|(name, _)| name.clone()),
+        servers: Vec::new(),
+        tags: Vec::new(),
+        paths: Vec::new(),
+        security_schemes: BTreeMap::new(),
+        operation_ids: Vec::new(),
+    };
+    if let Some((name, definition)) = &collection_scheme {
+        generator.security_schemes.insert(name.clone(), definition.clone());
+    }
+    generator.add_container(collection, &mut Vec::new());
+
+    let document = Document {
+        openapi: version.as_str(),
+        info: Info { title: name.to_string(), version: "1.0.0".to_string() },
+        servers: generator.servers,
+        security: collection_scheme.map(|(name, _)| vec![json!({ name: [] })]),
+        tags: generator.tags.into_iter().map(|name| json!({ "name": name })).collect(),
+        paths: generator.paths,
+        components: (!generator.security_schemes.is_empty())
+            .then(|| json!({ "securitySchemes": generator.security_schemes })),
+    };
+    serde_yaml::to_string(&document).map_err(|e| format!("Failed to serialize OpenAPI document: {}", e))
+}
+
+/// Generate an OpenAPI 3.0/3.1 YAML document from a collection, with response
+/// schemas inferred from the workspace history
+#[tauri::command]
+pub async fn export_collection_openapi(
+    app: tauri::AppHandle,
+    collection: Value,
+    version: Option<String>,
+    workspace_id: Option<String>,
+) -> Result<String, String> {
+    let version = OpenApiVersion::parse(version.as_deref())?;
+    let storage = app.state::<Arc<Storage>>();
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let history = match ws_id {
+        Some(ws_id) => storage.get_history(&ws_id)?,
+        None => Vec::new(),
+    };
+    to_openapi(&collection, &history, version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_paths_and_parameters() {
+        assert_eq!(
+            path_template("{{baseUrl}}/users/:id/posts/{postId}?sort=asc"),
+            ("{{baseUrl}}".to_string(), "/users/{id}/posts/{postId}".to_string())
+        );
+        assert_eq!(
+            path_template("https://api.example.com/v1/orders/{{orderId}}/items"),
+            ("https://api.example.com".to_string(), "/v1/orders/{orderId}/items".to_string())
+        );
+        assert_eq!(path_parameters("/users/{id}/posts/{postId}"), vec!["id", "postId"]);
+    }
+
+    #[test]
+    fn infers_schemas_from_responses() {
+        let collection = json!({
+            "id": "c1",
+            "name": "Users API",
+            "requests": [{
+                "id": "r1", "name": "Get user", "protocol": "http", "method": "GET",
+                "url": "https://api.example.com/users/:id", "headers": [], "params": [], "body": "", "bodyType": "none",
+            }],
+        });
+        let history = vec![HistoryItem {
+            id: "h1".to_string(),
+            request: json!({ "id": "r1", "method": "GET", "url": "https://api.example.com/users/1" }),
+            response: Some(json!({
+                "status": 200,
+                "headers": { "content-type": "application/json; charset=utf-8" },
+                "body": r#"{"id": 1, "email": "ada@example.com", "tags": [{"name": "a"}, {"name": "b", "color": null}]}"#,
+            })),
+            timing: None,
+            timestamp: 1,
+        }];
+
+        let yaml = to_openapi(&collection, &history, OpenApiVersion::V3_1).unwrap();
+        let document: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(document["openapi"], "3.1.0");
+        assert_eq!(document["servers"][0]["url"], "https://api.example.com");
+
+        let operation = &document["paths"]["/users/{id}"]["get"];
+        assert_eq!(operation["operationId"], "getUser");
+        assert_eq!(operation["parameters"][0], json!({ "name": "id", "in": "path", "required": true, "schema": { "type": "string" } }));
+        let schema = &operation["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(schema["properties"]["email"], json!({ "type": "string", "format": "email" }));
+        assert_eq!(schema["properties"]["tags"]["items"]["required"], json!(["name"]));
+        assert_eq!(schema["properties"]["tags"]["items"]["properties"]["color"], json!({ "type": "null" }));
+    }
+}
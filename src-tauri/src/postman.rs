@@ -0,0 +1,452 @@
+use serde_json::{json, Map, Value};
+
+// ============ Postman Collection v2.1 Export ============
+
+const SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+fn str_field<'a>(value: &'a Value, name: &str) -> &'a str {
+    value.get(name).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+fn enabled(value: &Value) -> bool {
+    value.get("enabled").and_then(|e| e.as_bool()).unwrap_or(true)
+}
+
+/// `{ key, value, enabled }` items with a key as Postman `{ key, value, disabled }` pairs
+fn key_values(items: Option<&Value>) -> Vec<Value> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter(|item| !str_field(item, "key").is_empty())
+                .map(|item| {
+                    let mut pair = json!({ "key": str_field(item, "key"), "value": str_field(item, "value") });
+                    if !enabled(item) {
+                        pair["disabled"] = Value::Bool(true);
+                    }
+                    if let Some(description) = item.get("description").and_then(|d| d.as_str()) {
+                        pair["description"] = Value::from(description);
+                    }
+                    pair
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Postman auth attributes: `[{ key, value, type }]`
+fn attributes(pairs: &[(&str, Value)]) -> Value {
+    Value::Array(
+        pairs
+            .iter()
+            .map(|(key, value)| {
+                let kind = match value {
+                    Value::Bool(_) => "boolean",
+                    Value::Number(_) => "number",
+                    _ => "string",
+                };
+                json!({ "key": key, "value": value, "type": kind })
+            })
+            .collect(),
+    )
+}
+
+/// An `AuthConfig` as a Postman auth object. Inherited auth is left out, which is
+/// also how Postman inherits; auth without a Postman equivalent becomes `noauth`.
+fn auth(config: Option<&Value>) -> Option<Value> {
+    let config = config?;
+    let kind = str_field(config, "type");
+    if kind == "inherit" {
+        return None;
+    }
+    if !enabled(config) || kind == "none" {
+        return Some(json!({ "type": "noauth" }));
+    }
+
+    let field = |name: &str| Value::from(str_field(config, name));
+    let nested = |object: &str, name: &str| Value::from(config.get(object).map(|o| str_field(o, name)).unwrap_or(""));
+    let (postman_type, pairs): (&str, Vec<(&str, Value)>) = match kind {
+        "basic" => ("basic", vec![("username", field("username")), ("password", field("password"))]),
+        "digest" => ("digest", vec![("username", field("username")), ("password", field("password"))]),
+        "bearer" => ("bearer", vec![("token", field("token"))]),
+        "api-key" => (
+            "apikey",
+            vec![
+                ("key", field("apiKeyName")),
+                ("value", field("apiKeyValue")),
+                ("in", Value::from(if str_field(config, "apiKeyIn") == "query" { "query" } else { "header" })),
+            ],
+        ),
+        "oauth2" => {
+            let grant_type = match config.get("oauth2").map(|o| str_field(o, "grantType")).unwrap_or("") {
+                "client_credentials" => "client_credentials",
+                "password" => "password_credentials",
+                "implicit" => "implicit",
+                _ => "authorization_code",
+            };
+            (
+                "oauth2",
+                vec![
+                    ("grant_type", Value::from(grant_type)),
+                    ("authUrl", nested("oauth2", "authUrl")),
+                    ("accessTokenUrl", nested("oauth2", "tokenUrl")),
+                    ("clientId", nested("oauth2", "clientId")),
+                    ("clientSecret", nested("oauth2", "clientSecret")),
+                    ("scope", nested("oauth2", "scope")),
+                    ("username", nested("oauth2", "username")),
+                    ("password", nested("oauth2", "password")),
+                    ("accessToken", nested("oauth2", "accessToken")),
+                    ("addTokenTo", Value::from("header")),
+                ],
+            )
+        }
+        "aws-sigv4" => (
+            "awsv4",
+            vec![
+                ("accessKey", nested("awsSigV4", "accessKeyId")),
+                ("secretKey", nested("awsSigV4", "secretAccessKey")),
+                ("sessionToken", nested("awsSigV4", "sessionToken")),
+                ("region", nested("awsSigV4", "region")),
+                ("service", nested("awsSigV4", "service")),
+            ],
+        ),
+        "hawk" => {
+            let hawk = config.get("hawk").cloned().unwrap_or(Value::Null);
+            let algorithm = if str_field(&hawk, "algorithm") == "sha1" { "sha1" } else { "sha256" };
+            (
+                "hawk",
+                vec![
+                    ("authId", nested("hawk", "id")),
+                    ("authKey", nested("hawk", "key")),
+                    ("algorithm", Value::from(algorithm)),
+                    ("extraData", nested("hawk", "ext")),
+                    ("app", nested("hawk", "app")),
+                    ("delegation", nested("hawk", "dlg")),
+                    ("includePayloadHash", hawk.get("includePayloadHash").cloned().unwrap_or(Value::Bool(false))),
+                ],
+            )
+        }
+        "jwt" => {
+            let jwt = config.get("jwt").cloned().unwrap_or(Value::Null);
+            (
+                "jwt",
+                vec![
+                    ("algorithm", nested("jwt", "algorithm")),
+                    ("secret", nested("jwt", "key")),
+                    ("privateKey", nested("jwt", "key")),
+                    ("isSecretBase64Encoded", jwt.get("keyBase64").cloned().unwrap_or(Value::Bool(false))),
+                    ("payload", nested("jwt", "claims")),
+                    ("header", nested("jwt", "header")),
+                    ("headerPrefix", Value::from(jwt.get("prefix").and_then(|p| p.as_str()).unwrap_or("Bearer"))),
+                    ("addTokenTo", Value::from("header")),
+                ],
+            )
+        }
+        _ => return Some(json!({ "type": "noauth" })),
+    };
+
+    let mut auth = json!({ "type": postman_type });
+    auth[postman_type] = attributes(&pairs);
+    Some(auth)
+}
+
+/// Variables as Postman `variable` entries. Secrets keep their `secret` type but are
+/// exported empty: their values are encrypted with the workspace master key.
+fn variables(items: Option<&Value>) -> Vec<Value> {
+    items
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter(|v| !str_field(v, "key").is_empty())
+                .map(|v| {
+                    let is_secret = v.get("isSecret").and_then(|s| s.as_bool()).unwrap_or(false);
+                    let mut variable = json!({
+                        "key": str_field(v, "key"),
+                        "value": if is_secret { "" } else { str_field(v, "value") },
+                        "type": if is_secret { "secret" } else { "string" },
+                    });
+                    if !enabled(v) {
+                        variable["disabled"] = Value::Bool(true);
+                    }
+                    variable
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `istek` script API members and their `pm` equivalents, longer names first
+const SCRIPT_API: &[(&str, &str)] = &[
+    ("istek.variables.get(", "pm.variables.get("),
+    ("istek.variables.set(", "pm.globals.set("),
+    ("istek.response.json(", "pm.response.json("),
+    ("istek.response.statusText", "pm.response.status"),
+    ("istek.response.status", "pm.response.code"),
+    ("istek.response.body", "pm.response.text()"),
+    ("istek.response.time", "pm.response.responseTime"),
+    ("istek.request.method", "pm.request.method"),
+    ("istek.request.url", "pm.request.url.toString()"),
+    ("istek.cookies.get(", "pm.cookies.get("),
+    ("istek.cookies.has(", "pm.cookies.has("),
+    ("istek.environment", "pm.environment.name"),
+    ("istek.abort(", "pm.execution.skipRequest("),
+];
+
+/// A script as Postman `exec` lines: `istek` calls are translated to `pm`, lines
+/// using the rest of the API are commented out
+fn script_lines(script: &str) -> Vec<String> {
+    let mut untranslated = false;
+    let mut lines: Vec<String> = script
+        .lines()
+        .map(|line| {
+            let translated = SCRIPT_API.iter().fold(line.to_string(), |line, (from, to)| line.replace(from, to));
+            if translated.contains("istek.") {
+                untranslated = true;
+                format!("// {}", translated)
+            } else {
+                translated
+            }
+        })
+        .collect();
+    if untranslated {
+        lines.insert(0, "// Commented-out lines use parts of the istek script API that have no pm equivalent".to_string());
+    }
+    lines
+}
+
+/// Pre-request and post-request scripts as `prerequest` and `test` events
+fn events(item: &Value) -> Vec<Value> {
+    [("preRequestScript", "prerequest"), ("postRequestScript", "test")]
+        .iter()
+        .filter(|(field, _)| !str_field(item, field).trim().is_empty())
+        .map(|(field, listen)| {
+            json!({
+                "listen": listen,
+                "script": {
+                    "type": "text/javascript",
+                    "exec": script_lines(str_field(item, field)),
+                },
+            })
+        })
+        .collect()
+}
+
+/// A url with its params as a Postman url object (`raw`, `host`, `path`, `query`)
+fn url(raw_url: &str, params: Option<&Value>) -> Value {
+    let (base, inline_query) = raw_url.split_once('?').unwrap_or((raw_url, ""));
+    let mut query: Vec<Value> = inline_query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|p| {
+            let (key, value) = p.split_once('=').unwrap_or((p, ""));
+            json!({ "key": key, "value": value })
+        })
+        .collect();
+    query.extend(key_values(params));
+
+    let enabled_query: Vec<String> = query
+        .iter()
+        .filter(|q| q.get("disabled").is_none())
+        .map(|q| format!("{}={}", str_field(q, "key"), str_field(q, "value")))
+        .collect();
+    let raw = if enabled_query.is_empty() { base.to_string() } else { format!("{}?{}", base, enabled_query.join("&")) };
+
+    let mut url = json!({ "raw": raw });
+    let (protocol, rest) = match base.split_once("://") {
+        Some((protocol, rest)) => (Some(protocol), rest),
+        None => (None, base),
+    };
+    if let Some(protocol) = protocol {
+        url["protocol"] = Value::from(protocol);
+    }
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    if authority.contains("{{") {
+        // A variable such as {{baseUrl}} may hold more than a host
+        url["host"] = json!([authority]);
+    } else {
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => (host, Some(port)),
+            _ => (authority, None),
+        };
+        url["host"] = Value::Array(host.split('.').map(Value::from).collect());
+        if let Some(port) = port {
+            url["port"] = Value::from(port);
+        }
+    }
+    if !path.is_empty() {
+        url["path"] = Value::Array(path.split('/').map(Value::from).collect());
+    }
+    if !query.is_empty() {
+        url["query"] = Value::Array(query);
+    }
+    url
+}
+
+fn raw_body(body: &str, language: &str) -> Value {
+    json!({ "mode": "raw", "raw": body, "options": { "raw": { "language": language } } })
+}
+
+/// The Postman body of an HTTP request
+fn http_body(request: &Value) -> Option<Value> {
+    let body = str_field(request, "body");
+    let body = match str_field(request, "bodyType") {
+        "json" => raw_body(body, "json"),
+        "xml" => raw_body(body, "xml"),
+        "html" => raw_body(body, "html"),
+        "raw" => raw_body(body, "text"),
+        "x-www-form-urlencoded" => json!({ "mode": "urlencoded", "urlencoded": key_values(request.get("urlEncodedData")) }),
+        "form-data" => {
+            let fields: Vec<Value> = request
+                .get("formData")
+                .and_then(|f| f.as_array())
+                .map(|fields| {
+                    fields
+                        .iter()
+                        .filter(|f| !str_field(f, "key").is_empty())
+                        .map(|f| {
+                            let mut field = if str_field(f, "type") == "file" {
+                                json!({ "key": str_field(f, "key"), "type": "file", "src": str_field(f, "value") })
+                            } else {
+                                json!({ "key": str_field(f, "key"), "type": "text", "value": str_field(f, "value") })
+                            };
+                            if !enabled(f) {
+                                field["disabled"] = Value::Bool(true);
+                            }
+                            field
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            json!({ "mode": "formdata", "formdata": fields })
+        }
+        _ => return None,
+    };
+    Some(body)
+}
+
+/// A collection request as a Postman item, or None for protocols Postman
+/// collections cannot describe (WebSocket, MQTT, gRPC, ...)
+fn request_item(request: &Value) -> Option<Value> {
+    let protocol = request.get("protocol").and_then(|p| p.as_str()).unwrap_or("http");
+    let (method, body) = match protocol {
+        "http" => (str_field(request, "method").to_uppercase(), http_body(request)),
+        "graphql" => (
+            "POST".to_string(),
+            Some(json!({
+                "mode": "graphql",
+                "graphql": { "query": str_field(request, "query"), "variables": str_field(request, "variables") },
+            })),
+        ),
+        _ => return None,
+    };
+
+    let mut postman_request = Map::new();
+    postman_request.insert("method".to_string(), Value::from(method));
+    postman_request.insert("header".to_string(), Value::Array(key_values(request.get("headers"))));
+    postman_request.insert("url".to_string(), url(str_field(request, "url"), request.get("params")));
+    if let Some(body) = body {
+        postman_request.insert("body".to_string(), body);
+    }
+    if let Some(auth) = auth(request.get("auth")) {
+        postman_request.insert("auth".to_string(), auth);
+    }
+
+    let mut item = json!({
+        "name": str_field(request, "name"),
+        "request": postman_request,
+        "response": [],
+    });
+    let events = events(request);
+    if !events.is_empty() {
+        item["event"] = Value::Array(events);
+    }
+    Some(item)
+}
+
+/// Requests and subfolders of a collection or folder as Postman items; folders come first
+fn items(container: &Value) -> Vec<Value> {
+    let mut items: Vec<Value> = container
+        .get("folders")
+        .and_then(|f| f.as_array())
+        .map(|folders| folders.iter().map(folder_item).collect())
+        .unwrap_or_default();
+    if let Some(requests) = container.get("requests").and_then(|r| r.as_array()) {
+        items.extend(requests.iter().filter_map(request_item));
+    }
+    items
+}
+
+fn folder_item(folder: &Value) -> Value {
+    let mut item = json!({ "name": str_field(folder, "name"), "item": items(folder) });
+    let settings = folder.get("settings");
+    if let Some(auth) = auth(settings.and_then(|s| s.get("auth"))) {
+        item["auth"] = auth;
+    }
+    let variables = variables(settings.and_then(|s| s.get("variables")));
+    if !variables.is_empty() {
+        item["variable"] = Value::Array(variables);
+    }
+    item
+}
+
+/// Convert a collection to a Postman Collection v2.1 document. Folder and
+/// collection auth and variables are kept; Postman has no equivalent for
+/// inherited headers, base URLs or query params.
+pub fn to_postman(collection: &Value) -> Result<Value, String> {
+    let id = collection.get("id").and_then(|v| v.as_str()).ok_or("Missing collection id")?;
+    let name = collection.get("name").and_then(|v| v.as_str()).ok_or("Missing collection name")?;
+
+    let mut document = json!({
+        "info": { "_postman_id": id, "name": name, "schema": SCHEMA },
+        "item": items(collection),
+    });
+    let settings = collection.get("settings");
+    if let Some(auth) = auth(settings.and_then(|s| s.get("auth"))) {
+        document["auth"] = auth;
+    }
+    let variables = variables(settings.and_then(|s| s.get("variables")));
+    if !variables.is_empty() {
+        document["variable"] = Value::Array(variables);
+    }
+    Ok(document)
+}
+
+/// Export a single collection as a Postman Collection v2.1 JSON document
+#[tauri::command]
+pub async fn export_collection_postman(collection: Value) -> Result<String, String> {
+    let document = to_postman(&collection)?;
+    serde_json::to_string_pretty(&document).map_err(|e| format!("Failed to serialize collection: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_urls_with_params() {
+        let params = json!([
+            { "id": "1", "key": "page", "value": "2", "enabled": true },
+            { "id": "2", "key": "debug", "value": "1", "enabled": false },
+        ]);
+        let converted = url("https://api.example.com:8443/v1/users?sort=name", Some(&params));
+        assert_eq!(converted["raw"], "https://api.example.com:8443/v1/users?sort=name&page=2");
+        assert_eq!(converted["host"], json!(["api", "example", "com"]));
+        assert_eq!(converted["port"], "8443");
+        assert_eq!(converted["path"], json!(["v1", "users"]));
+        assert_eq!(converted["query"][2], json!({ "key": "debug", "value": "1", "disabled": true }));
+
+        let templated = url("{{baseUrl}}/users/{{id}}", None);
+        assert_eq!(templated["host"], json!(["{{baseUrl}}"]));
+        assert_eq!(templated["path"], json!(["users", "{{id}}"]));
+    }
+
+    #[test]
+    fn translates_scripts_to_pm() {
+        let lines = script_lines("const data = istek.response.json();\nistek.variables.set('token', data.token);\nistek.request.setHeader('X-Trace', '1');");
+        assert_eq!(lines[1], "const data = pm.response.json();");
+        assert_eq!(lines[2], "pm.globals.set('token', data.token);");
+        assert_eq!(lines[3], "// istek.request.setHeader('X-Trace', '1');");
+        assert!(lines[0].starts_with("// "));
+        assert_eq!(script_lines("console.log(istek.response.status)"), vec!["console.log(pm.response.code)"]);
+    }
+}
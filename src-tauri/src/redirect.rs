@@ -0,0 +1,320 @@
+use reqwest::header::{self, HeaderMap};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use url::Url;
+use utoipa::ToSchema;
+
+use crate::cookies::CookieJar;
+use crate::http_client::HttpClientSettings;
+use crate::timing::{self, SendError, TimedResponse};
+
+/// How a request follows redirects
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectOptions {
+    #[serde(default = "default_follow")]
+    pub follow: bool,
+    #[serde(default = "default_max_redirects")]
+    pub max_redirects: u32,
+    /// Resend POST requests as POST on 301/302 (browsers and curl switch to GET)
+    #[serde(default)]
+    pub keep_method: bool,
+}
+
+fn default_follow() -> bool {
+    true
+}
+
+fn default_max_redirects() -> u32 {
+    10
+}
+
+impl Default for RedirectOptions {
+    fn default() -> Self {
+        Self {
+            follow: default_follow(),
+            max_redirects: default_max_redirects(),
+            keep_method: false,
+        }
+    }
+}
+
+/// A redirect response received on the way to the final response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RedirectHop {
+    pub method: String,
+    pub url: String,
+    pub status: u16,
+    pub status_text: String,
+    /// The raw `Location` header
+    pub location: Option<String>,
+    pub headers: HashMap<String, String>,
+    pub time: u64,
+}
+
+/// Send a request with the cookie jar, following redirects hop by hop so that
+/// every hop is recorded and cookies set along the way reach the next hop.
+///
+/// Returns the final response and the redirects that led to it. With `save_to`,
+/// each hop's body overwrites the file, which ends up holding the final body.
+///
+/// `sign` adds auth to every hop that stays on the origin of `request`, since
+/// signatures cover the method and URL of the hop they are sent with.
+pub async fn send(
+    settings: &HttpClientSettings,
+    request: reqwest::Request,
+    detailed: bool,
+    options: &RedirectOptions,
+    cookie_jar: &mut CookieJar,
+    save_to: Option<&Path>,
+    sign: impl Fn(&mut reqwest::Request) -> Result<(), String>,
+) -> Result<(TimedResponse, Vec<RedirectHop>), SendError> {
+    // The pooled client must hand every redirect back to us
+    let settings = HttpClientSettings {
+        follow_redirects: false,
+        ..settings.clone()
+    };
+
+    let origin = request.url().origin();
+    let mut request = request;
+    let mut hops = Vec::new();
+    loop {
+        // Auth and cookies are added per hop, so keep the request from before that
+        let unsent = Resendable::of(&request);
+        let method = unsent.method.clone();
+        let url = unsent.url.clone();
+
+        if url.origin() == origin {
+            sign(&mut request)?;
+        }
+        cookie_jar.add_to_request(&mut request);
+        let response = timing::send(&settings, request, detailed, save_to).await?;
+        cookie_jar.store_from_response(&url, &response.headers);
+
+        let location = response
+            .headers
+            .get(header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let Some(location) = location.filter(|_| options.follow && is_followed(response.status)) else {
+            return Ok((response, hops));
+        };
+
+        if hops.len() as u32 >= options.max_redirects {
+            return Err(format!("Too many redirects (limit {})", options.max_redirects).into());
+        }
+        let next_url = url
+            .join(&location)
+            .map_err(|e| format!("Invalid redirect location {}: {}", location, e))?;
+        if !matches!(next_url.scheme(), "http" | "https") {
+            return Err(format!("Unsupported redirect location: {}", next_url).into());
+        }
+
+        let Some(next) = unsent.follow(response.status, next_url, options.keep_method) else {
+            return Ok((response, hops));
+        };
+
+        hops.push(RedirectHop {
+            method: method.to_string(),
+            url: url.to_string(),
+            status: response.status.as_u16(),
+            status_text: response.status.canonical_reason().unwrap_or("Unknown").to_string(),
+            location: Some(location),
+            headers: response
+                .headers
+                .iter()
+                .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
+                .collect(),
+            time: response.timing.total_ms.round() as u64,
+        });
+        request = next;
+    }
+}
+
+/// Rebuild the request that received the final response of `send` from a copy
+/// of the original request and the redirects that were followed. None when a
+/// hop cannot be retraced.
+pub fn final_request(request: reqwest::Request, hops: &[RedirectHop], options: &RedirectOptions) -> Option<reqwest::Request> {
+    hops.iter().try_fold(request, |request, hop| {
+        let next_url = request.url().join(hop.location.as_deref()?).ok()?;
+        let status = StatusCode::from_u16(hop.status).ok()?;
+        Resendable::of(&request).follow(status, next_url, options.keep_method)
+    })
+}
+
+/// The parts of a request needed to build its follow-up after a redirect
+struct Resendable {
+    method: Method,
+    url: Url,
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    /// `Some(None)` for a streaming body
+    body: Option<Option<Vec<u8>>>,
+}
+
+impl Resendable {
+    fn of(request: &reqwest::Request) -> Self {
+        Self {
+            method: request.method().clone(),
+            url: request.url().clone(),
+            headers: request.headers().clone(),
+            timeout: request.timeout().copied(),
+            body: request.body().map(|b| b.as_bytes().map(|bytes| bytes.to_vec())),
+        }
+    }
+
+    /// The request that follows a `status` redirect to `next_url`. None when the
+    /// body has to be resent but is a stream (multipart uploads cannot be sent twice).
+    fn follow(self, status: StatusCode, next_url: Url, keep_method: bool) -> Option<reqwest::Request> {
+        let next_method = redirect_method(status, &self.method, keep_method);
+        let keep_body = next_method == self.method && !matches!(self.method, Method::GET | Method::HEAD);
+        if keep_body && matches!(self.body, Some(None)) {
+            return None;
+        }
+
+        let mut next = reqwest::Request::new(next_method, next_url.clone());
+        *next.headers_mut() = self.headers;
+        *next.timeout_mut() = self.timeout;
+        if keep_body {
+            *next.body_mut() = self.body.flatten().map(reqwest::Body::from);
+        } else {
+            strip_body_headers(next.headers_mut());
+        }
+        if next_url.origin() != self.url.origin() {
+            strip_credentials(next.headers_mut());
+        }
+        Some(next)
+    }
+}
+
+fn is_followed(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 301 | 302 | 303 | 307 | 308)
+}
+
+/// The method of the follow-up request (RFC 9110 section 15.4)
+fn redirect_method(status: StatusCode, method: &Method, keep_method: bool) -> Method {
+    match status.as_u16() {
+        303 if *method != Method::HEAD => Method::GET,
+        301 | 302 if *method == Method::POST && !keep_method => Method::GET,
+        _ => method.clone(),
+    }
+}
+
+fn strip_body_headers(headers: &mut HeaderMap) {
+    for name in [
+        header::CONTENT_TYPE,
+        header::CONTENT_LENGTH,
+        header::CONTENT_ENCODING,
+        header::TRANSFER_ENCODING,
+    ] {
+        headers.remove(name);
+    }
+}
+
+/// Credentials are not forwarded to another origin
+fn strip_credentials(headers: &mut HeaderMap) {
+    for name in [header::AUTHORIZATION, header::PROXY_AUTHORIZATION, header::COOKIE] {
+        headers.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switches_method_like_browsers() {
+        let found = StatusCode::FOUND;
+        assert_eq!(redirect_method(found, &Method::POST, false), Method::GET);
+        assert_eq!(redirect_method(found, &Method::POST, true), Method::POST);
+        assert_eq!(redirect_method(found, &Method::PUT, false), Method::PUT);
+        assert_eq!(redirect_method(StatusCode::SEE_OTHER, &Method::PUT, true), Method::GET);
+        assert_eq!(redirect_method(StatusCode::SEE_OTHER, &Method::HEAD, false), Method::HEAD);
+        assert_eq!(redirect_method(StatusCode::PERMANENT_REDIRECT, &Method::POST, false), Method::POST);
+        assert!(!is_followed(StatusCode::NOT_MODIFIED));
+    }
+
+    #[test]
+    fn retraces_followed_redirects() {
+        let mut request = reqwest::Request::new(Method::POST, "https://api.example.com/login".parse().unwrap());
+        request.headers_mut().insert(header::AUTHORIZATION, "Basic c2VjcmV0".parse().unwrap());
+        *request.body_mut() = Some("user=alice".into());
+        let hop = |url: &str, status: u16, location: &str| RedirectHop {
+            method: String::new(),
+            url: url.to_string(),
+            status,
+            status_text: String::new(),
+            location: Some(location.to_string()),
+            headers: HashMap::new(),
+            time: 0,
+        };
+        let hops = [
+            hop("https://api.example.com/login", 307, "/v2/login"),
+            hop("https://api.example.com/v2/login", 303, "https://auth.example.com/done"),
+        ];
+
+        let options = RedirectOptions::default();
+        let same_origin = final_request(request.try_clone().unwrap(), &hops[..1], &options).unwrap();
+        assert_eq!(same_origin.method(), Method::POST);
+        assert_eq!(same_origin.url().as_str(), "https://api.example.com/v2/login");
+        assert!(same_origin.headers().contains_key(header::AUTHORIZATION));
+        assert_eq!(same_origin.body().and_then(|b| b.as_bytes()), Some(b"user=alice".as_slice()));
+
+        let other_origin = final_request(request, &hops, &options).unwrap();
+        assert_eq!(other_origin.method(), Method::GET);
+        assert_eq!(other_origin.url().as_str(), "https://auth.example.com/done");
+        assert!(other_origin.headers().get(header::AUTHORIZATION).is_none());
+        assert!(other_origin.body().is_none());
+    }
+
+    #[tokio::test]
+    async fn signs_each_same_origin_hop() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Redirects /start to /next with a 307 and echoes the signatures /next received
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut received = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !received.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = socket.read(&mut buffer).await.unwrap();
+                    received.extend_from_slice(&buffer[..read]);
+                }
+                let received = String::from_utf8_lossy(&received).to_string();
+                let response = if received.starts_with("POST /start ") {
+                    "HTTP/1.1 307 Temporary Redirect\r\nLocation: /next?page=2\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+                } else {
+                    let signatures: Vec<&str> = received
+                        .lines()
+                        .filter_map(|line| line.split_once(": ").filter(|(name, _)| name.eq_ignore_ascii_case("x-signature")))
+                        .map(|(_, value)| value)
+                        .collect();
+                    let body = signatures.join(",");
+                    format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)
+                };
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        let request = reqwest::Request::new(Method::POST, format!("{}/start", base).parse().unwrap());
+        let sign = |request: &mut reqwest::Request| {
+            let signature = format!("{} {}", request.method(), request.url().path());
+            request.headers_mut().append("x-signature", signature.parse().unwrap());
+            Ok(())
+        };
+        let mut jar = CookieJar::default();
+        let settings = HttpClientSettings::default();
+        let (response, hops) = send(&settings, request, false, &RedirectOptions::default(), &mut jar, None, sign)
+            .await
+            .unwrap();
+        assert_eq!(hops.len(), 1);
+        assert_eq!(String::from_utf8_lossy(&response.body), "POST /next");
+    }
+}
@@ -0,0 +1,577 @@
+use chrono::{TimeZone, Utc};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+use tauri::Manager;
+
+use crate::storage::{Storage, TestRunHistory};
+use crate::test_runner::{TestResult, TestRunSummary, TestStatus};
+
+// ============ Formats ============
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportFormat {
+    Junit,
+    Tap,
+    Html,
+}
+
+impl ReportFormat {
+    /// "junit" (or "xml"), "tap" or "html"
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.trim().to_lowercase().as_str() {
+            "junit" | "xml" => Ok(Self::Junit),
+            "tap" => Ok(Self::Tap),
+            "html" => Ok(Self::Html),
+            other => Err(format!("Unsupported report format: {} (expected junit, tap or html)", other)),
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Junit => "application/xml",
+            Self::Tap => "text/plain; charset=utf-8",
+            Self::Html => "text/html; charset=utf-8",
+        }
+    }
+}
+
+/// Report for a collection run that started at `started` (Unix milliseconds)
+pub fn render(summary: &TestRunSummary, format: ReportFormat, started: i64) -> String {
+    match format {
+        ReportFormat::Junit => junit(summary, started),
+        ReportFormat::Tap => tap(summary),
+        ReportFormat::Html => html(summary, started),
+    }
+}
+
+/// Report for a stored run; its timestamp is when it finished
+pub fn history_report(run: &TestRunHistory, format: ReportFormat) -> Result<String, String> {
+    let summary: TestRunSummary = serde_json::from_value(run.summary.clone())
+        .map_err(|e| format!("Invalid test run summary: {}", e))?;
+    Ok(render(&summary, format, run.timestamp - summary.total_time as i64))
+}
+
+// ============ Helpers ============
+
+/// Escape text for XML and HTML, dropping control characters XML 1.0 can't hold
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if (c as u32) < 0x20 => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn seconds(millis: u64) -> String {
+    format!("{:.3}", millis as f64 / 1000.0)
+}
+
+fn iso_time(millis: i64) -> String {
+    Utc.timestamp_millis_opt(millis)
+        .single()
+        .unwrap_or_default()
+        .format("%Y-%m-%dT%H:%M:%S")
+        .to_string()
+}
+
+fn title(result: &TestResult) -> String {
+    format!("{} {}", result.method.to_uppercase(), result.request_name)
+}
+
+fn status_label(status: &TestStatus) -> &'static str {
+    match status {
+        TestStatus::Pending | TestStatus::Running => "skipped",
+        TestStatus::Passed => "passed",
+        TestStatus::Failed => "failed",
+        TestStatus::Error => "error",
+    }
+}
+
+/// One line per failed assertion (`Status: expected 200, got 500`)
+fn failed_assertions(result: &TestResult) -> Vec<String> {
+    result
+        .assertions
+        .iter()
+        .filter(|a| !a.passed)
+        .map(|a| format!("{}: expected {}, got {}", a.name, a.expected, a.actual))
+        .collect()
+}
+
+/// Results grouped by the iteration of a data-driven run, in run order
+fn by_iteration(summary: &TestRunSummary) -> Vec<(Option<usize>, Vec<&TestResult>)> {
+    let mut groups: Vec<(Option<usize>, Vec<&TestResult>)> = Vec::new();
+    for result in &summary.results {
+        match groups.last_mut() {
+            Some((iteration, results)) if *iteration == result.iteration => results.push(result),
+            _ => groups.push((result.iteration, vec![result])),
+        }
+    }
+    groups
+}
+
+fn iteration_data(summary: &TestRunSummary, iteration: Option<usize>) -> Option<&HashMap<String, String>> {
+    let iteration = iteration?;
+    summary.iterations.iter().find(|i| i.iteration == iteration).map(|i| &i.data)
+}
+
+fn sorted(map: &HashMap<String, String>) -> Vec<(&String, &String)> {
+    let mut entries: Vec<_> = map.iter().collect();
+    entries.sort();
+    entries
+}
+
+// ============ JUnit XML ============
+
+/// One `<testsuite>` per iteration (or for the whole run), one `<testcase>` per request
+fn junit(summary: &TestRunSummary, started: i64) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{}\" timestamp=\"{}\">",
+        escape(&summary.name),
+        summary.results.len(),
+        summary.failed,
+        summary.errors,
+        seconds(summary.total_time),
+        iso_time(started)
+    );
+
+    let mut offset = 0;
+    for (iteration, results) in by_iteration(summary) {
+        let count = |status: TestStatus| results.iter().filter(|r| r.status == status).count();
+        let skipped = results.len() - count(TestStatus::Passed) - count(TestStatus::Failed) - count(TestStatus::Error);
+        let time: u64 = results.iter().filter_map(|r| r.response_time).sum();
+        let name = match iteration {
+            Some(iteration) => format!("{} (iteration {})", summary.name, iteration),
+            None => summary.name.clone(),
+        };
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\" timestamp=\"{}\">",
+            escape(&name),
+            results.len(),
+            count(TestStatus::Failed),
+            count(TestStatus::Error),
+            skipped,
+            seconds(time),
+            iso_time(started + offset)
+        );
+        offset += time as i64;
+
+        // Variables of the iteration's row
+        if let Some(data) = iteration_data(summary, iteration) {
+            xml.push_str("    <properties>\n");
+            for (key, value) in sorted(data) {
+                let _ = writeln!(xml, "      <property name=\"{}\" value=\"{}\"/>", escape(key), escape(value));
+            }
+            xml.push_str("    </properties>\n");
+        }
+
+        for result in results {
+            let _ = writeln!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\">",
+                escape(&title(result)),
+                escape(&summary.name),
+                seconds(result.response_time.unwrap_or(0))
+            );
+            match result.status {
+                TestStatus::Failed => {
+                    let failures = failed_assertions(result);
+                    let _ = writeln!(
+                        xml,
+                        "      <failure message=\"{} of {} assertions failed\" type=\"AssertionFailure\">{}</failure>",
+                        failures.len(),
+                        result.assertions.len(),
+                        escape(&failures.join("\n"))
+                    );
+                }
+                TestStatus::Error => {
+                    let error = result.error.as_deref().unwrap_or("Request failed");
+                    let _ = writeln!(
+                        xml,
+                        "      <error message=\"{}\" type=\"RequestError\">{}</error>",
+                        escape(error),
+                        escape(error)
+                    );
+                }
+                TestStatus::Pending | TestStatus::Running => xml.push_str("      <skipped/>\n"),
+                TestStatus::Passed => {}
+            }
+            let response = result
+                .response_status
+                .map(|status| format!("\nHTTP {}", status))
+                .unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                "      <system-out>{} {}{}</system-out>",
+                escape(&result.method.to_uppercase()),
+                escape(&result.url),
+                response
+            );
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+// ============ TAP ============
+
+/// TAP version 13, with a YAML block for each failed or errored request
+fn tap(summary: &TestRunSummary) -> String {
+    let mut out = format!("TAP version 13\n1..{}\n# {}\n", summary.results.len(), summary.name);
+
+    for (number, result) in summary.results.iter().enumerate() {
+        let mut description = title(result);
+        if let Some(iteration) = result.iteration {
+            description.push_str(&format!(" [iteration {}]", iteration));
+        }
+        // `#` starts a directive in TAP
+        let description = description.replace('#', "\\#");
+
+        match result.status {
+            TestStatus::Passed => {
+                let _ = writeln!(out, "ok {} - {}", number + 1, description);
+                continue;
+            }
+            TestStatus::Pending | TestStatus::Running => {
+                let _ = writeln!(out, "ok {} - {} # SKIP not run", number + 1, description);
+                continue;
+            }
+            TestStatus::Failed | TestStatus::Error => {
+                let _ = writeln!(out, "not ok {} - {}", number + 1, description);
+            }
+        }
+
+        let message = match result.status {
+            TestStatus::Error => result.error.clone().unwrap_or_else(|| "Request failed".to_string()),
+            _ => failed_assertions(result).join("\n"),
+        };
+        let mut diagnostics = json!({
+            "message": message,
+            "severity": status_label(&result.status),
+            "method": result.method.to_uppercase(),
+            "url": result.url,
+        });
+        if let Some(status) = result.response_status {
+            diagnostics["status"] = json!(status);
+        }
+        if let Some(time) = result.response_time {
+            diagnostics["duration_ms"] = json!(time);
+        }
+        let failed: Vec<Value> = result
+            .assertions
+            .iter()
+            .filter(|a| !a.passed)
+            .map(|a| json!({ "name": a.name, "expected": a.expected, "actual": a.actual }))
+            .collect();
+        if !failed.is_empty() {
+            diagnostics["assertions"] = Value::Array(failed);
+        }
+
+        out.push_str("  ---\n");
+        for line in serde_yaml::to_string(&diagnostics).unwrap_or_default().lines() {
+            let _ = writeln!(out, "  {}", line);
+        }
+        out.push_str("  ...\n");
+    }
+
+    let _ = writeln!(
+        out,
+        "# passed {}, failed {}, errors {}, {} ms",
+        summary.passed, summary.failed, summary.errors, summary.total_time
+    );
+    if summary.cancelled {
+        let _ = writeln!(out, "# cancelled after {} of {} requests", summary.results.len(), summary.total);
+    }
+    out
+}
+
+// ============ HTML ============
+
+const HTML_STYLE: &str = "body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;margin:2rem;color:#1f2328;background:#fff}\
+h1{font-size:1.4rem;margin:0 0 .25rem}h2{font-size:1.1rem;margin:1.5rem 0 .5rem}h3{font-size:.85rem;margin:1rem 0 .25rem;color:#59636e}\
+.meta{color:#59636e;font-size:.85rem}.totals{display:flex;gap:1rem;margin:1rem 0}.totals div{padding:.5rem 1rem;border-radius:6px;background:#f6f8fa}\
+.passed{color:#1a7f37}.failed{color:#cf222e}.error{color:#bc4c00}.skipped{color:#59636e}\
+details{border:1px solid #d1d9e0;border-radius:6px;margin:.5rem 0}summary{cursor:pointer;padding:.5rem .75rem;display:flex;gap:.75rem;align-items:baseline}\
+details>div{padding:0 .75rem .75rem}.badge{font-size:.75rem;font-weight:600;text-transform:uppercase;min-width:4rem}\
+.method{font-family:monospace;font-weight:600}.right{margin-left:auto;color:#59636e;font-size:.85rem}\
+table{border-collapse:collapse;width:100%;font-size:.85rem}th,td{text-align:left;padding:.25rem .5rem;border-bottom:1px solid #d1d9e0;vertical-align:top}\
+td{font-family:monospace;word-break:break-all}tr.fail td{background:#ffebe9}\
+pre{background:#f6f8fa;padding:.5rem;border-radius:6px;overflow:auto;max-height:24rem;font-size:.8rem;white-space:pre-wrap;word-break:break-all}";
+
+/// Bodies as sent or received, JSON pretty-printed
+fn html_body(body: &str) -> String {
+    let body = serde_json::from_str::<Value>(body)
+        .ok()
+        .and_then(|json| serde_json::to_string_pretty(&json).ok())
+        .unwrap_or_else(|| body.to_string());
+    format!("<pre>{}</pre>", escape(&body))
+}
+
+/// Headers whose values are left out of reports, which get shared and archived by CI
+const SENSITIVE_HEADERS: &[&str] = &["authorization", "proxy-authorization", "cookie", "set-cookie", "x-api-key"];
+
+fn html_headers(headers: &HashMap<String, String>) -> String {
+    html_table(sorted(headers).into_iter().map(|(name, value)| {
+        let value = if SENSITIVE_HEADERS.contains(&name.to_lowercase().as_str()) { "[redacted]" } else { value };
+        (name.as_str(), value)
+    }))
+}
+
+fn html_table<'a>(rows: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let mut table = String::from("<table>");
+    for (name, value) in rows {
+        let _ = write!(table, "<tr><th>{}</th><td>{}</td></tr>", escape(name), escape(value));
+    }
+    table.push_str("</table>");
+    table
+}
+
+fn html_result(html: &mut String, result: &TestResult) {
+    let label = status_label(&result.status);
+    let open = if matches!(result.status, TestStatus::Failed | TestStatus::Error) { " open" } else { "" };
+    let mut details = Vec::new();
+    if let Some(status) = result.response_status {
+        details.push(status.to_string());
+    }
+    if let Some(time) = result.response_time {
+        details.push(format!("{} ms", time));
+    }
+    if let Some(iteration) = result.iteration {
+        details.push(format!("iteration {}", iteration));
+    }
+    let _ = write!(
+        html,
+        "<details{}><summary><span class=\"badge {}\">{}</span><span class=\"method\">{}</span><span>{}</span><span class=\"right\">{}</span></summary><div>",
+        open,
+        label,
+        label,
+        escape(&result.method.to_uppercase()),
+        escape(&result.request_name),
+        escape(&details.join(" · "))
+    );
+    let _ = write!(html, "<p class=\"meta\">{}</p>", escape(&result.url));
+
+    if let Some(error) = &result.error {
+        let _ = write!(html, "<h3>Error</h3><pre class=\"error\">{}</pre>", escape(error));
+    }
+    if !result.assertions.is_empty() {
+        html.push_str("<h3>Assertions</h3><table><tr><th>Assertion</th><th>Expected</th><th>Actual</th></tr>");
+        for assertion in &result.assertions {
+            let _ = write!(
+                html,
+                "<tr class=\"{}\"><td>{} {}</td><td>{}</td><td>{}</td></tr>",
+                if assertion.passed { "pass" } else { "fail" },
+                if assertion.passed { "✓" } else { "✗" },
+                escape(&assertion.name),
+                escape(&assertion.expected),
+                escape(&assertion.actual)
+            );
+        }
+        html.push_str("</table>");
+    }
+    if let Some(extracted) = result.extracted_variables.as_ref().filter(|v| !v.is_empty()) {
+        html.push_str("<h3>Extracted variables</h3><table>");
+        for var in extracted {
+            let value = var.error.as_deref().unwrap_or(&var.value);
+            let _ = write!(html, "<tr><th>{}</th><td>{}</td></tr>", escape(&var.variable_name), escape(value));
+        }
+        html.push_str("</table>");
+    }
+    if let Some(headers) = result.request_headers.as_ref().filter(|h| !h.is_empty()) {
+        let _ = write!(html, "<h3>Request headers</h3>{}", html_headers(headers));
+    }
+    if let Some(body) = result.request_body.as_deref().filter(|b| !b.is_empty()) {
+        let _ = write!(html, "<h3>Request body</h3>{}", html_body(body));
+    }
+    if let Some(headers) = result.response_headers.as_ref().filter(|h| !h.is_empty()) {
+        let _ = write!(html, "<h3>Response headers</h3>{}", html_headers(headers));
+    }
+    if let Some(body) = result.response_body.as_deref().filter(|b| !b.is_empty()) {
+        let _ = write!(html, "<h3>Response body</h3>{}", html_body(body));
+    }
+    html.push_str("</div></details>\n");
+}
+
+/// A single page with inline styles, no external assets
+fn html(summary: &TestRunSummary, started: i64) -> String {
+    let mut html = String::from("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(html, "<title>{} – Test report</title>", escape(&summary.name));
+    let _ = writeln!(html, "<style>{}</style>\n</head>\n<body>", HTML_STYLE);
+    let _ = writeln!(html, "<h1>{}</h1>", escape(&summary.name));
+    let _ = writeln!(
+        html,
+        "<p class=\"meta\">Started {} UTC · {} ms{}</p>",
+        iso_time(started).replace('T', " "),
+        summary.total_time,
+        if summary.cancelled { " · cancelled" } else { "" }
+    );
+    let _ = writeln!(
+        html,
+        "<div class=\"totals\"><div>{} requests</div><div class=\"passed\">{} passed</div><div class=\"failed\">{} failed</div><div class=\"error\">{} errors</div></div>",
+        summary.total, summary.passed, summary.failed, summary.errors
+    );
+
+    for (iteration, results) in by_iteration(summary) {
+        if let Some(iteration) = iteration {
+            let _ = writeln!(html, "<h2>Iteration {}</h2>", iteration);
+            if let Some(data) = iteration_data(summary, Some(iteration)) {
+                // Iteration variables are not headers and are shown unredacted
+                html.push_str(&html_table(sorted(data).into_iter().map(|(k, v)| (k.as_str(), v.as_str()))));
+                html.push('\n');
+            }
+        }
+        for result in results {
+            html_result(&mut html, result);
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+// ============ Commands ============
+
+/// Export the results of a collection run as a JUnit XML, TAP or HTML report
+#[tauri::command]
+pub async fn export_test_run_report(
+    summary: TestRunSummary,
+    format: String,
+    started_at: Option<i64>,
+) -> Result<String, String> {
+    let format = ReportFormat::parse(&format)?;
+    let started = started_at.unwrap_or_else(|| Utc::now().timestamp_millis() - summary.total_time as i64);
+    Ok(render(&summary, format, started))
+}
+
+/// Export a run from the test run history as a JUnit XML, TAP or HTML report
+#[tauri::command]
+pub async fn export_test_run_history_report(
+    app: tauri::AppHandle,
+    id: String,
+    format: String,
+) -> Result<String, String> {
+    let format = ReportFormat::parse(&format)?;
+    let storage = app.state::<Arc<Storage>>();
+    let run = storage
+        .get_test_runs()?
+        .into_iter()
+        .find(|run| run.id == id)
+        .ok_or("Test run not found")?;
+    history_report(&run, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iteration_data::IterationSummary;
+    use crate::test_runner::AssertionResult;
+
+    fn result(name: &str, status: TestStatus, assertions: Vec<AssertionResult>) -> TestResult {
+        TestResult {
+            request_id: name.to_lowercase(),
+            request_name: name.to_string(),
+            method: "get".to_string(),
+            url: "https://api.example.com/users?a=1&b=2".to_string(),
+            status,
+            request_headers: None,
+            request_body: None,
+            response_status: Some(500),
+            response_time: Some(42),
+            response_size: None,
+            response_body: Some("<html>".to_string()),
+            response_headers: None,
+            error: None,
+            assertions,
+            extracted_variables: None,
+            timing: None,
+            attempts: Vec::new(),
+            iteration: None,
+        }
+    }
+
+    fn summary() -> TestRunSummary {
+        let failed = AssertionResult {
+            name: "Status".to_string(),
+            passed: false,
+            expected: "200".to_string(),
+            actual: "500".to_string(),
+        };
+        let mut errored = result("Delete user", TestStatus::Error, Vec::new());
+        errored.error = Some("connection refused".to_string());
+        TestRunSummary {
+            run_id: "run".to_string(),
+            name: "Users & roles".to_string(),
+            total: 3,
+            passed: 1,
+            failed: 1,
+            errors: 1,
+            total_time: 126,
+            results: vec![
+                result("List users", TestStatus::Passed, Vec::new()),
+                result("Get user #1", TestStatus::Failed, vec![failed]),
+                errored,
+            ],
+            cancelled: false,
+            iterations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn renders_junit_xml() {
+        let xml = render(&summary(), ReportFormat::Junit, 1_700_000_000_000);
+        assert!(xml.contains(
+            "<testsuites name=\"Users &amp; roles\" tests=\"3\" failures=\"1\" errors=\"1\" time=\"0.126\" timestamp=\"2023-11-14T22:13:20\">"
+        ));
+        assert!(xml.contains("<testcase name=\"GET Get user #1\" classname=\"Users &amp; roles\" time=\"0.042\">"));
+        assert!(xml.contains(
+            "<failure message=\"1 of 1 assertions failed\" type=\"AssertionFailure\">Status: expected 200, got 500</failure>"
+        ));
+        assert!(xml.contains("<error message=\"connection refused\""));
+        assert!(xml.contains("<system-out>GET https://api.example.com/users?a=1&amp;b=2\nHTTP 500</system-out>"));
+    }
+
+    #[test]
+    fn renders_tap_and_html() {
+        let tap = render(&summary(), ReportFormat::Tap, 0);
+        assert!(tap.starts_with("TAP version 13\n1..3\n"));
+        assert!(tap.contains("ok 1 - GET List users\n"));
+        assert!(tap.contains("not ok 2 - GET Get user \\#1\n  ---\n"));
+        assert!(tap.contains("  message: connection refused\n"));
+
+        let mut summary = summary();
+        summary.results[0].request_headers = Some(HashMap::from([
+            ("Authorization".to_string(), "Bearer secret".to_string()),
+            ("Accept".to_string(), "application/json".to_string()),
+        ]));
+        let html = render(&summary, ReportFormat::Html, 0);
+        assert!(html.contains("<tr><th>Authorization</th><td>[redacted]</td></tr>"));
+        assert!(!html.contains("secret"));
+        assert!(html.contains("<tr><th>Accept</th><td>application/json</td></tr>"));
+        assert!(html.contains("<title>Users &amp; roles – Test report</title>"));
+        assert!(html.contains("<pre>&lt;html&gt;</pre>"));
+        assert!(html.contains("<details open><summary><span class=\"badge failed\">"));
+
+        for result in &mut summary.results {
+            result.iteration = Some(1);
+        }
+        let data = HashMap::from([("cookie".to_string(), "session=1".to_string())]);
+        summary.iterations = vec![IterationSummary::new(1, data, 3)];
+        let html = render(&summary, ReportFormat::Html, 0);
+        assert!(html.contains("<h2>Iteration 1</h2>\n<table><tr><th>cookie</th><td>session=1</td></tr></table>"));
+        assert!(ReportFormat::parse("pdf").is_err());
+    }
+}
@@ -0,0 +1,213 @@
+use reqwest::header::{self, HeaderMap};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::cookies::CookieJar;
+use crate::http_client::HttpClientSettings;
+use crate::redirect::{self, RedirectHop, RedirectOptions};
+use crate::timing::TimedResponse;
+
+/// How the delay grows between retries
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BackoffStrategy {
+    /// Always wait `delayMs`
+    Fixed,
+    /// Wait `delayMs`, 2 × `delayMs`, 3 × `delayMs`, ...
+    Linear,
+    /// Wait `delayMs`, 2 × `delayMs`, 4 × `delayMs`, ...
+    #[default]
+    Exponential,
+}
+
+/// When and how often a request is retried
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryPolicy {
+    /// Retries after the first attempt (0 disables retrying)
+    #[serde(default)]
+    pub max_retries: u32,
+    #[serde(default)]
+    pub backoff: BackoffStrategy,
+    #[serde(default = "default_delay_ms")]
+    pub delay_ms: u64,
+    /// Upper bound for a single delay, including `Retry-After`
+    #[serde(default = "default_max_delay_ms")]
+    pub max_delay_ms: u64,
+    /// Response status codes that are retried
+    #[serde(default = "default_retry_statuses")]
+    pub retry_on_status: Vec<u16>,
+    /// Retry when no response was received because connecting failed (DNS, refused,
+    /// TLS handshake) or the request timed out
+    #[serde(default = "default_true")]
+    pub retry_on_connection_error: bool,
+    /// Wait as long as the `Retry-After` header asks (up to `maxDelayMs`)
+    #[serde(default = "default_true")]
+    pub respect_retry_after: bool,
+}
+
+fn default_delay_ms() -> u64 {
+    500
+}
+
+fn default_max_delay_ms() -> u64 {
+    30_000
+}
+
+fn default_retry_statuses() -> Vec<u16> {
+    vec![429, 502, 503, 504]
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 0,
+            backoff: BackoffStrategy::default(),
+            delay_ms: default_delay_ms(),
+            max_delay_ms: default_max_delay_ms(),
+            retry_on_status: default_retry_statuses(),
+            retry_on_connection_error: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before retry number `retry` (starting at 1)
+    fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        let factor = match self.backoff {
+            BackoffStrategy::Fixed => 1,
+            BackoffStrategy::Linear => retry as u64,
+            BackoffStrategy::Exponential => 1u64.checked_shl(retry.saturating_sub(1)).unwrap_or(u64::MAX),
+        };
+        let delay = match retry_after.filter(|_| self.respect_retry_after) {
+            Some(retry_after) => retry_after,
+            None => Duration::from_millis(self.delay_ms.saturating_mul(factor)),
+        };
+        delay.min(Duration::from_millis(self.max_delay_ms))
+    }
+}
+
+/// One attempt at sending a request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RetryAttempt {
+    pub attempt: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub time: u64,
+    /// How long was waited before the next attempt (absent for the last attempt)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_delay_ms: Option<u64>,
+}
+
+/// Send a request (following redirects) and retry it according to `policy`.
+///
+/// Returns the outcome of the last attempt and, when a policy is given, every
+/// attempt made. Requests with a streaming body cannot be resent and are only
+/// sent once.
+///
+/// `sign` runs on every attempt and redirect hop right before it is sent (see
+/// `redirect::send`), so signatures are made afresh for each retry and hop.
+#[allow(clippy::too_many_arguments)]
+pub async fn send(
+    settings: &HttpClientSettings,
+    request: reqwest::Request,
+    detailed: bool,
+    redirects: &RedirectOptions,
+    cookie_jar: &mut CookieJar,
+    save_to: Option<&Path>,
+    policy: Option<&RetryPolicy>,
+    sign: impl Fn(&mut reqwest::Request) -> Result<(), String>,
+) -> (Result<(TimedResponse, Vec<RedirectHop>), String>, Vec<RetryAttempt>) {
+    let mut request = request;
+    let Some(policy) = policy else {
+        let sent = redirect::send(settings, request, detailed, redirects, cookie_jar, save_to, &sign).await;
+        return (sent.map_err(|e| e.message), Vec::new());
+    };
+
+    let mut attempts: Vec<RetryAttempt> = Vec::new();
+    loop {
+        let attempt = attempts.len() as u32 + 1;
+        let retry = if attempt <= policy.max_retries { request.try_clone() } else { None };
+
+        let start = Instant::now();
+        let sent = redirect::send(settings, request, detailed, redirects, cookie_jar, save_to, &sign).await;
+        let time = start.elapsed().as_millis() as u64;
+
+        let delay = match &sent {
+            Ok((response, _)) if policy.retry_on_status.contains(&response.status.as_u16()) => {
+                Some(policy.delay(attempt, retry_after(&response.headers)))
+            }
+            Err(e) if e.connection && policy.retry_on_connection_error => Some(policy.delay(attempt, None)),
+            _ => None,
+        }
+        .filter(|_| retry.is_some());
+
+        attempts.push(RetryAttempt {
+            attempt,
+            status: sent.as_ref().ok().map(|(response, _)| response.status.as_u16()),
+            error: sent.as_ref().err().map(|e| e.message.clone()),
+            time,
+            retry_delay_ms: delay.map(|d| d.as_millis() as u64),
+        });
+
+        match (delay, retry) {
+            (Some(delay), Some(next)) => {
+                tokio::time::sleep(delay).await;
+                request = next;
+            }
+            _ => return (sent.map_err(|e| e.message), attempts),
+        }
+    }
+}
+
+/// The delay asked for by a `Retry-After` header (seconds or an HTTP date)
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(wait.to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_and_caps_delays() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            delay_ms: 100,
+            max_delay_ms: 1_000,
+            ..Default::default()
+        };
+        let delays: Vec<u64> = (1..=5).map(|n| policy.delay(n, None).as_millis() as u64).collect();
+        assert_eq!(delays, [100, 200, 400, 800, 1_000]);
+
+        let linear = RetryPolicy { backoff: BackoffStrategy::Linear, ..policy.clone() };
+        assert_eq!(linear.delay(3, None), Duration::from_millis(300));
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(60))), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn parses_retry_after() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(header::RETRY_AFTER, "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,286 @@
+use futures_util::future::BoxFuture;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::cancel;
+
+/// Which earlier requests each request waits for: for every variable it uses
+/// (`{{name}}` or `{name}` in `texts[i]`), the last request before it that extracts it
+pub fn dependencies(texts: &[String], extracts: &[Vec<String>]) -> Vec<Vec<usize>> {
+    texts
+        .iter()
+        .enumerate()
+        .map(|(index, text)| {
+            let mut producers: Vec<usize> = Vec::new();
+            let mut seen: Vec<&str> = Vec::new();
+            for producer in (0..index).rev() {
+                for name in &extracts[producer] {
+                    if seen.contains(&name.as_str()) {
+                        continue;
+                    }
+                    seen.push(name);
+                    // `{{name}}` contains `{name}`
+                    if text.contains(&format!("{{{}}}", name)) && !producers.contains(&producer) {
+                        producers.push(producer);
+                    }
+                }
+            }
+            producers.sort_unstable();
+            producers
+        })
+        .collect()
+}
+
+/// Schedules one pass through a run's requests, at most `concurrency` at a time
+/// and each only once the requests it depends on have finished. Nothing starts
+/// while a request that may set cookies runs, so the requests after it send them.
+pub struct RunPlan {
+    dependencies: Vec<Vec<usize>>,
+    cookie_setters: Vec<bool>,
+    concurrency: usize,
+    started: Vec<bool>,
+    finished: Vec<bool>,
+    extracted: Vec<HashMap<String, String>>,
+    running: usize,
+    stopped: bool,
+}
+
+impl RunPlan {
+    pub fn new(dependencies: Vec<Vec<usize>>, concurrency: usize) -> Self {
+        let count = dependencies.len();
+        RunPlan {
+            dependencies,
+            cookie_setters: vec![false; count],
+            concurrency: concurrency.max(1),
+            started: vec![false; count],
+            finished: vec![false; count],
+            extracted: vec![HashMap::new(); count],
+            running: 0,
+            stopped: false,
+        }
+    }
+
+    pub fn set_cookie_setter(&mut self, index: usize) {
+        self.cookie_setters[index] = true;
+    }
+
+    /// The first request that can start now, marked as running
+    pub fn next_ready(&mut self) -> Option<usize> {
+        if self.stopped || self.running >= self.concurrency {
+            return None;
+        }
+        let count = self.started.len();
+        if (0..count).any(|i| self.cookie_setters[i] && self.started[i] && !self.finished[i]) {
+            return None;
+        }
+        let index = (0..count)
+            .find(|&i| !self.started[i] && self.dependencies[i].iter().all(|&d| self.finished[d]))?;
+        self.started[index] = true;
+        self.running += 1;
+        Some(index)
+    }
+
+    /// `base` with what the finished requests before a request extracted, later requests
+    /// winning as when the requests run in order
+    pub fn context(&self, index: usize, base: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut context = base.clone();
+        for extracted in &self.extracted[..index] {
+            context.extend(extracted.clone());
+        }
+        context
+    }
+
+    pub fn finish(&mut self, index: usize, extracted: HashMap<String, String>) {
+        self.finished[index] = true;
+        self.extracted[index] = extracted;
+        self.running -= 1;
+    }
+
+    /// Start nothing more (stop on failure); running requests still finish
+    pub fn stop(&mut self) {
+        self.stopped = true;
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.running == 0 && (self.stopped || self.finished.iter().all(|&f| f))
+    }
+}
+
+/// Drives the passes of a run (one per iteration data row) through their plans.
+/// Requests start as soon as they are ready and wait out the delay between requests
+/// in their own future, so the delay never holds up collecting finished requests.
+///
+/// Running requests each use a fork of the cookie jar, so nothing starts alongside a
+/// request that may set cookies: one that has not run yet in this run, or whose
+/// response set cookies before. Only requests known not to set cookies run concurrently.
+pub struct Scheduler<'a, T> {
+    dependencies: Vec<Vec<usize>>,
+    /// Whether each request set cookies, once it has run
+    sets_cookies: Vec<Option<bool>>,
+    concurrency: usize,
+    delay: Duration,
+    plan: RunPlan,
+    base: HashMap<String, String>,
+    running: FuturesUnordered<BoxFuture<'a, (usize, T)>>,
+    started: usize,
+}
+
+impl<'a, T: Send + 'a> Scheduler<'a, T> {
+    pub fn new(dependencies: Vec<Vec<usize>>, concurrency: usize, delay_ms: u64) -> Self {
+        let count = dependencies.len();
+        Scheduler {
+            plan: RunPlan::new(dependencies.clone(), concurrency),
+            dependencies,
+            sets_cookies: vec![None; count],
+            concurrency,
+            delay: Duration::from_millis(delay_ms),
+            base: HashMap::new(),
+            running: FuturesUnordered::new(),
+            started: 0,
+        }
+    }
+
+    /// Start a pass through every request with `base` as the context for variable substitution
+    pub fn begin(&mut self, base: HashMap<String, String>) {
+        self.plan = RunPlan::new(self.dependencies.clone(), self.concurrency);
+        for index in (0..self.sets_cookies.len()).filter(|&i| self.sets_cookies[i] != Some(false)) {
+            self.plan.set_cookie_setter(index);
+        }
+        self.base = base;
+    }
+
+    /// Start the requests that are ready with `start(index, context)` and wait for the
+    /// next one to finish; None once the pass is done or the run was cancelled
+    pub async fn next<F, Fut>(&mut self, cancellation: &cancel::Registration, mut start: F) -> Option<(usize, T)>
+    where
+        F: FnMut(usize, HashMap<String, String>) -> Fut,
+        Fut: Future<Output = T> + Send + 'a,
+    {
+        if self.plan.is_done() || cancellation.is_cancelled() {
+            return None;
+        }
+        while let Some(index) = self.plan.next_ready() {
+            let request = start(index, self.plan.context(index, &self.base));
+            // No delay before the first request of the run
+            let delay = if self.started > 0 { self.delay } else { Duration::ZERO };
+            self.started += 1;
+            self.running.push(Box::pin(async move {
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+                (index, request.await)
+            }));
+        }
+        cancellation.run(self.running.next()).await.ok().flatten()
+    }
+
+    /// Record a finished request: the variables it extracted and whether it set cookies
+    pub fn finish(&mut self, index: usize, extracted: HashMap<String, String>, set_cookies: bool) {
+        self.plan.finish(index, extracted);
+        self.sets_cookies[index] = Some(set_cookies || self.sets_cookies[index] == Some(true));
+    }
+
+    /// Start nothing more in this pass (stop on failure)
+    pub fn stop(&mut self) {
+        self.plan.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn depends_on_last_producer() {
+        let texts = strings(&[
+            "POST /login",
+            "GET /users",
+            "GET /me?token={{token}}",
+            "POST /refresh {token}",
+            "GET /users/{{userId}} Bearer {{token}}",
+        ]);
+        let extracts = vec![strings(&["token"]), strings(&["userId"]), vec![], strings(&["token"]), vec![]];
+        assert_eq!(dependencies(&texts, &extracts), vec![vec![], vec![], vec![0], vec![0], vec![1, 3]]);
+    }
+
+    #[test]
+    fn schedules_within_concurrency_and_dependencies() {
+        let mut plan = RunPlan::new(vec![vec![], vec![], vec![0], vec![]], 2);
+        assert_eq!(plan.next_ready(), Some(0));
+        assert_eq!(plan.next_ready(), Some(1));
+        assert_eq!(plan.next_ready(), None);
+
+        plan.finish(1, HashMap::new());
+        // Request 2 waits for request 0, so request 3 goes first
+        assert_eq!(plan.next_ready(), Some(3));
+        plan.finish(0, HashMap::from([("token".to_string(), "abc".to_string())]));
+        assert_eq!(plan.next_ready(), Some(2));
+        assert_eq!(plan.context(2, &HashMap::new())["token"], "abc");
+
+        plan.finish(3, HashMap::new());
+        plan.finish(2, HashMap::new());
+        assert!(plan.is_done());
+    }
+
+    #[test]
+    fn starts_nothing_while_a_cookie_setter_runs() {
+        let mut plan = RunPlan::new(vec![vec![]; 3], 3);
+        plan.set_cookie_setter(1);
+        assert_eq!(plan.next_ready(), Some(0));
+        assert_eq!(plan.next_ready(), Some(1));
+        assert_eq!(plan.next_ready(), None);
+        plan.finish(1, HashMap::new());
+        assert_eq!(plan.next_ready(), Some(2));
+    }
+
+    #[test]
+    fn keeps_earlier_extractions() {
+        // Request 2 uses the token of request 1, whose extraction failed
+        let mut plan = RunPlan::new(vec![vec![], vec![], vec![1]], 1);
+        for extracted in [HashMap::from([("token".to_string(), "first".to_string())]), HashMap::new()] {
+            let index = plan.next_ready().unwrap();
+            plan.finish(index, extracted);
+        }
+        assert_eq!(plan.next_ready(), Some(2));
+        assert_eq!(plan.context(2, &HashMap::new())["token"], "first");
+    }
+
+    #[tokio::test]
+    async fn waits_for_cookies_of_the_first_pass() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        // Request 0 logs in and sets the session cookie the others send
+        let session = AtomicBool::new(false);
+        let cancellation = cancel::Registration::new("run-plan-test");
+        let mut scheduler = Scheduler::new(vec![vec![]; 3], 2, 0);
+        for _ in 0..2 {
+            scheduler.begin(HashMap::new());
+            let mut results = Vec::new();
+            loop {
+                let next = scheduler.next(&cancellation, |index, _| {
+                    let (session, had_session) = (&session, session.load(Ordering::SeqCst));
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        if index == 0 {
+                            session.store(true, Ordering::SeqCst);
+                        }
+                        had_session
+                    }
+                });
+                let Some((index, had_session)) = next.await else {
+                    break;
+                };
+                scheduler.finish(index, HashMap::new(), index == 0);
+                results.push((index, had_session));
+            }
+            assert!(results.iter().filter(|(index, _)| *index > 0).all(|(_, had_session)| *had_session));
+            assert_eq!(results.len(), 3);
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use rquickjs::{Context, Runtime, Object, Function, Value, prelude::Rest};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::Manager;
+
+use crate::cookies::CookieJar;
+use crate::storage::Storage;
 
 // ============ Script Context Types ============
 
@@ -32,6 +37,9 @@ pub struct ScriptContext {
     pub response: Option<ScriptResponse>,
     pub variables: HashMap<String, String>,
     pub environment: String,
+    /// Cookies the request URL would receive (filled from the workspace jar)
+    #[serde(default)]
+    pub cookies: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,6 +50,8 @@ pub struct ScriptResult {
     pub console_output: Vec<String>,
     pub modified_variables: HashMap<String, String>,
     pub modified_headers: HashMap<String, String>,
+    /// Cookies set (Some) or removed (None) by the script
+    pub modified_cookies: HashMap<String, Option<String>>,
     pub abort_request: bool,
 }
 
@@ -53,6 +63,7 @@ impl Default for ScriptResult {
             console_output: vec![],
             modified_variables: HashMap::new(),
             modified_headers: HashMap::new(),
+            modified_cookies: HashMap::new(),
             abort_request: false,
         }
     }
@@ -63,27 +74,66 @@ impl Default for ScriptResult {
 /// Execute a pre-request script
 #[tauri::command]
 pub async fn run_pre_request_script(
+    app: tauri::AppHandle,
     script: String,
     context: ScriptContext,
+    workspace_id: Option<String>,
 ) -> Result<ScriptResult, String> {
     if script.trim().is_empty() {
         return Ok(ScriptResult::default());
     }
     
-    run_script_internal(script, context, false).await
+    run_with_cookie_jar(&app, script, context, workspace_id, false).await
 }
 
 /// Execute a post-request script
 #[tauri::command]
 pub async fn run_post_request_script(
+    app: tauri::AppHandle,
     script: String,
     context: ScriptContext,
+    workspace_id: Option<String>,
 ) -> Result<ScriptResult, String> {
     if script.trim().is_empty() {
         return Ok(ScriptResult::default());
     }
     
-    run_script_internal(script, context, true).await
+    run_with_cookie_jar(&app, script, context, workspace_id, true).await
+}
+
+/// Run a script with `istek.cookies` backed by the workspace cookie jar
+async fn run_with_cookie_jar(
+    app: &tauri::AppHandle,
+    script: String,
+    mut context: ScriptContext,
+    workspace_id: Option<String>,
+    is_post_request: bool,
+) -> Result<ScriptResult, String> {
+    let storage = app.state::<Arc<Storage>>();
+    let workspace_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let url = reqwest::Url::parse(&context.request.url).ok();
+    
+    let mut cookie_jar = CookieJar::load(&storage, workspace_id.as_deref());
+    if let Some(url) = &url {
+        for (name, value) in cookie_jar.values_for(url) {
+            context.cookies.entry(name).or_insert(value);
+        }
+    }
+    
+    let result = run_script_internal(script, context, is_post_request).await?;
+    
+    // Persist cookie changes made by the script
+    if let Some(url) = &url {
+        for (name, value) in &result.modified_cookies {
+            match value {
+                Some(value) => cookie_jar.set(url, name, value),
+                None => cookie_jar.remove(url, name),
+            }
+        }
+        cookie_jar.save(&storage, workspace_id.as_deref())?;
+    }
+    
+    Ok(result)
 }
 
 async fn run_script_internal(
@@ -101,6 +151,8 @@ async fn run_script_internal(
         std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
     let modified_headers: std::sync::Arc<std::sync::Mutex<HashMap<String, String>>> = 
         std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
+    let modified_cookies: std::sync::Arc<std::sync::Mutex<HashMap<String, Option<String>>>> = 
+        std::sync::Arc::new(std::sync::Mutex::new(HashMap::new()));
     let abort_flag: std::sync::Arc<std::sync::Mutex<bool>> = 
         std::sync::Arc::new(std::sync::Mutex::new(false));
     
@@ -176,6 +228,70 @@ async fn run_script_internal(
         
         istek.set("variables", vars_obj).unwrap();
         
+        // istek.cookies
+        let cookies_obj = Object::new(ctx.clone()).unwrap();
+        
+        // istek.cookies.get(name)
+        let cookies_for_get = context.cookies.clone();
+        let cookie_changes = modified_cookies.clone();
+        let get_cookie_fn = Function::new(ctx.clone(), move |name: String| -> Option<String> {
+            if let Ok(changes) = cookie_changes.lock() {
+                if let Some(change) = changes.get(&name) {
+                    return change.clone();
+                }
+            }
+            cookies_for_get.get(&name).cloned()
+        }).unwrap();
+        cookies_obj.set("get", get_cookie_fn).unwrap();
+        
+        // istek.cookies.has(name)
+        let cookies_for_has = context.cookies.clone();
+        let cookie_changes = modified_cookies.clone();
+        let has_cookie_fn = Function::new(ctx.clone(), move |name: String| -> bool {
+            if let Ok(changes) = cookie_changes.lock() {
+                if let Some(change) = changes.get(&name) {
+                    return change.is_some();
+                }
+            }
+            cookies_for_has.contains_key(&name)
+        }).unwrap();
+        cookies_obj.set("has", has_cookie_fn).unwrap();
+        
+        // istek.cookies.set(name, value)
+        let cookie_changes = modified_cookies.clone();
+        let set_cookie_fn = Function::new(ctx.clone(), move |name: String, value: String| {
+            if let Ok(mut changes) = cookie_changes.lock() {
+                changes.insert(name, Some(value));
+            }
+        }).unwrap();
+        cookies_obj.set("set", set_cookie_fn).unwrap();
+        
+        // istek.cookies.unset(name)
+        let cookie_changes = modified_cookies.clone();
+        let unset_cookie_fn = Function::new(ctx.clone(), move |name: String| {
+            if let Ok(mut changes) = cookie_changes.lock() {
+                changes.insert(name, None);
+            }
+        }).unwrap();
+        cookies_obj.set("unset", unset_cookie_fn).unwrap();
+        
+        // istek.cookies.clear() - remove every cookie sent to the request URL
+        let cookies_for_clear: Vec<String> = context.cookies.keys().cloned().collect();
+        let cookie_changes = modified_cookies.clone();
+        let clear_cookies_fn = Function::new(ctx.clone(), move || {
+            if let Ok(mut changes) = cookie_changes.lock() {
+                for value in changes.values_mut() {
+                    *value = None;
+                }
+                for name in &cookies_for_clear {
+                    changes.insert(name.clone(), None);
+                }
+            }
+        }).unwrap();
+        cookies_obj.set("clear", clear_cookies_fn).unwrap();
+        
+        istek.set("cookies", cookies_obj).unwrap();
+        
         // istek.request
         let request_obj = Object::new(ctx.clone()).unwrap();
         request_obj.set("method", context.request.method.as_str()).unwrap();
@@ -288,6 +404,9 @@ async fn run_script_internal(
     if let Ok(headers) = modified_headers.lock() {
         result.modified_headers = headers.clone();
     }
+    if let Ok(cookies) = modified_cookies.lock() {
+        result.modified_cookies = cookies.clone();
+    }
     if let Ok(abort) = abort_flag.lock() {
         result.abort_request = *abort;
     }
@@ -317,6 +436,7 @@ pub async fn test_script(script: String) -> Result<ScriptResult, String> {
         }),
         variables: HashMap::new(),
         environment: "Development".to_string(),
+        cookies: HashMap::new(),
     };
     
     run_script_internal(script, mock_context, true).await
@@ -9,6 +9,8 @@ use hkdf::Hkdf;
 use sha2::Sha256;
 use hmac::{Hmac, Mac};
 
+use crate::storage::SecretProvider;
+
 type Aes256CbcDec = cbc::Decryptor<aes::Aes256>;
 
 // Bitwarden encryption key structure
@@ -120,7 +122,7 @@ pub enum SecretProviderType {
     Azure,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProviderSpecificConfig {
     // AWS
@@ -162,6 +164,17 @@ pub struct SecretValue {
     pub value: String,
 }
 
+/// Points at a value held by a configured secret provider
+/// (same shape as the `secretProvider` of a variable)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretReference {
+    pub provider_id: String,
+    pub secret_path: String,
+    #[serde(default)]
+    pub secret_key: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FetchSecretsResult {
@@ -1267,24 +1280,80 @@ pub async fn test_secret_provider_connection(
     }
 }
 
-// Helper functions for AWS signature
-fn sha256_hex(data: &str) -> String {
-    use sha2::{Sha256, Digest};
-    let mut hasher = Sha256::new();
-    hasher.update(data.as_bytes());
-    hex::encode(hasher.finalize())
-}
+// Resolve a secret reference against the stored providers (used by the backend,
+// e.g. for client certificate passphrases; variables are resolved by the frontend)
+pub async fn resolve_secret(
+    providers: &[SecretProvider],
+    reference: &SecretReference,
+) -> Result<String, String> {
+    let provider = providers
+        .iter()
+        .find(|p| p.id == reference.provider_id)
+        .ok_or_else(|| format!("Secret provider not found: {}", reference.provider_id))?;
+    if !provider.enabled {
+        return Err(format!("Secret provider '{}' is disabled", provider.name));
+    }
 
-fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
-    use hmac::{Hmac, Mac};
-    use sha2::Sha256;
-    
-    type HmacSha256 = Hmac<Sha256>;
-    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take key of any size");
-    mac.update(data);
-    mac.finalize().into_bytes().to_vec()
+    // Map the stored provider config to the fetch parameters
+    let stored = provider.config.clone().unwrap_or_default();
+    let field = |name: &str| stored.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let path = Some(reference.secret_path.clone());
+    let config = match provider.provider_type.as_str() {
+        "aws" => ProviderSpecificConfig {
+            aws_region: field("region"),
+            aws_access_key_id: field("accessKeyId"),
+            aws_secret_access_key: field("secretAccessKey"),
+            aws_secret_name: path,
+            ..Default::default()
+        },
+        "gcp" => ProviderSpecificConfig {
+            gcp_project_id: field("projectId"),
+            gcp_credentials_json: field("credentialsJson"),
+            gcp_secret_name: path,
+            ..Default::default()
+        },
+        "azure" => ProviderSpecificConfig {
+            azure_vault_url: field("vaultUrl"),
+            azure_tenant_id: field("tenantId"),
+            azure_client_id: field("clientId"),
+            azure_client_secret: field("clientSecret"),
+            azure_secret_name: path,
+            ..Default::default()
+        },
+        "vault" => ProviderSpecificConfig {
+            vault_address: field("address"),
+            vault_token: field("token"),
+            vault_mount_path: field("mountPath"),
+            vault_namespace: field("namespace"),
+            vault_secret_path: path,
+            ..Default::default()
+        },
+        "bitwarden" => ProviderSpecificConfig {
+            bitwarden_server_url: field("serverUrl"),
+            bitwarden_api_key: field("apiKey"),
+            bitwarden_organization_id: field("organizationId"),
+            bitwarden_item_name: path,
+            ..Default::default()
+        },
+        other => return Err(format!("Unknown provider type: {}", other)),
+    };
+
+    let result = test_secret_provider_connection(provider.provider_type.clone(), config).await?;
+    if !result.success {
+        return Err(result.error.unwrap_or_else(|| "Failed to fetch secret".to_string()));
+    }
+
+    // The requested key, or the value of a single-value secret
+    result
+        .secrets
+        .iter()
+        .find(|s| s.key == reference.secret_key)
+        .or_else(|| (result.secrets.len() == 1).then(|| &result.secrets[0]))
+        .map(|s| s.value.clone())
+        .ok_or_else(|| format!("Key '{}' not found in secret '{}'", reference.secret_key, reference.secret_path))
 }
 
+// Helper functions for JWT signing
 fn base64_url_encode(data: &str) -> String {
     use base64::Engine;
     base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data.as_bytes())
@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tauri::{AppHandle, Emitter};
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::mpsc;
 use once_cell::sync::Lazy;
 use dashmap::DashMap;
+use url::Url;
+
+use crate::auth::{self, RequestAuth};
+use crate::http_client::{self, HttpClientSettings};
+use crate::storage::Storage;
 
 // Store active SSE connections
 pub static SSE_CONNECTIONS: Lazy<DashMap<String, mpsc::Sender<()>>> = Lazy::new(DashMap::new);
@@ -35,12 +41,21 @@ pub async fn sse_connect(
     connection_id: String,
     url: String,
     headers: HashMap<String, String>,
+    workspace_id: Option<String>,
+    auth: Option<RequestAuth>,
 ) -> Result<(), String> {
     // Check if already connected
     if SSE_CONNECTIONS.contains_key(&connection_id) {
         return Err("Already connected".to_string());
     }
 
+    // Use the workspace's client (proxy, TLS settings), without a total timeout
+    // since the stream stays open until disconnected
+    let storage = app.state::<Arc<Storage>>();
+    let settings = http_client::workspace_settings(&storage, workspace_id.as_deref());
+    let parsed_url = Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+    let client = http_client::client_for_url(&HttpClientSettings { timeout_ms: 0, ..settings }, &parsed_url).await?;
+
     // Create a channel to signal disconnection
     let (tx, mut rx) = mpsc::channel::<()>(1);
     SSE_CONNECTIONS.insert(connection_id.clone(), tx);
@@ -50,8 +65,6 @@ pub async fn sse_connect(
 
     // Spawn the SSE listener task
     tokio::spawn(async move {
-        let client = reqwest::Client::new();
-        
         let mut request = client.get(&url);
         for (key, value) in headers {
             request = request.header(&key, &value);
@@ -60,7 +73,11 @@ pub async fn sse_connect(
         request = request.header("Accept", "text/event-stream");
         request = request.header("Cache-Control", "no-cache");
 
-        match request.send().await {
+        let sent = match request.build() {
+            Ok(request) => auth::execute(&client, request, auth.as_ref()).await,
+            Err(e) => Err(e.to_string()),
+        };
+        match sent {
             Ok(response) => {
                 if !response.status().is_success() {
                     let _ = app_handle.emit("sse-connection", SseConnectionEvent {
@@ -2,7 +2,13 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
-use std::sync::RwLock;
+use std::sync::{Mutex, RwLock};
+
+use crate::http_client::HttpClientSettings;
+use crate::oauth::{AuthProfile, OAuthToken};
+use crate::proxy::ProxySettings;
+use crate::tls::TlsSettings;
+use crate::timing::ResponseTiming;
 
 // ============ Types ============
 
@@ -79,10 +85,39 @@ pub struct HistoryItem {
     pub request: serde_json::Value,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub response: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timing: Option<ResponseTiming>,
     pub timestamp: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Lowercase host or domain, without a leading dot
+    pub domain: String,
+    #[serde(default = "default_cookie_path")]
+    pub path: String,
+    /// Only sent to `domain` itself, not its subdomains (no Domain attribute was set)
+    #[serde(default)]
+    pub host_only: bool,
+    /// Unix timestamp in seconds; session cookies have none and live until cleared
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<i64>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub same_site: Option<String>,
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SecretProvider {
     pub id: String,
@@ -157,6 +192,14 @@ pub struct WorkspaceConfig {
     pub created_at: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub active_environment_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http_settings: Option<HttpClientSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxySettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_profiles: Option<Vec<AuthProfile>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -166,6 +209,54 @@ pub struct HistoryFile {
     pub items: Vec<HistoryItem>,
 }
 
+/// Proxy password, kept out of the git-synced workspace.yaml
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyCredentialsFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// Client certificate passphrases, keyed by certificate id
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsCredentialsFile {
+    #[serde(default)]
+    pub passphrases: HashMap<String, String>,
+}
+
+/// Auth profile client secrets and passwords, keyed by profile id
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthCredentialsFile {
+    #[serde(default)]
+    pub profiles: HashMap<String, AuthCredentials>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthCredentials {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// OAuth 2.0 tokens obtained for auth profiles
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OAuthTokensFile {
+    #[serde(default)]
+    pub tokens: Vec<OAuthToken>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CookiesFile {
+    #[serde(default)]
+    pub cookies: Vec<Cookie>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GlobalVariablesFile {
@@ -234,6 +325,10 @@ pub struct AppData {
 pub struct Storage {
     config_dir: PathBuf,
     cache: RwLock<StorageCache>,
+    /// Serializes read-modify-write of cookie files (concurrent sends, runs and edits)
+    cookies_lock: Mutex<()>,
+    /// Serializes read-modify-write of the OAuth token cache
+    oauth_tokens_lock: Mutex<()>,
 }
 
 #[derive(Default)]
@@ -253,6 +348,8 @@ impl Storage {
         let storage = Storage {
             config_dir,
             cache: RwLock::new(StorageCache::default()),
+            cookies_lock: Mutex::new(()),
+            oauth_tokens_lock: Mutex::new(()),
         };
         
         storage.ensure_default_workspace()?;
@@ -298,6 +395,26 @@ impl Storage {
         self.workspace_dir(workspace_id).join("history.yaml")
     }
 
+    fn cookies_path(&self, workspace_id: &str) -> PathBuf {
+        self.workspace_dir(workspace_id).join("cookies.local.yaml")
+    }
+
+    fn proxy_credentials_path(&self, workspace_id: &str) -> PathBuf {
+        self.workspace_dir(workspace_id).join("proxy.local.yaml")
+    }
+
+    fn tls_credentials_path(&self, workspace_id: &str) -> PathBuf {
+        self.workspace_dir(workspace_id).join("tls.local.yaml")
+    }
+
+    fn auth_credentials_path(&self, workspace_id: &str) -> PathBuf {
+        self.workspace_dir(workspace_id).join("auth.local.yaml")
+    }
+
+    fn oauth_tokens_path(&self, workspace_id: &str) -> PathBuf {
+        self.workspace_dir(workspace_id).join("oauth-tokens.local.yaml")
+    }
+
     fn secret_providers_path(&self) -> PathBuf {
         self.config_dir.join("secret-providers.yaml")
     }
@@ -394,6 +511,10 @@ impl Storage {
                 is_default: true,
                 created_at: now,
                 active_environment_id: None,
+                http_settings: None,
+                proxy: None,
+                tls: None,
+                auth_profiles: None,
             };
             self.write_yaml(&self.workspace_config_path(&workspace_id), &ws_config)?;
             
@@ -494,6 +615,10 @@ impl Storage {
             is_default: false,
             created_at: now,
             active_environment_id: None,
+            http_settings: None,
+            proxy: None,
+            tls: None,
+            auth_profiles: None,
         };
         self.write_yaml(&self.workspace_config_path(&workspace_id), &ws_config)?;
         
@@ -790,6 +915,123 @@ impl Storage {
         Ok(ws_config.active_environment_id)
     }
 
+    // ============ HTTP Client Settings ============
+
+    pub fn get_http_settings(&self, workspace_id: &str) -> Result<HttpClientSettings, String> {
+        let ws_config = self.load_workspace_config(workspace_id)?;
+        Ok(ws_config.http_settings.unwrap_or_default())
+    }
+
+    pub fn save_http_settings(&self, workspace_id: &str, settings: &HttpClientSettings) -> Result<(), String> {
+        let mut ws_config = self.load_workspace_config(workspace_id)?;
+        ws_config.http_settings = Some(settings.clone());
+        self.save_workspace_config(&ws_config)
+    }
+
+    // ============ Proxy Settings ============
+
+    pub fn get_proxy_settings(&self, workspace_id: &str) -> Result<ProxySettings, String> {
+        let ws_config = self.load_workspace_config(workspace_id)?;
+        let mut settings = ws_config.proxy.unwrap_or_default();
+        let credentials: ProxyCredentialsFile = self.read_yaml(&self.proxy_credentials_path(workspace_id))?;
+        settings.password = credentials.password;
+        Ok(settings)
+    }
+
+    pub fn save_proxy_settings(&self, workspace_id: &str, settings: &ProxySettings) -> Result<(), String> {
+        // Store the password in proxy.local.yaml (git-ignored), never in workspace.yaml
+        let mut settings = settings.clone();
+        let credentials = ProxyCredentialsFile {
+            password: settings.password.take().filter(|p| !p.is_empty()),
+        };
+        self.write_yaml(&self.proxy_credentials_path(workspace_id), &credentials)?;
+
+        let mut ws_config = self.load_workspace_config(workspace_id)?;
+        ws_config.proxy = Some(settings);
+        self.save_workspace_config(&ws_config)
+    }
+
+    // ============ TLS Settings ============
+
+    pub fn get_tls_settings(&self, workspace_id: &str) -> Result<TlsSettings, String> {
+        let ws_config = self.load_workspace_config(workspace_id)?;
+        let mut settings = ws_config.tls.unwrap_or_default();
+        let mut credentials: TlsCredentialsFile = self.read_yaml(&self.tls_credentials_path(workspace_id))?;
+        for cert in &mut settings.client_certificates {
+            cert.passphrase = credentials.passphrases.remove(&cert.id);
+        }
+        Ok(settings)
+    }
+
+    pub fn save_tls_settings(&self, workspace_id: &str, settings: &TlsSettings) -> Result<(), String> {
+        // Store passphrases in tls.local.yaml (git-ignored), never in workspace.yaml
+        let mut settings = settings.clone();
+        let mut credentials = TlsCredentialsFile::default();
+        for cert in &mut settings.client_certificates {
+            if let Some(passphrase) = cert.passphrase.take().filter(|p| !p.is_empty()) {
+                credentials.passphrases.insert(cert.id.clone(), passphrase);
+            }
+        }
+        self.write_yaml(&self.tls_credentials_path(workspace_id), &credentials)?;
+
+        let mut ws_config = self.load_workspace_config(workspace_id)?;
+        ws_config.tls = Some(settings);
+        self.save_workspace_config(&ws_config)
+    }
+
+    // ============ Auth Profiles ============
+
+    pub fn get_auth_profiles(&self, workspace_id: &str) -> Result<Vec<AuthProfile>, String> {
+        let ws_config = self.load_workspace_config(workspace_id)?;
+        let mut profiles = ws_config.auth_profiles.unwrap_or_default();
+        let mut credentials: AuthCredentialsFile = self.read_yaml(&self.auth_credentials_path(workspace_id))?;
+        for profile in &mut profiles {
+            if let Some(secrets) = credentials.profiles.remove(&profile.id) {
+                profile.client_secret = secrets.client_secret;
+                profile.password = secrets.password;
+            }
+        }
+        Ok(profiles)
+    }
+
+    pub fn save_auth_profiles(&self, workspace_id: &str, profiles: &[AuthProfile]) -> Result<(), String> {
+        // Store secrets in auth.local.yaml (git-ignored), never in workspace.yaml
+        let mut profiles = profiles.to_vec();
+        let mut credentials = AuthCredentialsFile::default();
+        for profile in &mut profiles {
+            let secrets = AuthCredentials {
+                client_secret: profile.client_secret.take().filter(|s| !s.is_empty()),
+                password: profile.password.take().filter(|p| !p.is_empty()),
+            };
+            if secrets.client_secret.is_some() || secrets.password.is_some() {
+                credentials.profiles.insert(profile.id.clone(), secrets);
+            }
+        }
+        self.write_yaml(&self.auth_credentials_path(workspace_id), &credentials)?;
+
+        // Drop tokens of profiles that no longer exist
+        self.update_oauth_tokens(workspace_id, |tokens| {
+            tokens.retain(|t| profiles.iter().any(|p| p.id == t.profile_id))
+        })?;
+
+        let mut ws_config = self.load_workspace_config(workspace_id)?;
+        ws_config.auth_profiles = Some(profiles);
+        self.save_workspace_config(&ws_config)
+    }
+
+    pub fn get_oauth_tokens(&self, workspace_id: &str) -> Result<Vec<OAuthToken>, String> {
+        let file: OAuthTokensFile = self.read_yaml(&self.oauth_tokens_path(workspace_id))?;
+        Ok(file.tokens)
+    }
+
+    /// Read, change and write the cached tokens while holding the token file lock
+    pub fn update_oauth_tokens(&self, workspace_id: &str, update: impl FnOnce(&mut Vec<OAuthToken>)) -> Result<(), String> {
+        let _guard = self.oauth_tokens_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file: OAuthTokensFile = self.read_yaml(&self.oauth_tokens_path(workspace_id))?;
+        update(&mut file.tokens);
+        self.write_yaml(&self.oauth_tokens_path(workspace_id), &file)
+    }
+
     // ============ Global Variables Operations ============
 
     pub fn get_global_variables(&self, workspace_id: &str) -> Result<Vec<Variable>, String> {
@@ -841,6 +1083,23 @@ impl Storage {
         self.write_yaml(&self.global_variables_path(workspace_id), &file)
     }
 
+    /// Add imported variables; existing variables with the same key take the imported value.
+    /// Returns the resulting global variables.
+    pub fn merge_global_variables(&self, workspace_id: &str, imported: &[Variable]) -> Result<Vec<Variable>, String> {
+        let mut variables = self.get_global_variables(workspace_id)?;
+        for variable in imported {
+            if let Some(existing) = variables.iter_mut().find(|v| v.key == variable.key) {
+                existing.value = variable.value.clone();
+                existing.is_secret = variable.is_secret;
+                existing.enabled = variable.enabled;
+            } else {
+                variables.push(variable.clone());
+            }
+        }
+        self.save_all_global_variables(workspace_id, &variables)?;
+        Ok(variables)
+    }
+
     // ============ History Operations ============
 
     pub fn get_history(&self, workspace_id: &str) -> Result<Vec<HistoryItem>, String> {
@@ -854,8 +1113,19 @@ impl Storage {
         // Remove existing if same ID
         file.items.retain(|i| i.id != item.id);
         
+        // Keep the timing breakdown alongside the item even if the caller only
+        // passed it inside the response
+        let mut item = item.clone();
+        if item.timing.is_none() {
+            item.timing = item
+                .response
+                .as_ref()
+                .and_then(|r| r.get("timing"))
+                .and_then(|t| serde_json::from_value(t.clone()).ok());
+        }
+        
         // Insert at beginning
-        file.items.insert(0, item.clone());
+        file.items.insert(0, item);
         
         // Keep only last 100
         file.items.truncate(100);
@@ -874,6 +1144,48 @@ impl Storage {
         self.write_yaml(&self.history_path(workspace_id), &file)
     }
 
+    // ============ Cookie Operations ============
+
+    pub fn get_cookies(&self, workspace_id: &str) -> Result<Vec<Cookie>, String> {
+        let file: CookiesFile = self.read_yaml(&self.cookies_path(workspace_id))?;
+        Ok(file.cookies)
+    }
+
+    /// Change the stored cookies of a workspace; other updates wait, so none is lost
+    pub fn update_cookies(&self, workspace_id: &str, update: impl FnOnce(&mut Vec<Cookie>)) -> Result<(), String> {
+        let _guard = self.cookies_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut file: CookiesFile = self.read_yaml(&self.cookies_path(workspace_id))?;
+        update(&mut file.cookies);
+        self.write_yaml(&self.cookies_path(workspace_id), &file)
+    }
+
+    /// Insert or replace a cookie (cookies are identified by name, domain and path)
+    pub fn save_cookie(&self, workspace_id: &str, cookie: &Cookie) -> Result<(), String> {
+        self.update_cookies(workspace_id, |cookies| {
+            if let Some(existing) = cookies.iter_mut().find(|c| {
+                c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path
+            }) {
+                *existing = cookie.clone();
+            } else {
+                cookies.push(cookie.clone());
+            }
+        })
+    }
+
+    pub fn delete_cookie(&self, workspace_id: &str, name: &str, domain: &str, path: &str) -> Result<(), String> {
+        self.update_cookies(workspace_id, |cookies| {
+            cookies.retain(|c| !(c.name == name && c.domain == domain && c.path == path));
+        })
+    }
+
+    /// Remove all cookies, or only those stored for one domain
+    pub fn clear_cookies(&self, workspace_id: &str, domain: Option<&str>) -> Result<(), String> {
+        self.update_cookies(workspace_id, |cookies| match domain {
+            Some(domain) => cookies.retain(|c| c.domain != domain),
+            None => cookies.clear(),
+        })
+    }
+
     // ============ Secret Provider Operations (Global) ============
 
     pub fn get_secret_providers(&self) -> Result<Vec<SecretProvider>, String> {
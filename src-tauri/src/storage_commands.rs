@@ -1,8 +1,12 @@
 use std::sync::Arc;
 use tauri::Manager;
 
+use crate::http_client::{self, HttpClientSettings};
+use crate::proxy::{self, ProxyMode, ProxySettings};
+use crate::tls::TlsSettings;
+use crate::oauth::AuthProfile;
 use crate::storage::{
-    AppData, Collection, Environment, HistoryItem, McpServer, SecretProvider, 
+    AppData, Collection, Cookie, Environment, HistoryItem, McpServer, SecretProvider, 
     SensitiveValue, Storage, TestRunHistory, Variable, Workspace
 };
 
@@ -124,6 +128,58 @@ pub async fn clear_history(app: tauri::AppHandle, workspace_id: Option<String>)
     storage.clear_history(&ws_id)
 }
 
+// ============ Cookie Commands ============
+
+#[tauri::command]
+pub async fn get_cookies(app: tauri::AppHandle, workspace_id: Option<String>, domain: Option<String>) -> Result<Vec<Cookie>, String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    let cookies = storage.get_cookies(&ws_id)?;
+    Ok(match domain {
+        Some(domain) => cookies.into_iter().filter(|c| c.domain == domain).collect(),
+        None => cookies,
+    })
+}
+
+#[tauri::command]
+pub async fn save_cookie(app: tauri::AppHandle, cookie: Cookie, workspace_id: Option<String>) -> Result<(), String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    storage.save_cookie(&ws_id, &cookie)
+}
+
+#[tauri::command]
+pub async fn delete_cookie(
+    app: tauri::AppHandle,
+    name: String,
+    domain: String,
+    path: String,
+    workspace_id: Option<String>,
+) -> Result<(), String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    storage.delete_cookie(&ws_id, &name, &domain, &path)
+}
+
+#[tauri::command]
+pub async fn clear_cookies(app: tauri::AppHandle, workspace_id: Option<String>, domain: Option<String>) -> Result<(), String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    storage.clear_cookies(&ws_id, domain.as_deref())
+}
+
 // ============ Global Variable Commands ============
 
 #[tauri::command]
@@ -198,6 +254,111 @@ pub async fn save_active_environment_id(app: tauri::AppHandle, id: Option<String
     storage.set_active_environment(&ws_id, id.as_deref())
 }
 
+// ============ HTTP Client Settings Commands ============
+
+#[tauri::command]
+pub async fn get_http_settings(app: tauri::AppHandle, workspace_id: Option<String>) -> Result<HttpClientSettings, String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    storage.get_http_settings(&ws_id)
+}
+
+#[tauri::command]
+pub async fn save_http_settings(app: tauri::AppHandle, settings: HttpClientSettings, workspace_id: Option<String>) -> Result<(), String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    storage.save_http_settings(&ws_id, &settings)?;
+    // Rebuild clients lazily with the new configuration
+    http_client::clear_clients();
+    Ok(())
+}
+
+// ============ Proxy Settings Commands ============
+
+#[tauri::command]
+pub async fn get_proxy_settings(app: tauri::AppHandle, workspace_id: Option<String>) -> Result<ProxySettings, String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    storage.get_proxy_settings(&ws_id)
+}
+
+#[tauri::command]
+pub async fn save_proxy_settings(app: tauri::AppHandle, settings: ProxySettings, workspace_id: Option<String>) -> Result<(), String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    if settings.mode == ProxyMode::Manual {
+        proxy::parse_proxy_url(&settings.url)?;
+    }
+    storage.save_proxy_settings(&ws_id, &settings)?;
+    http_client::clear_clients();
+    Ok(())
+}
+
+// ============ TLS Settings Commands ============
+
+#[tauri::command]
+pub async fn get_tls_settings(app: tauri::AppHandle, workspace_id: Option<String>) -> Result<TlsSettings, String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    storage.get_tls_settings(&ws_id)
+}
+
+#[tauri::command]
+pub async fn save_tls_settings(app: tauri::AppHandle, settings: TlsSettings, workspace_id: Option<String>) -> Result<(), String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    settings.ca_certificates()?;
+    if let Some(cert) = settings.client_certificates.iter().find(|c| c.host.trim().is_empty() || c.cert_path.trim().is_empty()) {
+        return Err(format!("Client certificate {} needs a host and a certificate file", cert.id));
+    }
+    storage.save_tls_settings(&ws_id, &settings)?;
+    http_client::clear_clients();
+    Ok(())
+}
+
+// ============ Auth Profile Commands ============
+
+#[tauri::command]
+pub async fn get_auth_profiles(app: tauri::AppHandle, workspace_id: Option<String>) -> Result<Vec<AuthProfile>, String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    storage.get_auth_profiles(&ws_id)
+}
+
+#[tauri::command]
+pub async fn save_auth_profiles(app: tauri::AppHandle, profiles: Vec<AuthProfile>, workspace_id: Option<String>) -> Result<(), String> {
+    let storage = app.state::<Arc<Storage>>();
+    
+    let ws_id = workspace_id.or_else(|| storage.get_active_workspace_id().ok().flatten());
+    let ws_id = ws_id.ok_or("No active workspace")?;
+    
+    if let Some(profile) = profiles.iter().find(|p| p.token_url.trim().is_empty() || p.client_id.trim().is_empty()) {
+        return Err(format!("Auth profile \"{}\" needs a token URL and a client id", profile.name));
+    }
+    storage.save_auth_profiles(&ws_id, &profiles)
+}
+
 // ============ Secret Provider Commands ============
 
 #[tauri::command]
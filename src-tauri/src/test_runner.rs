@@ -1,9 +1,23 @@
 use jsonpath_rust::JsonPath;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::auth::{self, RequestAuth};
+use crate::cancel;
+use crate::cookies::CookieJar;
+use crate::http_client::{self, HttpClientSettings};
+use crate::iteration_data::IterationSummary;
+use crate::oauth;
+use crate::redirect::RedirectOptions;
+use crate::retry::{RetryAttempt, RetryPolicy};
+use crate::run_plan::{self, Scheduler};
+use crate::storage::Storage;
+use crate::timing::{ResponseTiming, TimingPhase};
 
 // ============ Assertion Types ============
 
@@ -16,9 +30,12 @@ pub enum AssertionType {
     Contains,
     ResponseTime,
     Header,
+    Timing,
+    JsonSchema,
+    BodySize,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum JsonPathOperator {
     Equals,
@@ -26,6 +43,21 @@ pub enum JsonPathOperator {
     Contains,
     Exists,
     NotExists,
+    /// Regular expression match
+    Matches,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    /// string, number, integer, boolean, array, object or null
+    IsType,
+    /// Length of an array, object or string
+    LengthEquals,
+    LengthGt,
+    /// The array contains the expected value
+    In,
+    /// The value is one of a JSON array or comma-separated list
+    OneOf,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -42,15 +74,21 @@ pub struct Assertion {
     pub max_status: Option<u16>,
     // For jsonpath
     pub json_path: Option<String>,
+    // For jsonpath, header, response_time and body_size
     pub operator: Option<JsonPathOperator>,
     pub expected_value: Option<String>,
     // For contains
     pub search_string: Option<String>,
-    // For response_time
+    // For response_time and timing
     pub max_time_ms: Option<u64>,
+    // For timing
+    pub timing_phase: Option<TimingPhase>,
     // For header
     pub header_name: Option<String>,
     pub header_value: Option<String>,
+    // For json_schema (the request's response schema when not set)
+    #[serde(default)]
+    pub json_schema: Option<Value>,
 }
 
 // ============ Variable Extraction ============
@@ -91,6 +129,21 @@ pub struct TestRequest {
     pub assertions: Option<Vec<Assertion>>,
     // Variables to extract from response
     pub extract_variables: Option<Vec<VariableExtraction>>,
+    // Measure DNS/connect/TLS on a dedicated connection
+    pub detailed_timing: Option<bool>,
+    // Redirect handling (follows up to 10 redirects by default)
+    pub redirects: Option<RedirectOptions>,
+    // Retry policy (overrides the one of the run)
+    pub retry: Option<RetryPolicy>,
+    // Auth profile whose OAuth 2.0 token is sent (overrides the one of the run)
+    #[serde(default)]
+    pub auth_profile_id: Option<String>,
+    // Authentication applied when the request is sent (e.g. AWS Signature V4)
+    #[serde(default)]
+    pub auth: Option<RequestAuth>,
+    // Response schema from an OpenAPI import, for json_schema assertions
+    #[serde(default)]
+    pub response_schema: Option<Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -109,6 +162,11 @@ pub struct TestResult {
     pub method: String,
     pub url: String,
     pub status: TestStatus,
+    // Headers and body as sent (before backend-applied auth), for exports
+    #[serde(default)]
+    pub request_headers: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub request_body: Option<String>,
     pub response_status: Option<u16>,
     pub response_time: Option<u64>,
     pub response_size: Option<usize>,
@@ -117,6 +175,13 @@ pub struct TestResult {
     pub error: Option<String>,
     pub assertions: Vec<AssertionResult>,
     pub extracted_variables: Option<Vec<ExtractedVariable>>,
+    pub timing: Option<ResponseTiming>,
+    // Every attempt when a retry policy applies
+    #[serde(default)]
+    pub attempts: Vec<RetryAttempt>,
+    // Iteration of a data-driven run (1-based)
+    #[serde(default)]
+    pub iteration: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -146,6 +211,21 @@ pub struct TestRunConfig {
     pub requests: Vec<TestRequest>,
     pub stop_on_failure: bool,
     pub delay_between_requests: u64, // milliseconds
+    // Retry policy for requests without their own
+    #[serde(default)]
+    pub retry: Option<RetryPolicy>,
+    // Auth profile for requests without their own
+    #[serde(default)]
+    pub auth_profile_id: Option<String>,
+    // One full pass through the requests per row, seeded with its variables
+    #[serde(default)]
+    pub iteration_data: Vec<HashMap<String, String>>,
+    // Requests running at once (1, the default, runs them in order); requests
+    // using an extracted variable still wait for the one extracting it, and
+    // nothing starts alongside a request that may set cookies (each request the
+    // first time it runs, and those that set cookies)
+    #[serde(default)]
+    pub concurrency: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -159,6 +239,12 @@ pub struct TestRunSummary {
     pub errors: usize,
     pub total_time: u64,
     pub results: Vec<TestResult>,
+    /// The run was cancelled before all requests finished
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Per-iteration counts of a data-driven run
+    #[serde(default)]
+    pub iterations: Vec<IterationSummary>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -215,16 +301,303 @@ fn value_to_string(value: &Value) -> String {
     }
 }
 
+// ============ Assertion Operators ============
+
+impl JsonPathOperator {
+    /// The expected side of an assertion as shown in results (`> 5`, `matches ^a`)
+    pub(crate) fn describe(&self, expected: &str) -> String {
+        match self {
+            JsonPathOperator::Equals | JsonPathOperator::NotEquals | JsonPathOperator::Contains => expected.to_string(),
+            JsonPathOperator::Exists => "exists".to_string(),
+            JsonPathOperator::NotExists => "not exists".to_string(),
+            JsonPathOperator::Matches => format!("matches {}", expected),
+            JsonPathOperator::Gt => format!("> {}", expected),
+            JsonPathOperator::Gte => format!(">= {}", expected),
+            JsonPathOperator::Lt => format!("< {}", expected),
+            JsonPathOperator::Lte => format!("<= {}", expected),
+            JsonPathOperator::IsType => format!("type {}", expected),
+            JsonPathOperator::LengthEquals => format!("length {}", expected),
+            JsonPathOperator::LengthGt => format!("length > {}", expected),
+            JsonPathOperator::In => format!("includes {}", expected),
+            JsonPathOperator::OneOf => format!("one of {}", expected),
+        }
+    }
+}
+
+/// Numbers, and strings holding one (header values)
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn length(value: &Value) -> Option<usize> {
+    match value {
+        Value::Array(items) => Some(items.len()),
+        Value::Object(fields) => Some(fields.len()),
+        Value::String(s) => Some(s.chars().count()),
+        _ => None,
+    }
+}
+
+fn is_type(value: &Value, expected: &str) -> Result<bool, String> {
+    Ok(match expected.trim().to_lowercase().as_str() {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        other => return Err(format!("Unknown type '{}'", other)),
+    })
+}
+
+/// `["a", 1]` or `a, 1` as the string forms of its items
+fn expected_list(expected: &str) -> Vec<String> {
+    match serde_json::from_str::<Value>(expected) {
+        Ok(Value::Array(items)) => items.iter().map(value_to_string).collect(),
+        _ => expected.split(',').map(|item| item.trim().to_string()).collect(),
+    }
+}
+
+/// Whether `actual` (null when missing) satisfies the operator; errors
+/// describe an expected value the operator cannot use
+pub(crate) fn apply_operator(operator: &JsonPathOperator, actual: &Value, expected: &str) -> Result<bool, String> {
+    let actual_str = value_to_string(actual);
+    let number = |s: &str| s.trim().parse::<f64>().map_err(|_| format!("'{}' is not a number", s));
+    let count = |s: &str| s.trim().parse::<usize>().map_err(|_| format!("'{}' is not a length", s));
+    Ok(match operator {
+        JsonPathOperator::Equals => actual_str == expected,
+        JsonPathOperator::NotEquals => actual_str != expected,
+        JsonPathOperator::Contains => actual_str.contains(expected),
+        JsonPathOperator::Exists => !actual.is_null(),
+        JsonPathOperator::NotExists => actual.is_null(),
+        JsonPathOperator::Matches => Regex::new(expected)
+            .map_err(|e| format!("Invalid regex: {}", e))?
+            .is_match(&actual_str),
+        JsonPathOperator::Gt | JsonPathOperator::Gte | JsonPathOperator::Lt | JsonPathOperator::Lte => {
+            let limit = number(expected)?;
+            as_number(actual).is_some_and(|n| match operator {
+                JsonPathOperator::Gt => n > limit,
+                JsonPathOperator::Gte => n >= limit,
+                JsonPathOperator::Lt => n < limit,
+                _ => n <= limit,
+            })
+        }
+        JsonPathOperator::IsType => is_type(actual, expected)?,
+        JsonPathOperator::LengthEquals => {
+            let expected = count(expected)?;
+            length(actual) == Some(expected)
+        }
+        JsonPathOperator::LengthGt => {
+            let min = count(expected)?;
+            length(actual).is_some_and(|n| n > min)
+        }
+        JsonPathOperator::In => actual
+            .as_array()
+            .is_some_and(|items| items.iter().any(|item| value_to_string(item) == expected)),
+        JsonPathOperator::OneOf => expected_list(expected).contains(&actual_str),
+    })
+}
+
+/// Whether an operator assertion passed, with `actual` as a string
+pub(crate) fn operator_result(operator: &JsonPathOperator, actual: &Value, expected: &str) -> Result<(bool, String), String> {
+    apply_operator(operator, actual, expected).map(|passed| (passed, value_to_string(actual)))
+}
+
+/// An operator assertion result; `shown` formats the actual value unless the comparison failed
+pub(crate) fn operator_assertion(
+    name: String,
+    operator: &JsonPathOperator,
+    actual: &Value,
+    expected: &str,
+    shown: impl FnOnce(String) -> String,
+) -> AssertionResult {
+    let (passed, actual) = match operator_result(operator, actual, expected) {
+        Ok((passed, actual)) => (passed, shown(actual)),
+        Err(e) => (false, format!("Error: {}", e)),
+    };
+    AssertionResult {
+        name,
+        passed,
+        expected: operator.describe(expected),
+        actual,
+    }
+}
+
+// ============ JSON Schema Validation ============
+
+/// OpenAPI 3.0 schemas mark nullable values with `nullable: true`, which
+/// JSON Schema spells as a `null` type
+fn openapi_to_json_schema(schema: &Value) -> Value {
+    match schema {
+        Value::Object(obj) => {
+            let mut converted: serde_json::Map<String, Value> = obj
+                .iter()
+                .map(|(key, value)| (key.clone(), openapi_to_json_schema(value)))
+                .collect();
+            if converted.get("nullable") == Some(&Value::Bool(true)) {
+                converted.remove("nullable");
+                if let Some(Value::String(t)) = converted.get("type").cloned() {
+                    converted.insert("type".to_string(), serde_json::json!([t, "null"]));
+                }
+            }
+            Value::Object(converted)
+        }
+        Value::Array(items) => Value::Array(items.iter().map(openapi_to_json_schema).collect()),
+        _ => schema.clone(),
+    }
+}
+
+/// Validate a response body against a JSON Schema: one failed result per
+/// violation, named after the instance path, or a single passed result
+pub(crate) fn evaluate_json_schema(schema: Option<&Value>, response_body: &str) -> Vec<AssertionResult> {
+    let failure = |actual: String| {
+        vec![AssertionResult {
+            name: "JSON Schema".to_string(),
+            passed: false,
+            expected: "matches schema".to_string(),
+            actual,
+        }]
+    };
+
+    let Some(schema) = schema else {
+        return failure("No schema configured and the request has no response schema".to_string());
+    };
+    let instance: Value = match serde_json::from_str(response_body) {
+        Ok(instance) => instance,
+        Err(e) => return failure(format!("Invalid JSON: {}", e)),
+    };
+    let validator = match jsonschema::validator_for(&openapi_to_json_schema(schema)) {
+        Ok(validator) => validator,
+        Err(e) => return failure(format!("Invalid schema: {}", e)),
+    };
+
+    let violations: Vec<AssertionResult> = validator
+        .iter_errors(&instance)
+        .map(|error| {
+            let path = error.instance_path.to_string();
+            AssertionResult {
+                name: format!("JSON Schema {}", if path.is_empty() { "(root)" } else { &path }),
+                passed: false,
+                expected: format!("matches {}", error.schema_path),
+                actual: error.to_string(),
+            }
+        })
+        .collect();
+    if !violations.is_empty() {
+        return violations;
+    }
+    vec![AssertionResult {
+        name: "Response matches JSON Schema".to_string(),
+        passed: true,
+        expected: "matches schema".to_string(),
+        actual: "valid".to_string(),
+    }]
+}
+
 // ============ Assertion Evaluation ============
 
+/// A response_time assertion: an operator comparison, or `<= max_time_ms` without an operator
+pub(crate) fn response_time_result(
+    operator: Option<&JsonPathOperator>,
+    expected_value: Option<&str>,
+    max_time_ms: Option<u64>,
+    response_time_ms: u64,
+) -> AssertionResult {
+    match operator {
+        Some(operator) => {
+            let expected = expected_value
+                .map(|v| v.to_string())
+                .or_else(|| max_time_ms.map(|ms| ms.to_string()))
+                .unwrap_or_default();
+            operator_assertion(
+                format!("Response time {}ms", operator.describe(&expected)),
+                operator,
+                &Value::from(response_time_ms),
+                &expected,
+                |actual| format!("{}ms", actual),
+            )
+        }
+        None => {
+            let max_time = max_time_ms.unwrap_or(5000);
+            let passed = response_time_ms <= max_time;
+            AssertionResult {
+                name: format!("Response time < {}ms", max_time),
+                passed,
+                expected: format!("< {}ms", max_time),
+                actual: format!("{}ms", response_time_ms),
+            }
+        }
+    }
+}
+
+/// A header assertion. Without an operator, a value is compared for equality and its
+/// absence checks existence.
+pub(crate) fn header_result(
+    header_name: &str,
+    expected_value: Option<&str>,
+    operator: Option<&JsonPathOperator>,
+    response_headers: &HashMap<String, String>,
+) -> AssertionResult {
+    let operator = match operator {
+        Some(operator) => operator.clone(),
+        None if expected_value.is_some() => JsonPathOperator::Equals,
+        None => JsonPathOperator::Exists,
+    };
+
+    let actual_value = response_headers
+        .iter()
+        .find(|(k, _)| k.to_lowercase() == header_name.to_lowercase())
+        .map(|(_, v)| Value::String(v.clone()));
+
+    let mut result = operator_assertion(
+        format!("Header '{}'", header_name),
+        &operator,
+        actual_value.as_ref().unwrap_or(&Value::Null),
+        expected_value.unwrap_or(""),
+        |actual| actual,
+    );
+    if actual_value.is_none() {
+        result.actual = "header not found".to_string();
+    }
+    result
+}
+
+/// A body_size assertion (`<=` by default); the expected size has to be set
+pub(crate) fn body_size_result(operator: Option<&JsonPathOperator>, expected_value: Option<&str>, size: usize) -> AssertionResult {
+    let operator = operator.unwrap_or(&JsonPathOperator::Lte);
+    let Some(expected) = expected_value.filter(|v| !v.trim().is_empty()) else {
+        return AssertionResult {
+            name: "Body size".to_string(),
+            passed: false,
+            expected: operator.describe("?"),
+            actual: "Error: no expected size set".to_string(),
+        };
+    };
+    operator_assertion(
+        format!("Body size {} bytes", operator.describe(expected)),
+        operator,
+        &Value::from(size),
+        expected,
+        |actual| format!("{} bytes", actual),
+    )
+}
+
 fn evaluate_assertion(
     assertion: &Assertion,
     response_status: u16,
-    response_time_ms: u64,
+    timing: &ResponseTiming,
     response_body: &str,
+    body_size: usize,
     response_headers: &HashMap<String, String>,
-) -> AssertionResult {
-    match assertion.assertion_type {
+    response_schema: Option<&Value>,
+) -> Vec<AssertionResult> {
+    let response_time_ms = timing.total_ms.round() as u64;
+    let result = match assertion.assertion_type {
         AssertionType::Status => {
             let expected = assertion.expected_status.unwrap_or(200);
             let passed = response_status == expected;
@@ -252,26 +625,13 @@ fn evaluate_assertion(
             let expected_value = assertion.expected_value.as_deref().unwrap_or("");
             
             match evaluate_jsonpath(response_body, path) {
-                Ok(actual_value) => {
-                    let actual_str = value_to_string(&actual_value);
-                    let passed = match operator {
-                        JsonPathOperator::Equals => actual_str == expected_value,
-                        JsonPathOperator::NotEquals => actual_str != expected_value,
-                        JsonPathOperator::Contains => actual_str.contains(expected_value),
-                        JsonPathOperator::Exists => actual_value != Value::Null,
-                        JsonPathOperator::NotExists => actual_value == Value::Null,
-                    };
-                    AssertionResult {
-                        name: format!("JSONPath {} {:?}", path, operator),
-                        passed,
-                        expected: match operator {
-                            JsonPathOperator::Exists => "exists".to_string(),
-                            JsonPathOperator::NotExists => "not exists".to_string(),
-                            _ => expected_value.to_string(),
-                        },
-                        actual: actual_str,
-                    }
-                }
+                Ok(actual_value) => operator_assertion(
+                    format!("JSONPath {} {:?}", path, operator),
+                    operator,
+                    &actual_value,
+                    expected_value,
+                    |actual| actual,
+                ),
                 Err(e) => AssertionResult {
                     name: format!("JSONPath {}", path),
                     passed: false,
@@ -290,48 +650,41 @@ fn evaluate_assertion(
                 actual: if passed { "found".to_string() } else { "not found".to_string() },
             }
         }
-        AssertionType::ResponseTime => {
+        AssertionType::ResponseTime => response_time_result(
+            assertion.operator.as_ref(),
+            assertion.expected_value.as_deref(),
+            assertion.max_time_ms,
+            response_time_ms,
+        ),
+        AssertionType::Header => header_result(
+            assertion.header_name.as_deref().unwrap_or(""),
+            assertion.header_value.as_deref(),
+            assertion.operator.as_ref(),
+            response_headers,
+        ),
+        AssertionType::BodySize => body_size_result(
+            assertion.operator.as_ref(),
+            assertion.expected_value.as_deref(),
+            body_size,
+        ),
+        AssertionType::Timing => {
+            let phase = assertion.timing_phase.unwrap_or(TimingPhase::Total);
             let max_time = assertion.max_time_ms.unwrap_or(5000);
-            let passed = response_time_ms <= max_time;
+            let actual = timing.phase_ms(phase);
             AssertionResult {
-                name: format!("Response time < {}ms", max_time),
-                passed,
+                name: format!("{:?} time < {}ms", phase, max_time),
+                passed: actual.is_some_and(|ms| ms <= max_time as f64),
                 expected: format!("< {}ms", max_time),
-                actual: format!("{}ms", response_time_ms),
+                actual: actual
+                    .map(|ms| format!("{:.1}ms", ms))
+                    .unwrap_or_else(|| "not measured".to_string()),
             }
         }
-        AssertionType::Header => {
-            let header_name = assertion.header_name.as_deref().unwrap_or("");
-            let expected_value = assertion.header_value.as_deref();
-            
-            let actual_value = response_headers
-                .iter()
-                .find(|(k, _)| k.to_lowercase() == header_name.to_lowercase())
-                .map(|(_, v)| v.as_str());
-            
-            let (passed, expected_str, actual_str) = match (expected_value, actual_value) {
-                (Some(expected), Some(actual)) => {
-                    (expected == actual, expected.to_string(), actual.to_string())
-                }
-                (Some(expected), None) => {
-                    (false, expected.to_string(), "header not found".to_string())
-                }
-                (None, Some(actual)) => {
-                    (true, "exists".to_string(), actual.to_string())
-                }
-                (None, None) => {
-                    (false, "exists".to_string(), "header not found".to_string())
-                }
-            };
-            
-            AssertionResult {
-                name: format!("Header '{}'", header_name),
-                passed,
-                expected: expected_str,
-                actual: actual_str,
-            }
+        AssertionType::JsonSchema => {
+            return evaluate_json_schema(assertion.json_schema.as_ref().or(response_schema), response_body);
         }
-    }
+    };
+    vec![result]
 }
 
 // ============ Variable Extraction ============
@@ -388,34 +741,14 @@ fn substitute_variables(text: &str, variables: &HashMap<String, String>) -> Stri
 // ============ Request Execution ============
 
 async fn execute_single_request(
+    client: &reqwest::Client,
+    settings: &HttpClientSettings,
+    cookie_jar: &mut CookieJar,
     request: &TestRequest,
     run_context: &HashMap<String, String>,
+    retry: Option<&RetryPolicy>,
+    authorization: Result<Option<String>, String>,
 ) -> TestResult {
-    let client = match reqwest::Client::builder()
-        .danger_accept_invalid_certs(true)
-        .timeout(std::time::Duration::from_secs(30))
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            return TestResult {
-                request_id: request.id.clone(),
-                request_name: request.name.clone(),
-                method: request.method.clone(),
-                url: request.url.clone(),
-                status: TestStatus::Error,
-                response_status: None,
-                response_time: None,
-                response_size: None,
-                response_body: None,
-                response_headers: None,
-                error: Some(format!("Failed to create HTTP client: {}", e)),
-                assertions: vec![],
-                extracted_variables: None,
-            };
-        }
-    };
-
     // Substitute variables in URL
     let mut request_url = substitute_variables(&request.url, run_context);
     
@@ -447,35 +780,39 @@ async fn execute_single_request(
     }
 
     // Build request
-    let mut req = match request.method.to_uppercase().as_str() {
-        "GET" => client.get(&request_url),
-        "POST" => client.post(&request_url),
-        "PUT" => client.put(&request_url),
-        "PATCH" => client.patch(&request_url),
-        "DELETE" => client.delete(&request_url),
-        "HEAD" => client.head(&request_url),
-        "OPTIONS" => client.request(reqwest::Method::OPTIONS, &request_url),
-        _ => {
+    let method = authorization.and_then(|a| http_client::parse_method(&request.method).map(|m| (m, a)));
+    let (mut req, authorization) = match method {
+        Ok((method, authorization)) => (client.request(method, &request_url), authorization),
+        Err(e) => {
             return TestResult {
                 request_id: request.id.clone(),
                 request_name: request.name.clone(),
                 method: request.method.clone(),
                 url: request.url.clone(),
                 status: TestStatus::Error,
+                request_headers: None,
+                request_body: None,
                 response_status: None,
                 response_time: None,
                 response_size: None,
                 response_body: None,
                 response_headers: None,
-                error: Some(format!("Unsupported method: {}", request.method)),
+                error: Some(e),
                 assertions: vec![],
                 extracted_variables: None,
+                timing: None,
+                attempts: Vec::new(),
+                iteration: None,
             };
         }
     };
 
     // Add headers (with variable substitution)
     for header in &request.headers {
+        // The auth profile's token replaces any Authorization header
+        if authorization.is_some() && header.key.eq_ignore_ascii_case("authorization") {
+            continue;
+        }
         if header.enabled && !header.key.is_empty() {
             let key = substitute_variables(&header.key, run_context);
             let value = substitute_variables(&header.value, run_context);
@@ -483,6 +820,13 @@ async fn execute_single_request(
         }
     }
 
+    if let Some(authorization) = &authorization {
+        req = req.header(reqwest::header::AUTHORIZATION, authorization);
+    }
+
+    // Test runs never wait longer than 30 seconds per request
+    req = req.timeout(std::time::Duration::from_secs(30));
+
     // Add body (with variable substitution)
     if let Some(body_content) = &request.body {
         if !body_content.is_empty() {
@@ -510,10 +854,40 @@ async fn execute_single_request(
         }
     }
 
+    // Connection phases (DNS, connect, TLS) can only be asserted on a dedicated connection
+    let detailed = request.detailed_timing.unwrap_or(false)
+        || request.assertions.iter().flatten().any(|a| {
+            a.enabled
+                && matches!(a.assertion_type, AssertionType::Timing)
+                && a.timing_phase.is_some_and(|p| p.needs_detailed_timing())
+        });
+
     // Execute request
     let start = Instant::now();
-    let response = match req.send().await {
-        Ok(r) => r,
+    let redirects = request.redirects.clone().unwrap_or_default();
+    let retry = request.retry.as_ref().or(retry);
+    let auth = request.auth.clone().map(|a| a.resolve(|s| substitute_variables(s, run_context)));
+    let mut request_headers = None;
+    let mut request_body = None;
+    let (sent, attempts) = match req.build() {
+        Ok(built) => {
+            request_headers = Some(
+                built
+                    .headers()
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
+                    .collect(),
+            );
+            request_body = built
+                .body()
+                .and_then(|b| b.as_bytes())
+                .map(|b| String::from_utf8_lossy(b).to_string());
+            auth::send(settings, built, detailed, &redirects, cookie_jar, None, retry, auth.as_ref()).await
+        }
+        Err(e) => (Err(e.to_string()), Vec::new()),
+    };
+    let response = match sent {
+        Ok((r, _)) => r,
         Err(e) => {
             return TestResult {
                 request_id: request.id.clone(),
@@ -521,44 +895,50 @@ async fn execute_single_request(
                 method: request.method.clone(),
                 url: request_url,
                 status: TestStatus::Error,
+                request_headers,
+                request_body,
                 response_status: None,
                 response_time: Some(start.elapsed().as_millis() as u64),
                 response_size: None,
                 response_body: None,
                 response_headers: None,
-                error: Some(e.to_string()),
+                error: Some(e),
                 assertions: vec![],
                 extracted_variables: None,
+                timing: None,
+                attempts,
+                iteration: None,
             };
         }
     };
 
-    let elapsed = start.elapsed().as_millis() as u64;
-    let status_code = response.status().as_u16();
+    let elapsed = response.timing.total_ms.round() as u64;
+    let status_code = response.status.as_u16();
     
     // Collect response headers
     let response_headers: HashMap<String, String> = response
-        .headers()
+        .headers
         .iter()
         .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
         .collect();
 
     // Get body
-    let body_bytes = response.bytes().await.unwrap_or_default();
-    let size = body_bytes.len();
-    let body_str = String::from_utf8_lossy(&body_bytes).to_string();
+    let size = response.body.len();
+    let body_str = String::from_utf8_lossy(&response.body).to_string();
 
     // Evaluate assertions
     let mut assertions = vec![];
     
     if let Some(configured_assertions) = &request.assertions {
         for assertion in configured_assertions.iter().filter(|a| a.enabled) {
-            assertions.push(evaluate_assertion(
+            assertions.extend(evaluate_assertion(
                 assertion,
                 status_code,
-                elapsed,
+                &response.timing,
                 &body_str,
+                size,
                 &response_headers,
+                request.response_schema.as_ref(),
             ));
         }
     }
@@ -599,6 +979,8 @@ async fn execute_single_request(
         method: request.method.clone(),
         url: request_url,
         status: test_status,
+        request_headers,
+        request_body,
         response_status: Some(status_code),
         response_time: Some(elapsed),
         response_size: Some(size),
@@ -607,75 +989,169 @@ async fn execute_single_request(
         error: None,
         assertions,
         extracted_variables,
+        timing: Some(response.timing),
+        attempts,
+        iteration: None,
     }
 }
 
+/// Which earlier requests each request waits for, from the variables it uses
+fn request_dependencies(requests: &[TestRequest]) -> Vec<Vec<usize>> {
+    let texts: Vec<String> = requests
+        .iter()
+        .map(|request| {
+            // Everything substitute_variables is applied to
+            let mut text = format!("{} {}", request.url, request.body.as_deref().unwrap_or(""));
+            for kv in request.headers.iter().chain(&request.params) {
+                text.push_str(&format!(" {} {}", kv.key, kv.value));
+            }
+            if let Some(auth) = &request.auth {
+                text.push_str(&serde_json::to_string(auth).unwrap_or_default());
+            }
+            text
+        })
+        .collect();
+    let extracts: Vec<Vec<String>> = requests
+        .iter()
+        .map(|request| {
+            request
+                .extract_variables
+                .iter()
+                .flatten()
+                .filter(|e| e.enabled)
+                .map(|e| e.variable_name.clone())
+                .collect()
+        })
+        .collect();
+    run_plan::dependencies(&texts, &extracts)
+}
+
 #[tauri::command]
 pub async fn run_collection_tests(
     app: AppHandle,
     config: TestRunConfig,
 ) -> Result<TestRunSummary, String> {
     let run_id = config.id.clone();
-    let total = config.requests.len();
+    // Without iteration data the requests run once with an empty context
+    let rows: Vec<Option<&HashMap<String, String>>> = if config.iteration_data.is_empty() {
+        vec![None]
+    } else {
+        config.iteration_data.iter().map(Some).collect()
+    };
+    let per_iteration = config.requests.len();
+    let total = per_iteration * rows.len();
+    let dependencies = request_dependencies(&config.requests);
+    let concurrency = config.concurrency.unwrap_or(1);
     let mut results: Vec<TestResult> = Vec::new();
+    let mut iterations: Vec<IterationSummary> = Vec::new();
     let mut passed = 0;
     let mut failed = 0;
     let mut errors = 0;
+    let mut completed = 0;
     let start_time = Instant::now();
-    
-    // Run context for variable extraction/substitution
-    let mut run_context: HashMap<String, String> = HashMap::new();
-
-    for (index, request) in config.requests.iter().enumerate() {
-        // Execute the request with current context
-        let result = execute_single_request(request, &run_context).await;
-
-        // Extract variables and add to context for subsequent requests
-        if let Some(extracted) = &result.extracted_variables {
-            for var in extracted {
-                if var.success {
-                    run_context.insert(var.variable_name.clone(), var.value.clone());
+
+    // All requests in the run share one pooled client and the workspace cookie jar
+    let storage = app.state::<Arc<Storage>>();
+    let storage: &Storage = &storage;
+    let workspace_id = storage.get_active_workspace_id().ok().flatten();
+    let settings = http_client::workspace_settings(storage, workspace_id.as_deref());
+    let client = http_client::get_client(&settings)?;
+    let mut cookie_jar = CookieJar::load(storage, workspace_id.as_deref());
+
+    // The whole run can be cancelled with cancel_request(run_id)
+    let cancellation = cancel::Registration::new(&run_id);
+
+    let mut scheduler = Scheduler::new(dependencies, concurrency, config.delay_between_requests);
+
+    for (iteration_index, row) in rows.iter().enumerate() {
+        // Run context for variable substitution: the row, plus what the
+        // requests a request depends on extracted
+        scheduler.begin(row.cloned().unwrap_or_default());
+        let iteration = row.map(|_| iteration_index + 1);
+        let mut iteration_summary = row.map(|data| IterationSummary::new(iteration_index + 1, data.clone(), per_iteration));
+        let iteration_start = Instant::now();
+        let mut iteration_results: Vec<Option<TestResult>> = vec![None; per_iteration];
+
+        loop {
+            // Concurrent requests each use a fork of the jar, merged back when they finish
+            let next = scheduler.next(&cancellation, |index, context| {
+                let request = &config.requests[index];
+                let mut jar = cookie_jar.fork();
+                let (client, settings, workspace_id) = (&client, &settings, workspace_id.as_deref());
+                let retry = config.retry.as_ref();
+                let profile_id = request.auth_profile_id.as_deref().or(config.auth_profile_id.as_deref());
+                async move {
+                    let authorization = oauth::authorization_for(storage, workspace_id, profile_id).await;
+                    let result = execute_single_request(client, settings, &mut jar, request, &context, retry, authorization).await;
+                    (result, jar)
                 }
-            }
-        }
+            });
+            let Some((index, (mut result, jar))) = next.await else {
+                break;
+            };
+            let set_cookies = jar.changed();
+            cookie_jar.merge(jar);
+            result.iteration = iteration;
+            completed += 1;
 
-        // Update counters
-        match result.status {
-            TestStatus::Passed => passed += 1,
-            TestStatus::Failed => failed += 1,
-            TestStatus::Error => errors += 1,
-            _ => {}
-        }
+            // Variables extracted for the requests depending on this one
+            let extracted = result
+                .extracted_variables
+                .iter()
+                .flatten()
+                .filter(|var| var.success)
+                .map(|var| (var.variable_name.clone(), var.value.clone()))
+                .collect();
+            scheduler.finish(index, extracted, set_cookies);
 
-        // Emit progress event
-        let progress = TestProgressEvent {
-            run_id: run_id.clone(),
-            current: index + 1,
-            total,
-            result: result.clone(),
-        };
-        let _ = app.emit("test-progress", &progress);
+            // Update counters
+            match result.status {
+                TestStatus::Passed => passed += 1,
+                TestStatus::Failed => failed += 1,
+                TestStatus::Error => errors += 1,
+                _ => {}
+            }
+            if let Some(summary) = iteration_summary.as_mut() {
+                match result.status {
+                    TestStatus::Passed => summary.passed += 1,
+                    TestStatus::Failed => summary.failed += 1,
+                    TestStatus::Error => summary.errors += 1,
+                    _ => {}
+                }
+                summary.total_time = iteration_start.elapsed().as_millis() as u64;
+            }
+
+            // Emit progress event
+            let progress = TestProgressEvent {
+                run_id: run_id.clone(),
+                current: completed,
+                total,
+                result: result.clone(),
+            };
+            let _ = app.emit("test-progress", &progress);
 
-        results.push(result.clone());
+            // Stop on failure if configured (a failed row doesn't stop the next ones)
+            if config.stop_on_failure
+                && (result.status == TestStatus::Failed || result.status == TestStatus::Error)
+            {
+                scheduler.stop();
+            }
 
-        // Stop on failure if configured
-        if config.stop_on_failure
-            && (result.status == TestStatus::Failed || result.status == TestStatus::Error)
-        {
-            break;
+            iteration_results[index] = Some(result);
         }
 
-        // Delay between requests
-        if config.delay_between_requests > 0 && index < total - 1 {
-            tokio::time::sleep(std::time::Duration::from_millis(
-                config.delay_between_requests,
-            ))
-            .await;
+        // Results in request order, whatever order they finished in
+        results.extend(iteration_results.into_iter().flatten());
+        iterations.extend(iteration_summary);
+        if cancellation.is_cancelled() {
+            break;
         }
     }
 
     let total_time = start_time.elapsed().as_millis() as u64;
 
+    cookie_jar.save(storage, workspace_id.as_deref())?;
+
     let summary = TestRunSummary {
         run_id,
         name: config.name,
@@ -685,6 +1161,8 @@ pub async fn run_collection_tests(
         errors,
         total_time,
         results,
+        cancelled: cancellation.is_cancelled(),
+        iterations,
     };
 
     // Emit completion event
@@ -699,6 +1177,8 @@ pub fn create_test_config(
     requests: Vec<TestRequest>,
     stop_on_failure: bool,
     delay_between_requests: u64,
+    retry: Option<RetryPolicy>,
+    auth_profile_id: Option<String>,
 ) -> TestRunConfig {
     TestRunConfig {
         id: generate_id(),
@@ -706,6 +1186,10 @@ pub fn create_test_config(
         requests,
         stop_on_failure,
         delay_between_requests,
+        retry,
+        auth_profile_id,
+        iteration_data: Vec::new(),
+        concurrency: None,
     }
 }
 
@@ -715,3 +1199,65 @@ pub fn create_test_config(
 pub fn evaluate_jsonpath_test(json_str: String, path: String) -> Result<String, String> {
     evaluate_jsonpath(&json_str, &path).map(|v| value_to_string(&v))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn applies_assertion_operators() {
+        use JsonPathOperator::*;
+        let check = |operator: JsonPathOperator, actual: Value, expected: &str| apply_operator(&operator, &actual, expected);
+
+        assert_eq!(check(Matches, json!("user-42"), r"^user-\d+$"), Ok(true));
+        assert_eq!(check(Gt, json!(10), "9.5"), Ok(true));
+        assert_eq!(check(Lte, json!("120"), "100"), Ok(false));
+        assert_eq!(check(IsType, json!(3.0), "integer"), Ok(true));
+        assert_eq!(check(IsType, json!([]), "object"), Ok(false));
+        assert_eq!(check(LengthEquals, json!([1, 2, 3]), "3"), Ok(true));
+        assert_eq!(check(LengthGt, json!("abc"), "3"), Ok(false));
+        assert_eq!(check(In, json!(["admin", "user"]), "admin"), Ok(true));
+        assert_eq!(check(OneOf, json!(201), "200, 201"), Ok(true));
+        assert_eq!(check(OneOf, json!("b"), r#"["a", "c"]"#), Ok(false));
+        assert!(check(Gt, json!(1), "many").is_err());
+
+        assert_eq!(operator_result(&Lt, &json!(2048), "1024"), Ok((false, "2048".to_string())));
+        assert!(operator_result(&Gt, &json!(1), "many").is_err());
+
+        let result = body_size_result(None, Some("1024"), 2048);
+        assert!(!result.passed);
+        assert_eq!((result.expected.as_str(), result.actual.as_str()), ("<= 1024", "2048 bytes"));
+        assert!(!body_size_result(Some(&Gt), None, 10).passed);
+    }
+
+    #[test]
+    fn reports_each_schema_violation() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+                "id": { "type": "integer" },
+                "name": { "type": "string" },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            }
+        });
+        let results = evaluate_json_schema(Some(&schema), r#"{"id": "7", "tags": ["a", 2]}"#);
+        let mut names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["JSON Schema (root)", "JSON Schema /id", "JSON Schema /tags/1"]);
+        assert!(results.iter().all(|r| !r.passed));
+
+        let results = evaluate_json_schema(Some(&schema), r#"{"id": 7, "name": "Ada"}"#);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].passed);
+        assert!(!evaluate_json_schema(None, "{}")[0].passed);
+    }
+
+    #[test]
+    fn accepts_openapi_nullable() {
+        let schema = json!({ "type": "object", "properties": { "email": { "type": "string", "nullable": true } } });
+        assert!(evaluate_json_schema(Some(&schema), r#"{"email": null}"#)[0].passed);
+        assert!(!evaluate_json_schema(Some(&schema), r#"{"email": 1}"#)[0].passed);
+    }
+}
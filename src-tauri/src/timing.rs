@@ -0,0 +1,491 @@
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::TlsConnector;
+use utoipa::ToSchema;
+
+use crate::http_client::{self, HttpClientSettings};
+use crate::tls;
+
+/// Phase-by-phase timing of a single HTTP request (all durations in milliseconds)
+///
+/// `dns_ms`, `connect_ms` and `tls_ms` are only measured when the request is sent
+/// over a dedicated connection (detailed timing). Requests that go through the
+/// shared connection pool leave them empty and their `ttfb_ms` includes any
+/// connection setup.
+///
+/// `tls_version` and `tls_cipher` are likewise only known for a dedicated
+/// connection. The pool doesn't expose the handshake of the (possibly reused)
+/// connection a request went over, so pooled requests never report them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseTiming {
+    pub dns_ms: Option<f64>,
+    pub connect_ms: Option<f64>,
+    pub tls_ms: Option<f64>,
+    /// Request sent until response headers received
+    pub ttfb_ms: f64,
+    /// Response headers received until the body was fully read
+    pub download_ms: f64,
+    pub total_ms: f64,
+    pub remote_ip: Option<String>,
+    pub http_version: String,
+    pub tls_version: Option<String>,
+    pub tls_cipher: Option<String>,
+}
+
+/// Which phase of `ResponseTiming` an assertion or report refers to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TimingPhase {
+    Dns,
+    Connect,
+    Tls,
+    Ttfb,
+    Download,
+    Total,
+}
+
+impl TimingPhase {
+    /// Phases that can only be measured on a dedicated connection
+    pub fn needs_detailed_timing(&self) -> bool {
+        matches!(self, TimingPhase::Dns | TimingPhase::Connect | TimingPhase::Tls)
+    }
+}
+
+impl ResponseTiming {
+    pub fn phase_ms(&self, phase: TimingPhase) -> Option<f64> {
+        match phase {
+            TimingPhase::Dns => self.dns_ms,
+            TimingPhase::Connect => self.connect_ms,
+            TimingPhase::Tls => self.tls_ms,
+            TimingPhase::Ttfb => Some(self.ttfb_ms),
+            TimingPhase::Download => Some(self.download_ms),
+            TimingPhase::Total => Some(self.total_ms),
+        }
+    }
+}
+
+/// Why sending a request failed
+#[derive(Debug)]
+pub struct SendError {
+    pub message: String,
+    /// No response arrived because connecting (DNS, TCP, TLS) failed or the
+    /// request timed out, so sending it again may succeed
+    pub connection: bool,
+}
+
+impl SendError {
+    fn connection(message: String) -> Self {
+        Self { message, connection: true }
+    }
+}
+
+impl From<String> for SendError {
+    fn from(message: String) -> Self {
+        Self { message, connection: false }
+    }
+}
+
+impl From<&str> for SendError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<reqwest::Error> for SendError {
+    fn from(e: reqwest::Error) -> Self {
+        Self { connection: e.is_connect() || e.is_timeout(), message: e.to_string() }
+    }
+}
+
+/// A fully read response together with its timing
+pub struct TimedResponse {
+    pub status: reqwest::StatusCode,
+    pub headers: reqwest::header::HeaderMap,
+    /// Empty when the body was saved to a file
+    pub body: Vec<u8>,
+    /// Body size in bytes
+    pub size: u64,
+    pub timing: ResponseTiming,
+}
+
+/// Send a request, measuring every phase when `detailed` is set.
+///
+/// Detailed timing needs a fresh connection, so it bypasses the shared pool.
+/// Requests with streaming bodies (multipart uploads) and requests routed
+/// through a proxy always use the pool, with the client for the request's
+/// host (see `http_client::client_for_url`).
+///
+/// With `save_to`, the body is streamed into that file (created or truncated)
+/// instead of being held in memory.
+pub async fn send(
+    settings: &HttpClientSettings,
+    request: reqwest::Request,
+    detailed: bool,
+    save_to: Option<&Path>,
+) -> Result<TimedResponse, SendError> {
+    let buffered = request.body().is_none_or(|b| b.as_bytes().is_some());
+    let proxied = settings.proxy.proxy_for(request.url()).is_some();
+    if detailed && buffered && !proxied {
+        send_instrumented(settings, request, save_to).await
+    } else {
+        let client = http_client::client_for_url(settings, request.url()).await?;
+        send_pooled(&client, request, save_to).await
+    }
+}
+
+/// Send through the shared client (connection phases and the negotiated TLS
+/// version and cipher are not observable here)
+pub async fn send_pooled(
+    client: &reqwest::Client,
+    request: reqwest::Request,
+    save_to: Option<&Path>,
+) -> Result<TimedResponse, SendError> {
+    let start = Instant::now();
+    let mut response = client.execute(request).await?;
+    let ttfb = start.elapsed();
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let remote_ip = response.remote_addr().map(|addr| addr.ip().to_string());
+    let http_version = format!("{:?}", response.version());
+
+    let (body, size) = match save_to {
+        Some(path) => {
+            let mut file = BodyFile::create(path).await?;
+            while let Some(chunk) = response.chunk().await? {
+                file.write(&chunk).await?;
+            }
+            (Vec::new(), file.finish().await?)
+        }
+        None => {
+            let body = response.bytes().await?.to_vec();
+            let size = body.len() as u64;
+            (body, size)
+        }
+    };
+    let total = start.elapsed();
+
+    Ok(TimedResponse {
+        status,
+        headers,
+        body,
+        size,
+        timing: ResponseTiming {
+            ttfb_ms: millis(ttfb),
+            download_ms: millis(total - ttfb),
+            total_ms: millis(total),
+            remote_ip,
+            http_version,
+            ..Default::default()
+        },
+    })
+}
+
+/// Send over a dedicated connection, timing DNS, TCP connect, TLS handshake,
+/// time to first byte and download separately
+pub async fn send_instrumented(
+    settings: &HttpClientSettings,
+    request: reqwest::Request,
+    save_to: Option<&Path>,
+) -> Result<TimedResponse, SendError> {
+    let timeout = request
+        .timeout()
+        .copied()
+        .or_else(|| (settings.timeout_ms > 0).then(|| Duration::from_millis(settings.timeout_ms)));
+
+    match timeout {
+        Some(timeout) => tokio::time::timeout(timeout, instrumented_exchange(settings, request, save_to))
+            .await
+            .map_err(|_| SendError::connection(format!("Request timed out after {}ms", timeout.as_millis())))?,
+        None => instrumented_exchange(settings, request, save_to).await,
+    }
+}
+
+async fn instrumented_exchange(
+    settings: &HttpClientSettings,
+    request: reqwest::Request,
+    save_to: Option<&Path>,
+) -> Result<TimedResponse, SendError> {
+    let url = request.url().clone();
+    let https = match url.scheme() {
+        "https" => true,
+        "http" => false,
+        other => return Err(format!("Unsupported URL scheme: {}", other).into()),
+    };
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| format!("No port for URL: {}", url))?;
+
+    let start = Instant::now();
+
+    // DNS
+    let (addrs, server_name, dns_ms) = match url.host() {
+        Some(url::Host::Domain(domain)) => {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, port))
+                .await
+                .map_err(|e| SendError::connection(format!("DNS lookup failed for {}: {}", domain, e)))?
+                .collect();
+            let server_name = ServerName::try_from(domain.to_string())
+                .map_err(|e| format!("Invalid host name {}: {}", domain, e))?;
+            (addrs, server_name, Some(millis(start.elapsed())))
+        }
+        Some(url::Host::Ipv4(ip)) => {
+            (vec![SocketAddr::new(ip.into(), port)], ServerName::from(std::net::IpAddr::from(ip)), None)
+        }
+        Some(url::Host::Ipv6(ip)) => {
+            (vec![SocketAddr::new(ip.into(), port)], ServerName::from(std::net::IpAddr::from(ip)), None)
+        }
+        None => return Err(format!("URL has no host: {}", url).into()),
+    };
+
+    // TCP connect
+    let connect_start = Instant::now();
+    let (tcp, remote_addr) = connect_tcp(&addrs, settings.connect_timeout_ms)
+        .await
+        .map_err(SendError::connection)?;
+    let _ = tcp.set_nodelay(true);
+    let connect_ms = millis(connect_start.elapsed());
+
+    let body = match request.body() {
+        Some(body) => body
+            .as_bytes()
+            .ok_or("Streaming request bodies cannot be timed")?
+            .to_vec(),
+        None => Vec::new(),
+    };
+
+    // TLS handshake, then the HTTP exchange itself
+    let mut tls_ms = None;
+    let mut tls_version = None;
+    let mut tls_cipher = None;
+    let (response, request_sent) = if https {
+        let identity = settings.tls.identity_for(&url).await?;
+        let tls_start = Instant::now();
+        let config = tls::rustls_config(
+            settings.accept_invalid_certs,
+            &settings.tls,
+            identity.as_deref(),
+            &[b"h2", b"http/1.1"],
+        )?;
+        let connector = TlsConnector::from(config);
+        let stream = connector
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| SendError::connection(format!("TLS handshake failed: {}", e)))?;
+        tls_ms = Some(millis(tls_start.elapsed()));
+
+        let (_, session) = stream.get_ref();
+        tls_version = session.protocol_version().map(format_tls_version);
+        tls_cipher = session
+            .negotiated_cipher_suite()
+            .map(|suite| format!("{:?}", suite.suite()));
+        let h2 = session.alpn_protocol() == Some(b"h2".as_slice());
+
+        let hyper_request = to_hyper_request(&request, body, h2)?;
+        let request_sent = Instant::now();
+        (exchange(stream, h2, hyper_request).await?, request_sent)
+    } else {
+        let hyper_request = to_hyper_request(&request, body, false)?;
+        let request_sent = Instant::now();
+        (exchange(tcp, false, hyper_request).await?, request_sent)
+    };
+    let ttfb = request_sent.elapsed();
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let http_version = format!("{:?}", response.version());
+
+    let download_start = Instant::now();
+    let mut incoming = response.into_body();
+    let (body, size) = match save_to {
+        Some(path) => {
+            let mut file = BodyFile::create(path).await?;
+            while let Some(frame) = incoming.frame().await {
+                let frame = frame.map_err(|e| format!("Failed to read response body: {}", e))?;
+                if let Some(chunk) = frame.data_ref() {
+                    file.write(chunk).await?;
+                }
+            }
+            (Vec::new(), file.finish().await?)
+        }
+        None => {
+            let body = incoming
+                .collect()
+                .await
+                .map_err(|e| format!("Failed to read response body: {}", e))?
+                .to_bytes()
+                .to_vec();
+            let size = body.len() as u64;
+            (body, size)
+        }
+    };
+
+    Ok(TimedResponse {
+        status,
+        headers,
+        body,
+        size,
+        timing: ResponseTiming {
+            dns_ms,
+            connect_ms: Some(connect_ms),
+            tls_ms,
+            ttfb_ms: millis(ttfb),
+            download_ms: millis(download_start.elapsed()),
+            total_ms: millis(start.elapsed()),
+            remote_ip: Some(remote_addr.ip().to_string()),
+            http_version,
+            tls_version,
+            tls_cipher,
+        },
+    })
+}
+
+/// A response body being written to disk
+struct BodyFile<'a> {
+    path: &'a Path,
+    file: tokio::io::BufWriter<tokio::fs::File>,
+    size: u64,
+}
+
+impl<'a> BodyFile<'a> {
+    async fn create(path: &'a Path) -> Result<Self, String> {
+        let file = tokio::fs::File::create(path)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        Ok(Self { path, file: tokio::io::BufWriter::new(file), size: 0 })
+    }
+
+    async fn write(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.file
+            .write_all(chunk)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))?;
+        self.size += chunk.len() as u64;
+        Ok(())
+    }
+
+    async fn finish(mut self) -> Result<u64, String> {
+        self.file
+            .flush()
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", self.path.display(), e))?;
+        Ok(self.size)
+    }
+}
+
+/// Try each resolved address in turn until one accepts the connection
+async fn connect_tcp(addrs: &[SocketAddr], connect_timeout_ms: u64) -> Result<(TcpStream, SocketAddr), String> {
+    let mut last_error = "no addresses resolved".to_string();
+
+    for addr in addrs {
+        let attempt = TcpStream::connect(addr);
+        let result = if connect_timeout_ms > 0 {
+            match tokio::time::timeout(Duration::from_millis(connect_timeout_ms), attempt).await {
+                Ok(result) => result,
+                Err(_) => {
+                    last_error = format!("connection to {} timed out", addr);
+                    continue;
+                }
+            }
+        } else {
+            attempt.await
+        };
+
+        match result {
+            Ok(stream) => return Ok((stream, *addr)),
+            Err(e) => last_error = format!("{}: {}", addr, e),
+        }
+    }
+
+    Err(format!("Failed to connect: {}", last_error))
+}
+
+async fn exchange<T>(
+    io: T,
+    h2: bool,
+    request: hyper::Request<Full<Bytes>>,
+) -> Result<hyper::Response<hyper::body::Incoming>, String>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let io = TokioIo::new(io);
+
+    if h2 {
+        let (mut sender, connection) = hyper::client::conn::http2::handshake(TokioExecutor::new(), io)
+            .await
+            .map_err(|e| format!("HTTP/2 handshake failed: {}", e))?;
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+        sender.send_request(request).await.map_err(|e| e.to_string())
+    } else {
+        let (mut sender, connection) = hyper::client::conn::http1::handshake(io)
+            .await
+            .map_err(|e| format!("HTTP/1.1 handshake failed: {}", e))?;
+        tokio::spawn(async move {
+            let _ = connection.await;
+        });
+        sender.send_request(request).await.map_err(|e| e.to_string())
+    }
+}
+
+/// Convert a built reqwest request into a hyper request for a raw connection
+fn to_hyper_request(
+    request: &reqwest::Request,
+    body: Vec<u8>,
+    h2: bool,
+) -> Result<hyper::Request<Full<Bytes>>, String> {
+    let url = request.url();
+
+    // HTTP/2 carries the authority in the URI, HTTP/1.1 in the Host header
+    let uri = if h2 {
+        url.as_str().to_string()
+    } else {
+        url[url::Position::BeforePath..url::Position::AfterQuery].to_string()
+    };
+
+    let mut builder = hyper::Request::builder()
+        .method(request.method().clone())
+        .uri(uri);
+
+    let headers = request.headers();
+    if !h2 && !headers.contains_key(reqwest::header::HOST) {
+        builder = builder.header(
+            reqwest::header::HOST,
+            &url[url::Position::BeforeHost..url::Position::AfterPort],
+        );
+    }
+    // Match what reqwest sends by default
+    if !headers.contains_key(reqwest::header::ACCEPT) {
+        builder = builder.header(reqwest::header::ACCEPT, "*/*");
+    }
+    for (key, value) in headers {
+        builder = builder.header(key, value);
+    }
+
+    builder
+        .body(Full::new(Bytes::from(body)))
+        .map_err(|e| format!("Failed to build request: {}", e))
+}
+
+fn format_tls_version(version: rustls::ProtocolVersion) -> String {
+    match version {
+        rustls::ProtocolVersion::TLSv1_2 => "TLSv1.2".to_string(),
+        rustls::ProtocolVersion::TLSv1_3 => "TLSv1.3".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,388 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio_rustls::rustls;
+use tokio_rustls::rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use url::Url;
+
+use crate::secret_providers::{self, SecretReference};
+use crate::storage::SecretProvider;
+
+// Loaded client identities, keyed by the certificate config they were loaded from,
+// so passphrases are fetched from secret providers once rather than per connection
+static IDENTITIES: Lazy<DashMap<String, Arc<ClientIdentity>>> = Lazy::new(DashMap::new);
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CertificateFormat {
+    #[default]
+    Pem,
+    Pkcs12,
+}
+
+/// A client certificate presented to hosts matching `host`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientCertificate {
+    pub id: String,
+    /// `api.example.com`, `*.example.com` or `*`, optionally with a `:port`
+    pub host: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub format: CertificateFormat,
+    /// PEM certificate chain, or the PKCS#12 (.p12/.pfx) archive
+    pub cert_path: String,
+    /// PEM private key (defaults to `cert_path` for combined PEM files)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_path: Option<String>,
+    /// PKCS#12 passphrase, kept in the git-ignored tls.local.yaml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passphrase: Option<String>,
+    /// PKCS#12 passphrase fetched from a secret provider instead
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub passphrase_secret: Option<SecretReference>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Per-workspace TLS configuration (stored in workspace.yaml).
+///
+/// Certificate verification itself is controlled by `HttpClientSettings::accept_invalid_certs`;
+/// turning it off (strict mode) verifies against the system roots plus `ca_certificates`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsSettings {
+    /// PEM files with additional trusted CA certificates
+    #[serde(default)]
+    pub ca_certificates: Vec<String>,
+    #[serde(default)]
+    pub client_certificates: Vec<ClientCertificate>,
+    /// Secret providers for passphrase lookups (attached on load, not stored here)
+    #[serde(skip)]
+    pub secret_providers: Vec<SecretProvider>,
+}
+
+impl TlsSettings {
+    /// The first enabled client certificate whose host pattern matches `url`
+    pub fn certificate_for(&self, url: &Url) -> Option<&ClientCertificate> {
+        let host = url.host_str()?.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+        let port = url.port_or_known_default();
+        self.client_certificates
+            .iter()
+            .find(|c| c.enabled && host_matches(&c.host, &host, port))
+    }
+
+    /// Whether anything beyond the system defaults is configured for `url`
+    pub fn is_customized_for(&self, url: &Url) -> bool {
+        !self.ca_certificates.is_empty() || self.certificate_for(url).is_some()
+    }
+
+    /// The extra CA certificates as one PEM bundle
+    pub fn ca_bundle_pem(&self) -> Result<Vec<u8>, String> {
+        let mut bundle = Vec::new();
+        for path in &self.ca_certificates {
+            let pem = std::fs::read(path).map_err(|e| format!("Failed to read CA certificate {}: {}", path, e))?;
+            bundle.extend_from_slice(&pem);
+            bundle.push(b'\n');
+        }
+        Ok(bundle)
+    }
+
+    /// The system roots plus the extra CA certificates as one PEM bundle (MQTT)
+    pub fn root_bundle_pem(&self) -> Result<Vec<u8>, String> {
+        let mut bundle: Vec<u8> = rustls_native_certs::load_native_certs()
+            .certs
+            .iter()
+            .flat_map(|c| pem_encode("CERTIFICATE", c.as_ref()).into_bytes())
+            .collect();
+        bundle.extend(self.ca_bundle_pem()?);
+        Ok(bundle)
+    }
+
+    /// The extra CA certificates
+    pub fn ca_certificates(&self) -> Result<Vec<CertificateDer<'static>>, String> {
+        let bundle = self.ca_bundle_pem()?;
+        CertificateDer::pem_slice_iter(&bundle)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid CA certificate: {}", e))
+    }
+
+    /// Load the client certificate for `url`, if one is configured
+    pub async fn identity_for(&self, url: &Url) -> Result<Option<Arc<ClientIdentity>>, String> {
+        match self.certificate_for(url) {
+            Some(certificate) => load_identity(certificate, &self.secret_providers).await.map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Match a host against a certificate host pattern
+fn host_matches(pattern: &str, host: &str, port: Option<u16>) -> bool {
+    let pattern = pattern.trim().to_lowercase();
+    let (pattern_host, pattern_port) = match pattern.rsplit_once(':') {
+        Some((h, p)) if !h.ends_with(':') && p.parse::<u16>().is_ok() => (h.to_string(), p.parse().ok()),
+        _ => (pattern.clone(), None),
+    };
+    let pattern_host = pattern_host.trim_start_matches('[').trim_end_matches(']');
+
+    if pattern_port.is_some() && pattern_port != port {
+        return false;
+    }
+    if pattern_host == "*" || pattern_host == host {
+        return true;
+    }
+    pattern_host
+        .strip_prefix("*.")
+        .is_some_and(|domain| host.strip_suffix(domain).is_some_and(|rest| rest.ends_with('.')))
+}
+
+/// A client certificate chain and its private key
+pub struct ClientIdentity {
+    pub cert_chain: Vec<CertificateDer<'static>>,
+    pub key: PrivateKeyDer<'static>,
+}
+
+impl ClientIdentity {
+    fn from_pem(cert_pem: &[u8], key_pem: &[u8]) -> Result<Self, String> {
+        let cert_chain = CertificateDer::pem_slice_iter(cert_pem)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid certificate: {}", e))?;
+        if cert_chain.is_empty() {
+            return Err("No certificate found in PEM file".to_string());
+        }
+        let key = PrivateKeyDer::from_pem_slice(key_pem).map_err(|e| format!("Invalid private key: {}", e))?;
+        Ok(Self { cert_chain, key })
+    }
+
+    fn from_pkcs12(der: &[u8], passphrase: &str) -> Result<Self, String> {
+        let keystore = p12_keystore::KeyStore::from_pkcs12(der, passphrase)
+            .map_err(|e| format!("Failed to open PKCS#12 archive: {}", e))?;
+        let (_, chain) = keystore
+            .private_key_chain()
+            .ok_or("PKCS#12 archive contains no private key")?;
+        Ok(Self {
+            cert_chain: chain
+                .chain()
+                .iter()
+                .map(|c| CertificateDer::from(c.as_der().to_vec()))
+                .collect(),
+            key: PrivateKeyDer::Pkcs8(chain.key().to_vec().into()),
+        })
+    }
+
+    /// The certificate chain as PEM
+    pub fn cert_chain_pem(&self) -> Vec<u8> {
+        self.cert_chain
+            .iter()
+            .map(|c| pem_encode("CERTIFICATE", c.as_ref()))
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    /// The private key as PEM, in its original encoding
+    pub fn key_pem(&self) -> Vec<u8> {
+        let (label, der) = match &self.key {
+            PrivateKeyDer::Pkcs1(key) => ("RSA PRIVATE KEY", key.secret_pkcs1_der()),
+            PrivateKeyDer::Sec1(key) => ("EC PRIVATE KEY", key.secret_sec1_der()),
+            PrivateKeyDer::Pkcs8(key) => ("PRIVATE KEY", key.secret_pkcs8_der()),
+            _ => ("PRIVATE KEY", &[][..]),
+        };
+        pem_encode(label, der).into_bytes()
+    }
+
+    /// native-tls only accepts PKCS#8 keys
+    fn require_pkcs8(&self) -> Result<(), String> {
+        match self.key {
+            PrivateKeyDer::Pkcs8(_) => Ok(()),
+            _ => Err("Client certificate keys must be PKCS#8 (convert with `openssl pkcs8 -topk8 -nocrypt`)".to_string()),
+        }
+    }
+
+    pub fn to_reqwest(&self) -> Result<reqwest::Identity, String> {
+        self.require_pkcs8()?;
+        reqwest::Identity::from_pkcs8_pem(&self.cert_chain_pem(), &self.key_pem())
+            .map_err(|e| format!("Invalid client certificate: {}", e))
+    }
+
+    pub fn to_native_tls(&self) -> Result<native_tls::Identity, String> {
+        self.require_pkcs8()?;
+        native_tls::Identity::from_pkcs8(&self.cert_chain_pem(), &self.key_pem())
+            .map_err(|e| format!("Invalid client certificate: {}", e))
+    }
+}
+
+fn pem_encode(label: &str, der: &[u8]) -> String {
+    let encoded = STANDARD.encode(der);
+    let mut pem = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {}-----\n", label));
+    pem
+}
+
+/// Read a client certificate from disk, resolving its passphrase if needed
+pub async fn load_identity(
+    certificate: &ClientCertificate,
+    providers: &[SecretProvider],
+) -> Result<Arc<ClientIdentity>, String> {
+    let key = serde_json::to_string(certificate).unwrap_or_default();
+    if let Some(identity) = IDENTITIES.get(&key) {
+        return Ok(identity.clone());
+    }
+
+    let read = |path: &str| {
+        std::fs::read(path).map_err(|e| format!("Failed to read client certificate {}: {}", path, e))
+    };
+    let identity = match certificate.format {
+        CertificateFormat::Pem => {
+            let cert = read(&certificate.cert_path)?;
+            let key = match certificate.key_path.as_deref().filter(|p| !p.is_empty()) {
+                Some(path) => read(path)?,
+                None => cert.clone(),
+            };
+            ClientIdentity::from_pem(&cert, &key)?
+        }
+        CertificateFormat::Pkcs12 => {
+            let passphrase = match (&certificate.passphrase_secret, &certificate.passphrase) {
+                (Some(reference), _) => secret_providers::resolve_secret(providers, reference).await?,
+                (None, Some(passphrase)) => passphrase.clone(),
+                (None, None) => String::new(),
+            };
+            ClientIdentity::from_pkcs12(&read(&certificate.cert_path)?, &passphrase)?
+        }
+    };
+
+    let identity = Arc::new(identity);
+    IDENTITIES.insert(key, identity.clone());
+    Ok(identity)
+}
+
+/// Forget loaded identities (e.g. after the certificate files or settings changed)
+pub fn clear_identities() {
+    IDENTITIES.clear();
+}
+
+/// rustls configuration for connections the app manages itself (detailed timing, gRPC)
+pub fn rustls_config(
+    accept_invalid_certs: bool,
+    tls: &TlsSettings,
+    identity: Option<&ClientIdentity>,
+    alpn: &[&[u8]],
+) -> Result<Arc<rustls::ClientConfig>, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let builder = rustls::ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Failed to configure TLS: {}", e))?;
+
+    let builder = if accept_invalid_certs {
+        builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyCertificate(provider)))
+    } else {
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+        roots.add_parsable_certificates(tls.ca_certificates()?);
+        builder.with_root_certificates(roots)
+    };
+
+    let mut config = match identity {
+        Some(identity) => builder
+            .with_client_auth_cert(identity.cert_chain.clone(), identity.key.clone_key())
+            .map_err(|e| format!("Invalid client certificate: {}", e))?,
+        None => builder.with_no_client_auth(),
+    };
+    config.alpn_protocols = alpn.iter().map(|p| p.to_vec()).collect();
+
+    Ok(Arc::new(config))
+}
+
+/// native-tls connector with the same settings (WebSocket)
+pub fn native_tls_connector(
+    accept_invalid_certs: bool,
+    tls: &TlsSettings,
+    identity: Option<&ClientIdentity>,
+) -> Result<native_tls::TlsConnector, String> {
+    let mut builder = native_tls::TlsConnector::builder();
+    builder.danger_accept_invalid_certs(accept_invalid_certs);
+    for cert in tls.ca_certificates()? {
+        let cert = native_tls::Certificate::from_der(cert.as_ref())
+            .map_err(|e| format!("Invalid CA certificate: {}", e))?;
+        builder.add_root_certificate(cert);
+    }
+    if let Some(identity) = identity {
+        builder.identity(identity.to_native_tls()?);
+    }
+    builder.build().map_err(|e| format!("Failed to configure TLS: {}", e))
+}
+
+/// Mirrors `danger_accept_invalid_certs` on the pooled client: the certificate
+/// chain is not checked, but handshake signatures still are
+#[derive(Debug)]
+struct AcceptAnyCertificate(Arc<rustls::crypto::CryptoProvider>);
+
+impl ServerCertVerifier for AcceptAnyCertificate {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_certificate_hosts() {
+        assert!(host_matches("api.example.com", "api.example.com", Some(443)));
+        assert!(host_matches("*.example.com", "api.example.com", Some(443)));
+        assert!(!host_matches("*.example.com", "example.com", Some(443)));
+        assert!(host_matches("api.example.com:8443", "api.example.com", Some(8443)));
+        assert!(!host_matches("api.example.com:8443", "api.example.com", Some(443)));
+        assert!(host_matches("*", "anything.internal", None));
+    }
+
+    #[test]
+    fn encodes_pem() {
+        let pem = pem_encode("CERTIFICATE", &[0u8; 60]);
+        assert!(pem.starts_with("-----BEGIN CERTIFICATE-----\n"));
+        assert_eq!(pem.lines().nth(1).map(str::len), Some(64));
+        assert!(CertificateDer::pem_slice_iter(pem.as_bytes()).next().unwrap().is_ok());
+    }
+}
@@ -3,9 +3,15 @@ use futures_util::{SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::Mutex;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::{client_async_tls_with_config, connect_async_tls_with_config, tungstenite::Message, Connector};
+use url::Url;
+
+use crate::http_client;
+use crate::proxy;
+use crate::storage::Storage;
+use crate::tls;
 
 pub struct WsConnection {
     pub sender: Arc<Mutex<futures_util::stream::SplitSink<
@@ -39,10 +45,29 @@ pub async fn ws_connect(
     connection_id: String,
     url: String,
     _headers: HashMap<String, String>,
+    workspace_id: Option<String>,
 ) -> Result<String, String> {
-    let (ws_stream, _response) = connect_async(&url)
-        .await
-        .map_err(|e| format!("Failed to connect: {}", e))?;
+    let storage = app.state::<Arc<Storage>>();
+    let settings = http_client::workspace_settings(&storage, workspace_id.as_deref());
+    let target = Url::parse(&url).map_err(|e| format!("Invalid URL: {}", e))?;
+
+    // Same certificate verification, CA roots and client certificate as HTTP requests
+    let connector = if target.scheme() == "wss" {
+        let identity = settings.tls.identity_for(&target).await?;
+        let connector = tls::native_tls_connector(settings.accept_invalid_certs, &settings.tls, identity.as_deref())?;
+        Some(Connector::NativeTls(connector))
+    } else {
+        None
+    };
+
+    let (ws_stream, _response) = match settings.proxy.proxy_for(&target) {
+        Some(proxy_url) => {
+            let stream = proxy::connect(&target, Some(&proxy_url), settings.connect_timeout_ms).await?;
+            client_async_tls_with_config(&url, stream, None, connector).await
+        }
+        None => connect_async_tls_with_config(&url, None, false, connector).await,
+    }
+    .map_err(|e| format!("Failed to connect: {}", e))?;
 
     let (write, mut read) = ws_stream.split();
     let write = Arc::new(Mutex::new(write));
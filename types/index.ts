@@ -10,7 +10,8 @@ export interface Workspace {
 // Protocol Types
 export type ProtocolType = 'http' | 'websocket' | 'graphql' | 'grpc' | 'mqtt' | 'unix-socket' | 'mcp' | 'sse'
 
-export type HttpMethod = 'GET' | 'POST' | 'PUT' | 'PATCH' | 'DELETE' | 'HEAD' | 'OPTIONS'
+// Any valid method token is accepted (WebDAV methods, PURGE, custom verbs)
+export type HttpMethod = 'GET' | 'POST' | 'PUT' | 'PATCH' | 'DELETE' | 'HEAD' | 'OPTIONS' | (string & {})
 
 export interface KeyValue {
   id: string
@@ -59,6 +60,14 @@ export interface HttpRequest {
   preRequestScript?: string
   /** Post-request script (JavaScript) */
   postRequestScript?: string
+  /** Measure DNS, connect and TLS on a dedicated connection */
+  detailedTiming?: boolean
+  /** Redirect handling (follows up to 10 redirects by default) */
+  redirects?: RedirectOptions
+  /** Stream the response body into this file instead of returning it */
+  saveTo?: string
+  /** Resend on connection errors and retryable statuses */
+  retry?: RetryPolicy
   /** Order for test execution (lower numbers run first) */
   testOrder?: number
   /** Test configuration: assertions and variable extractions */
@@ -72,11 +81,60 @@ export interface HttpResponse {
   status: number
   statusText: string
   headers: Record<string, string>
+  /** Body decoded as text (empty for binary bodies and saved downloads) */
   body: string
+  /** Raw body, set when it is binary or did not decode cleanly as text */
+  bodyBase64?: string | null
+  contentType?: string | null
+  /** Charset the body was decoded with */
+  charset?: string | null
+  isBinary?: boolean
+  /** File the body was written to instead of being returned */
+  savedTo?: string | null
   time: number
   size: number
+  timing?: ResponseTiming
+  /** Redirects followed before this response, in order */
+  redirects?: RedirectHop[]
+  /** Every attempt when a retry policy applies */
+  attempts?: RetryAttempt[]
 }
 
+export interface RedirectOptions {
+  follow: boolean
+  maxRedirects: number
+  /** Resend POST as POST on 301/302 (browsers and curl switch to GET) */
+  keepMethod: boolean
+}
+
+export interface RedirectHop {
+  method: string
+  url: string
+  status: number
+  statusText: string
+  location: string | null
+  headers: Record<string, string>
+  time: number
+}
+
+/** Phase timing breakdown, all durations in milliseconds */
+export interface ResponseTiming {
+  /** Only measured with detailed timing */
+  dnsMs: number | null
+  connectMs: number | null
+  tlsMs: number | null
+  ttfbMs: number
+  downloadMs: number
+  totalMs: number
+  remoteIp: string | null
+  httpVersion: string
+  /** Only known for dedicated connections (detailed timing); null for pooled requests */
+  tlsVersion: string | null
+  tlsCipher: string | null
+}
+
+export type TimingPhase = 'dns' | 'connect' | 'tls' | 'ttfb' | 'download' | 'total'
+
 // ============ WebSocket ============
 export interface WebSocketRequest {
   id: string
@@ -114,6 +172,7 @@ export interface GraphQLRequest {
   query: string
   variables: string
   operationName: string
+  auth?: AuthConfig
   /** Order for test execution (lower numbers run first) */
   testOrder?: number
 }
@@ -205,6 +264,7 @@ export interface SseRequest {
   url: string
   headers: KeyValue[]
   withCredentials: boolean
+  auth?: AuthConfig
   /** Order for test execution (lower numbers run first) */
   testOrder?: number
 }
@@ -380,7 +440,7 @@ export type RequestType =
 export type ResponseType = HttpResponse | GraphQLResponse | GrpcResponse | McpResponse
 
 // ============ Auth Configuration ============
-export type AuthType = 'none' | 'inherit' | 'basic' | 'bearer' | 'api-key' | 'oauth2'
+export type AuthType = 'none' | 'inherit' | 'basic' | 'bearer' | 'api-key' | 'oauth2' | 'aws-sigv4' | 'digest' | 'hawk' | 'jwt'
 
 export interface OAuth2Config {
   grantType: 'authorization_code' | 'client_credentials' | 'password' | 'implicit'
@@ -402,7 +462,7 @@ export interface AuthConfig {
   type: AuthType
   enabled: boolean
   enabledWhen?: string // Conditional expression: "{{ENV}} == 'production'"
-  // Basic Auth
+  // Basic and Digest Auth
   username?: string
   password?: string
   // Bearer Token
@@ -414,6 +474,107 @@ export interface AuthConfig {
   apiKeyIn?: 'header' | 'query'
   // OAuth2
   oauth2?: OAuth2Config
+  /** Auth profile whose token the backend obtains, caches and refreshes */
+  authProfileId?: string
+  // AWS Signature V4 (signed by the backend when the request is sent)
+  awsSigV4?: AwsSigV4Config
+  // Hawk
+  hawk?: HawkConfig
+  // JWT signed for every request
+  jwt?: JwtConfig
+}
+
+export interface AwsSigV4Config {
+  accessKeyId: string
+  secretAccessKey: string
+  /** Temporary credentials (sent as X-Amz-Security-Token) */
+  sessionToken?: string
+  region: string
+  /** Signing name of the service, e.g. execute-api or s3 */
+  service: string
+  /** Sign UNSIGNED-PAYLOAD instead of the body hash (S3 and compatible stores) */
+  unsignedPayload?: boolean
+}
+
+export interface HawkConfig {
+  id: string
+  key: string
+  algorithm?: 'sha256' | 'sha1'
+  /** Application-specific data sent with the request */
+  ext?: string
+  app?: string
+  dlg?: string
+  /** Also authenticate the body with a payload hash */
+  includePayloadHash?: boolean
+}
+
+export type JwtAlgorithm = 'HS256' | 'HS384' | 'HS512' | 'RS256' | 'RS384' | 'RS512' | 'ES256' | 'ES384'
+
+export interface JwtConfig {
+  algorithm: JwtAlgorithm
+  /** HMAC secret (HS*) or PEM private key (RS*, ES*) */
+  key: string
+  keyBase64?: boolean
+  /** Claims as JSON; iat, exp and jti are added unless present */
+  claims: string
+  /** Additional header fields as JSON, e.g. {"kid": "..."} */
+  header?: string
+  /** Token lifetime in seconds (default 300) */
+  expiresIn?: number
+  prefix?: string
+}
+
+/** Authentication the backend applies to the final request */
+export type RequestAuth =
+  | ({ type: 'aws-sigv4' } & AwsSigV4Config)
+  | { type: 'digest', username: string, password: string }
+  | ({ type: 'hawk' } & HawkConfig)
+  | { type: 'jwt', algorithm: JwtAlgorithm, key: string, keyBase64: boolean, claims: Record<string, any>, header: Record<string, any>, expiresIn?: number, prefix?: string }
+
+// ============ OAuth 2.0 Auth Profiles ============
+export type OAuthGrantType = 'client_credentials' | 'password' | 'authorization_code' | 'device_code'
+
+export interface AuthProfile {
+  id: string
+  name: string
+  grantType: OAuthGrantType
+  tokenUrl: string
+  /** Authorization endpoint (authorization code grant) */
+  authorizationUrl?: string
+  /** Device authorization endpoint (device code grant) */
+  deviceAuthorizationUrl?: string
+  clientId: string
+  /** Stored in the git-ignored auth.local.yaml */
+  clientSecret?: string
+  clientAuthentication?: 'basic_header' | 'request_body'
+  scope?: string
+  audience?: string
+  username?: string
+  /** Stored in the git-ignored auth.local.yaml */
+  password?: string
+  /** Port of the loopback redirect listener (http://127.0.0.1:{port}/callback), 0 picks a free port */
+  redirectPort?: number
+  pkce?: boolean
+  extraParams?: Record<string, string>
+}
+
+export interface OAuthToken {
+  profileId: string
+  environmentId?: string
+  accessToken: string
+  tokenType: string
+  refreshToken?: string
+  /** Unix timestamp in seconds */
+  expiresAt?: number
+  scope?: string
+}
+
+/** Payload of the `oauth-authorization` event */
+export interface AuthorizationPrompt {
+  profileId: string
+  url: string
+  /** Code to enter on the verification page (device code grant) */
+  userCode?: string
 }
 
 // ============ Folder/Collection Settings ============
@@ -445,10 +606,66 @@ export interface Collection {
   createdAt: number
 }
 
+export interface Cookie {
+  name: string
+  value: string
+  domain: string
+  path: string
+  hostOnly: boolean
+  /** Unix timestamp in seconds; session cookies have none */
+  expires?: number
+  secure: boolean
+  httpOnly: boolean
+  sameSite?: string
+}
+
+export type ProxyMode = 'none' | 'environment' | 'manual'
+
+export interface ProxySettings {
+  mode: ProxyMode
+  /** http://, https://, socks5:// or socks5h:// (manual mode) */
+  url: string
+  username?: string
+  /** Stored in the git-ignored proxy.local.yaml */
+  password?: string
+  /** NO_PROXY style host patterns that are connected to directly */
+  bypass: string[]
+}
+
+export type CertificateFormat = 'pem' | 'pkcs12'
+
+export interface SecretReference {
+  providerId: string
+  secretPath: string
+  secretKey?: string
+}
+
+export interface ClientCertificate {
+  id: string
+  /** api.example.com, *.example.com or *, optionally with a :port */
+  host: string
+  enabled: boolean
+  format: CertificateFormat
+  /** PEM certificate chain or PKCS#12 (.p12/.pfx) archive */
+  certPath: string
+  /** PEM private key (defaults to certPath) */
+  keyPath?: string
+  /** Stored in the git-ignored tls.local.yaml */
+  passphrase?: string
+  passphraseSecret?: SecretReference
+}
+
+export interface TlsSettings {
+  /** PEM files with additional trusted CA certificates */
+  caCertificates: string[]
+  clientCertificates: ClientCertificate[]
+}
+
 export interface HistoryItem {
   id: string
   request: RequestType
   response: ResponseType | null
+  timing?: ResponseTiming
   timestamp: number
 }
 
@@ -531,6 +748,27 @@ export type AssertionType =
   | 'contains'         // body contains "success"
   | 'response_time'    // responseTime < 500ms
   | 'header'           // header exists or equals value
+  | 'timing'           // dns/connect/tls/ttfb/download/total < 200ms
+  | 'json_schema'      // body matches a JSON Schema
+  | 'body_size'        // body size <= 1024 bytes
+
+// Comparison applied by jsonpath, header, response_time and body_size assertions
+export type AssertionOperator =
+  | 'equals'
+  | 'not_equals'
+  | 'contains'
+  | 'exists'
+  | 'not_exists'
+  | 'matches'          // regular expression
+  | 'gt'
+  | 'gte'
+  | 'lt'
+  | 'lte'
+  | 'is_type'          // string, number, integer, boolean, array, object or null
+  | 'length_equals'    // length of an array, object or string
+  | 'length_gt'
+  | 'in'               // the array contains the expected value
+  | 'one_of'           // the value is one of a JSON array or comma-separated list
 
 export interface Assertion {
   id: string
@@ -543,15 +781,20 @@ export interface Assertion {
   maxStatus?: number
   // For jsonpath
   jsonPath?: string
-  operator?: 'equals' | 'not_equals' | 'contains' | 'exists' | 'not_exists'
+  // For jsonpath, header, response_time and body_size
+  operator?: AssertionOperator
   expectedValue?: string
   // For contains
   searchString?: string
-  // For response_time
+  // For response_time and timing
   maxTimeMs?: number
+  // For timing
+  timingPhase?: TimingPhase
   // For header
   headerName?: string
   headerValue?: string
+  // For json_schema (the request's response schema when not set)
+  jsonSchema?: Record<string, any>
 }
 
 // Variable Extraction for response chaining
@@ -575,6 +818,44 @@ export interface TestRequest {
   assertions?: Assertion[]
   // Variables to extract from response
   extractVariables?: VariableExtraction[]
+  // Measure DNS/connect/TLS on a dedicated connection
+  detailedTiming?: boolean
+  // Redirect handling (follows up to 10 redirects by default)
+  redirects?: RedirectOptions
+  // Retry policy (overrides the one of the run)
+  retry?: RetryPolicy
+  // Auth profile whose OAuth 2.0 token is sent (overrides the one of the run)
+  authProfileId?: string
+  // Authentication applied when the request is sent (e.g. AWS Signature V4)
+  auth?: RequestAuth
+  // Response schema (e.g. from an OpenAPI import) for json_schema assertions
+  responseSchema?: Record<string, any>
+}
+
+export type BackoffStrategy = 'fixed' | 'linear' | 'exponential'
+
+export interface RetryPolicy {
+  /** Retries after the first attempt (0 disables retrying) */
+  maxRetries: number
+  backoff?: BackoffStrategy
+  delayMs?: number
+  /** Upper bound for a single delay, including Retry-After */
+  maxDelayMs?: number
+  /** Response status codes that are retried (default 429, 502, 503, 504) */
+  retryOnStatus?: number[]
+  /** Retry when connecting failed or the request timed out */
+  retryOnConnectionError?: boolean
+  /** Wait as long as the Retry-After header asks */
+  respectRetryAfter?: boolean
+}
+
+export interface RetryAttempt {
+  attempt: number
+  status?: number
+  error?: string
+  time: number
+  /** How long was waited before the next attempt */
+  retryDelayMs?: number
 }
 
 export type TestStatus = 'pending' | 'running' | 'passed' | 'failed' | 'error'
@@ -601,6 +882,9 @@ export interface TestResult {
   method: string
   url: string
   status: TestStatus
+  /** Headers and body as sent (before backend-applied auth) */
+  requestHeaders?: Record<string, string>
+  requestBody?: string
   responseStatus?: number
   responseTime?: number
   responseSize?: number
@@ -608,6 +892,11 @@ export interface TestResult {
   error?: string
   assertions: AssertionResult[]
   extractedVariables?: ExtractedVariable[]
+  timing?: ResponseTiming
+  /** Every attempt when a retry policy applies */
+  attempts?: RetryAttempt[]
+  /** Iteration of a data-driven run (1-based) */
+  iteration?: number
 }
 
 export interface TestRunConfig {
@@ -616,6 +905,25 @@ export interface TestRunConfig {
   requests: TestRequest[]
   stopOnFailure: boolean
   delayBetweenRequests: number
+  /** Retry policy for requests without their own */
+  retry?: RetryPolicy
+  /** Auth profile for requests without their own */
+  authProfileId?: string
+  /** One full pass through the requests per row, seeded with its variables */
+  iterationData?: Record<string, string>[]
+  /** Requests running at once (1 runs them in order); consumers of extracted variables wait for their producer */
+  concurrency?: number
+}
+
+/** Counts of one iteration of a data-driven run */
+export interface IterationSummary {
+  iteration: number
+  data: Record<string, string>
+  total: number
+  passed: number
+  failed: number
+  errors: number
+  totalTime: number
 }
 
 export interface TestRunSummary {
@@ -627,6 +935,10 @@ export interface TestRunSummary {
   errors: number
   totalTime: number
   results: TestResult[]
+  /** The run was cancelled (cancel_request with the run id) */
+  cancelled?: boolean
+  /** Per-iteration counts of a data-driven run */
+  iterations?: IterationSummary[]
 }
 
 export interface TestProgressEvent {